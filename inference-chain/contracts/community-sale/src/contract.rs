@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    entry_point, from_json, to_json_binary, to_json_vec, BankMsg, Binary, Coin, Deps, DepsMut,
-    Env, MessageInfo, Response, StdError, StdResult, Uint128, QueryRequest, GrpcQuery,
+    entry_point, from_json, to_json_binary, to_json_vec, BankMsg, Binary, Coin, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, QueryRequest, GrpcQuery,
     ContractResult, SystemResult, WasmMsg, WasmQuery,
 };
 use prost::Message;
@@ -8,11 +8,11 @@ use cw2::{get_contract_version, set_contract_version};
 
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, Cw20ReceiveMsg, ExecuteMsg, InstantiateMsg,
+    AcceptedToken, AcceptedTokensResponse, ConfigResponse, Cw20ReceiveMsg, ExecuteMsg, InstantiateMsg,
     NativeBalanceResponse, PurchaseTokenMsg, QueryMsg, TestBridgeValidationResponse,
     TokenCalculationResponse, BlockHeightResponse, ApprovedTokensForTradeJson, ApprovedTokenJson,
 };
-use crate::state::{calculate_tokens_for_usd, Config, CONFIG};
+use crate::state::{calculate_tokens_for_usd, Config, ACCEPTED_TOKENS, CONFIG, LEGACY_CONFIG};
 
 #[derive(Clone, PartialEq, Message)]
 pub struct QueryValidateWrappedTokenForTradeRequest {
@@ -68,6 +68,13 @@ fn validate_wrapped_token_for_trade(deps: Deps, token_identifier: &str) -> Resul
         .strip_prefix("cw20:")
         .unwrap_or(token_identifier);
 
+    // Malformed addresses can never be approved, so don't spend a gRPC round-trip on them
+    if deps.api.addr_validate(contract_address).is_err() {
+        return Err(ContractError::InvalidToken {
+            token: format!("CW20 contract address {} is not a valid bech32 address", contract_address),
+        });
+    }
+
     let request = QueryValidateWrappedTokenForTradeRequest {
         contract_address: contract_address.to_string(),
     };
@@ -170,15 +177,21 @@ pub fn instantiate(
     let config = Config {
         admin: admin.clone(),
         buyer: buyer.clone(),
-        accepted_chain_id: msg.accepted_chain_id.clone(),
-        accepted_eth_contract: msg.accepted_eth_contract.to_lowercase(),
         price_usd: msg.price_usd,
         native_denom: native_denom.clone(),
         is_paused: false,
         total_tokens_sold: Uint128::zero(),
+        guardian: None,
     };
     CONFIG.save(deps.storage, &config)?;
 
+    // The initial token is treated as a 6-decimal USD stablecoin (1 unit = 1 micro-USD)
+    ACCEPTED_TOKENS.save(
+        deps.storage,
+        (msg.accepted_chain_id.as_str(), msg.accepted_eth_contract.to_lowercase().as_str()),
+        &Decimal::one(),
+    )?;
+
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("admin", admin)
@@ -200,10 +213,17 @@ pub fn execute(
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Pause {} => pause_contract(deps, info),
         ExecuteMsg::Resume {} => resume_contract(deps, info),
+        ExecuteMsg::UpdateGuardian { guardian } => update_guardian(deps, info, guardian),
         ExecuteMsg::UpdateBuyer { buyer } => update_buyer(deps, info, buyer),
         ExecuteMsg::UpdatePrice { price_usd } => update_price(deps, info, price_usd),
         ExecuteMsg::WithdrawNativeTokens { amount, recipient } => withdraw_native_tokens(deps, info, amount, recipient),
         ExecuteMsg::EmergencyWithdraw { recipient } => emergency_withdraw(deps, env, info, recipient),
+        ExecuteMsg::SetAcceptedToken { chain_id, eth_contract, usd_rate } => {
+            set_accepted_token(deps, info, chain_id, eth_contract, usd_rate)
+        }
+        ExecuteMsg::RemoveAcceptedToken { chain_id, eth_contract } => {
+            remove_accepted_token(deps, info, chain_id, eth_contract)
+        }
     }
 }
 
@@ -235,32 +255,39 @@ fn receive_cw20(
         });
     }
 
-    // Check 3: Query underlying Ethereum address and compare to expected
+    // Check 3: Query underlying Ethereum address and look up its USD rate
     let (chain_id, eth_contract) = query_bridge_info(deps.as_ref(), &cw20_contract)?;
-    if chain_id != config.accepted_chain_id || eth_contract != config.accepted_eth_contract {
-        return Err(ContractError::WrongToken {
-            expected_chain: config.accepted_chain_id.clone(),
-            expected_contract: config.accepted_eth_contract.clone(),
-            got_chain: chain_id,
-            got_contract: eth_contract,
-        });
-    }
+    let usd_rate = ACCEPTED_TOKENS
+        .may_load(deps.storage, (chain_id.as_str(), eth_contract.as_str()))?
+        .ok_or_else(|| ContractError::TokenNotAccepted {
+            token: format!("{}:{}", chain_id, eth_contract),
+        })?;
 
-    let _purchase_msg: PurchaseTokenMsg = from_json(&cw20_msg.msg)?;
+    let purchase_msg: PurchaseTokenMsg = from_json(&cw20_msg.msg)?;
     let buyer = cw20_msg.sender;
-    let usd_amount = cw20_msg.amount;
+    let cw20_amount = cw20_msg.amount;
 
-    if usd_amount.is_zero() {
+    if cw20_amount.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
 
+    let usd_amount = cw20_amount
+        .checked_mul_floor(usd_rate)
+        .map_err(|e| ContractError::Std(StdError::msg(format!("overflow: {}", e))))?;
+
     // Fixed price calculation
     let tokens_to_buy = calculate_tokens_for_usd(usd_amount, config.price_usd);
     if tokens_to_buy.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
+    if let Some(min_tokens_out) = purchase_msg.min_tokens_out {
+        if tokens_to_buy < min_tokens_out {
+            return Err(ContractError::SlippageExceeded { min_tokens_out, tokens_out: tokens_to_buy });
+        }
+    }
 
-    // Check contract balance
+    // Check contract balance before building any message, so an underfunded sale
+    // fails outright instead of forwarding the buyer's CW20
     let contract_balance = deps
         .querier
         .query_balance(env.contract.address.to_string(), &config.native_denom)?;
@@ -300,7 +327,7 @@ fn receive_cw20(
         let transfer_cw20_msg = create_cw20_transfer_msg(
             cw20_contract.clone(),
             config.admin.clone(),
-            usd_amount,
+            cw20_amount,
         )?;
         response = response.add_message(transfer_cw20_msg);
     }
@@ -308,19 +335,30 @@ fn receive_cw20(
     Ok(response
         .add_attribute("method", "purchase")
         .add_attribute("buyer", buyer)
-        .add_attribute("usdt_amount", usd_amount)
+        .add_attribute("cw20_amount", cw20_amount)
+        // Deprecated: same values as usd_received and tokens_purchased below; kept for existing
+        // consumers and to be removed once they have moved to the indexer keys
+        .add_attribute("usd_amount", usd_amount)
         .add_attribute("gnk_purchased", tokens_to_buy)
-        .add_attribute("price_usd", config.price_usd))
+        .add_attribute("price_usd", config.price_usd)
+        // Indexer fields: enough to rebuild each buyer's position from events alone
+        .add_attribute("cw20_contract", cw20_contract)
+        .add_attribute("usd_received", usd_amount)
+        .add_attribute("tokens_purchased", tokens_to_buy)
+        .add_attribute("cumulative_sold", updated_config.total_tokens_sold))
 }
 
 fn pause_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
-    if info.sender.as_str() != config.admin {
+    let is_guardian = config.guardian.as_deref() == Some(info.sender.as_str());
+    if info.sender.as_str() != config.admin && !is_guardian {
         return Err(ContractError::Unauthorized {});
     }
     config.is_paused = true;
     CONFIG.save(deps.storage, &config)?;
-    Ok(Response::new().add_attribute("method", "pause"))
+    Ok(Response::new()
+        .add_attribute("method", "pause")
+        .add_attribute("paused_by", info.sender))
 }
 
 fn resume_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
@@ -333,6 +371,20 @@ fn resume_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
     Ok(Response::new().add_attribute("method", "resume"))
 }
 
+fn update_guardian(deps: DepsMut, info: MessageInfo, guardian: Option<String>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.guardian = guardian
+        .map(|guardian| deps.api.addr_validate(&guardian).map(|addr| addr.to_string()))
+        .transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("method", "update_guardian")
+        .add_attribute("guardian", config.guardian.unwrap_or_else(|| "none".to_string())))
+}
+
 fn update_buyer(deps: DepsMut, info: MessageInfo, buyer: String) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     if info.sender.as_str() != config.admin {
@@ -354,11 +406,15 @@ fn update_price(deps: DepsMut, info: MessageInfo, price_usd: Uint128) -> Result<
     if price_usd.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
+    // Only future purchases are affected; completed ones were paid out at their own price
+    let old_price_usd = config.price_usd;
     config.price_usd = price_usd;
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new()
         .add_attribute("method", "update_price")
-        .add_attribute("price_usd", price_usd))
+        .add_attribute("price_usd", price_usd)
+        .add_attribute("old_price_usd", old_price_usd)
+        .add_attribute("new_price_usd", price_usd))
 }
 
 fn withdraw_native_tokens(
@@ -421,14 +477,61 @@ fn emergency_withdraw(
         .add_attribute("recipient", recipient))
 }
 
+fn set_accepted_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    chain_id: String,
+    eth_contract: String,
+    usd_rate: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if chain_id.is_empty() || eth_contract.is_empty() {
+        return Err(ContractError::Std(StdError::msg("chain_id and eth_contract required")));
+    }
+    if usd_rate.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let eth_contract = eth_contract.to_lowercase();
+    ACCEPTED_TOKENS.save(deps.storage, (chain_id.as_str(), eth_contract.as_str()), &usd_rate)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_accepted_token")
+        .add_attribute("chain_id", chain_id)
+        .add_attribute("eth_contract", eth_contract)
+        .add_attribute("usd_rate", usd_rate.to_string()))
+}
+
+fn remove_accepted_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    chain_id: String,
+    eth_contract: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let eth_contract = eth_contract.to_lowercase();
+    ACCEPTED_TOKENS.remove(deps.storage, (chain_id.as_str(), eth_contract.as_str()));
+    Ok(Response::new()
+        .add_attribute("method", "remove_accepted_token")
+        .add_attribute("chain_id", chain_id)
+        .add_attribute("eth_contract", eth_contract))
+}
+
 #[entry_point]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Paused {} => to_json_binary(&query_paused(deps)?),
         QueryMsg::NativeBalance {} => to_json_binary(&query_native_balance(deps, env)?),
+        QueryMsg::AcceptedTokens {} => to_json_binary(&query_accepted_tokens(deps)?),
         QueryMsg::CalculateTokens { usd_amount } => to_json_binary(&query_calculate_tokens(deps, usd_amount)?),
         QueryMsg::TestBridgeValidation { cw20_contract } => to_json_binary(&query_test_bridge_validation(deps, cw20_contract)?),
         QueryMsg::BlockHeight {} => to_json_binary(&query_block_height(env)?),
+        QueryMsg::ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
         QueryMsg::TestApprovedTokens {} => to_json_binary(&query_test_approved_tokens(deps)?),
     }
 }
@@ -439,6 +542,19 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: Binary) -> Result<Response, Contr
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
+
+    // Convert configs stored before multiple accepted tokens (only they carry the accepted_* fields);
+    // the single token they accepted keeps being taken as a 6-decimal USD stablecoin, as
+    // instantiate sets it up. Resaving also drops the obsolete fields.
+    if let Ok(legacy) = LEGACY_CONFIG.load(deps.storage) {
+        ACCEPTED_TOKENS.save(
+            deps.storage,
+            (legacy.accepted_chain_id.as_str(), legacy.accepted_eth_contract.to_lowercase().as_str()),
+            &Decimal::one(),
+        )?;
+        CONFIG.save(deps.storage, &legacy.into())?;
+    }
+
     Ok(Response::new()
         .add_attribute("action", "migrate")
         .add_attribute("from_version", old.version)
@@ -450,15 +566,18 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     Ok(ConfigResponse {
         admin: config.admin,
         buyer: config.buyer,
-        accepted_chain_id: config.accepted_chain_id,
-        accepted_eth_contract: config.accepted_eth_contract,
         price_usd: config.price_usd,
         native_denom: config.native_denom,
         is_paused: config.is_paused,
         total_tokens_sold: config.total_tokens_sold,
+        guardian: config.guardian,
     })
 }
 
+fn query_paused(deps: Deps) -> StdResult<bool> {
+    Ok(CONFIG.load(deps.storage)?.is_paused)
+}
+
 fn query_native_balance(deps: Deps, env: Env) -> StdResult<NativeBalanceResponse> {
     let config = CONFIG.load(deps.storage)?;
     let balance = deps
@@ -467,6 +586,20 @@ fn query_native_balance(deps: Deps, env: Env) -> StdResult<NativeBalanceResponse
     Ok(NativeBalanceResponse { balance })
 }
 
+fn query_accepted_tokens(deps: Deps) -> StdResult<AcceptedTokensResponse> {
+    let tokens = ACCEPTED_TOKENS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|((chain_id, eth_contract), usd_rate)| AcceptedToken {
+                chain_id,
+                eth_contract,
+                usd_rate,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AcceptedTokensResponse { tokens })
+}
+
 fn query_calculate_tokens(deps: Deps, usd_amount: Uint128) -> StdResult<TokenCalculationResponse> {
     let config = CONFIG.load(deps.storage)?;
     let tokens = calculate_tokens_for_usd(usd_amount, config.price_usd);
@@ -512,7 +645,8 @@ fn query_grpc(deps: Deps, path: &str, data: Binary) -> StdResult<Binary> {
         path: path.to_string(),
         data,
     });
-    query_raw(deps, &request)
+    // Name the path so it's clear which of several gRPC queries failed
+    query_raw(deps, &request).map_err(|e| StdError::msg(format!("{}: {}", path, e)))
 }
 
 fn query_raw(deps: Deps, request: &QueryRequest<GrpcQuery>) -> StdResult<Binary> {
@@ -530,23 +664,82 @@ where
     TResponse: prost::Message + Default,
 {
     let mut buf = Vec::new();
-    request.encode(&mut buf).map_err(|e| StdError::msg(format!("Encode: {}", e)))?;
+    request.encode(&mut buf).map_err(|e| StdError::msg(format!("Encode request for {}: {}", path, e)))?;
     let bytes = query_grpc(deps, path, Binary::from(buf))?;
-    TResponse::decode(bytes.as_slice()).map_err(|e| StdError::msg(format!("Decode: {}", e)))
+    TResponse::decode(bytes.as_slice()).map_err(|e| StdError::msg(format!("Decode response from {}: {}", path, e)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi};
-    use cosmwasm_std::{from_json, Addr, MessageInfo};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{
+        coins, from_json, Addr, CosmosMsg, Empty, MessageInfo, OwnedDeps, Querier, QuerierResult,
+        SystemError,
+    };
+    use std::marker::PhantomData;
+
+    const USDT: &str = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    /// Answers the chain's bridge-validation gRPC; everything else goes to the base mock querier
+    struct SaleQuerier {
+        base: MockQuerier,
+    }
+
+    impl Querier for SaleQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = match from_json(bin_request) {
+                Ok(r) => r,
+                Err(e) => {
+                    return SystemResult::Err(SystemError::InvalidRequest {
+                        error: e.to_string(),
+                        request: bin_request.into(),
+                    })
+                }
+            };
+            match request {
+                QueryRequest::Grpc(GrpcQuery { path, .. })
+                    if path == "/inference.inference.Query/ValidateWrappedTokenForTrade" =>
+                {
+                    let mut buf = Vec::new();
+                    QueryValidateWrappedTokenForTradeResponse { is_valid: true }
+                        .encode(&mut buf)
+                        .unwrap();
+                    SystemResult::Ok(ContractResult::Ok(Binary::from(buf)))
+                }
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    /// Dependencies where every CW20 reports the given bridge origin and the sale holds `balance` GNK
+    fn mock_deps_with_bridge(chain_id: &str, eth_contract: &str, balance: u128) -> OwnedDeps<MockStorage, MockApi, SaleQuerier> {
+        let mut base = MockQuerier::new(&[(mock_env().contract.address.as_str(), &coins(balance, "ngonka"))]);
+        let bridge_info = format!(r#"{{"chain_id":"{}","contract_address":"{}"}}"#, chain_id, eth_contract);
+        base.update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(Binary::from(bridge_info.as_bytes()))));
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: SaleQuerier { base },
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn purchase_msg(buyer: &Addr, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&PurchaseTokenMsg::default()).unwrap(),
+        })
+    }
 
     fn mock_instantiate_msg(api: &MockApi) -> InstantiateMsg {
         InstantiateMsg {
             admin: api.addr_make("admin").to_string(),
             buyer: api.addr_make("buyer").to_string(),
             accepted_chain_id: "ethereum".to_string(),
-            accepted_eth_contract: "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+            accepted_eth_contract: USDT.to_string(),
             price_usd: Uint128::from(25000u128), // $0.025
         }
     }
@@ -564,19 +757,23 @@ mod tests {
             funds: vec![],
         };
 
-        let res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg(&api)).unwrap();
+        let res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
         assert!(res.attributes.iter().any(|a| a.key == "buyer" && a.value == buyer_addr));
         assert!(res.attributes.iter().any(|a| a.key == "accepted_chain_id" && a.value == "ethereum"));
         assert!(res.attributes.iter().any(|a| a.key == "accepted_eth_contract" && a.value == "0xdac17f958d2ee523a2206206994597c13d831ec7"));
+
+        let version: cw2::ContractVersion =
+            from_json(query(deps.as_ref(), env, QueryMsg::ContractVersion {}).unwrap()).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
     }
 
     #[test]
     fn test_pause_resume() {
-        let deps = mock_dependencies();
         let api = MockApi::default();
         let admin_addr = api.addr_make("admin");
-        
-        let mut deps = deps;
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000_000);
         let env = mock_env();
         let info = MessageInfo {
             sender: Addr::unchecked("creator"),
@@ -584,6 +781,13 @@ mod tests {
         };
         instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
 
+        let outsider = MessageInfo {
+            sender: api.addr_make("outsider"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, ExecuteMsg::Pause {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
         let info = MessageInfo {
             sender: admin_addr.clone(),
             funds: vec![],
@@ -591,13 +795,84 @@ mod tests {
         execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Pause {}).unwrap();
 
         let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
         assert!(config.is_paused);
+        let paused: bool = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert!(paused);
+
+        let cw20_info = MessageInfo {
+            sender: api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        let outsider = MessageInfo {
+            sender: api.addr_make("outsider"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, ExecuteMsg::Resume {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
 
         execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Resume {}).unwrap();
         let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(!config.is_paused);
+        let paused: bool = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert!(!paused);
+
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_guardian_can_only_pause() {
+        let api = MockApi::default();
+        let admin_addr = api.addr_make("admin");
+        let guardian = api.addr_make("guardian");
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        let admin = MessageInfo {
+            sender: admin_addr.clone(),
+            funds: vec![],
+        };
+        let guardian_info = MessageInfo {
+            sender: guardian.clone(),
+            funds: vec![],
+        };
+        let set_guardian = ExecuteMsg::UpdateGuardian { guardian: Some(guardian.to_string()) };
+        let err = execute(deps.as_mut(), env.clone(), guardian_info.clone(), set_guardian.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), admin.clone(), set_guardian).unwrap();
+
+        execute(deps.as_mut(), env.clone(), guardian_info.clone(), ExecuteMsg::Pause {}).unwrap();
+        let paused: bool = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert!(paused);
+
+        let err = execute(deps.as_mut(), env.clone(), guardian_info.clone(), ExecuteMsg::Resume {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let withdraw = ExecuteMsg::WithdrawNativeTokens { amount: Uint128::new(1_000), recipient: guardian.to_string() };
+        let err = execute(deps.as_mut(), env.clone(), guardian_info.clone(), withdraw).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            guardian_info,
+            ExecuteMsg::EmergencyWithdraw { recipient: guardian.to_string() },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // Resuming stays with the admin
+        execute(deps.as_mut(), env.clone(), admin, ExecuteMsg::Resume {}).unwrap();
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
         assert!(!config.is_paused);
+        assert_eq!(config.guardian, Some(guardian.to_string()));
     }
 
     #[test]
@@ -628,17 +903,16 @@ mod tests {
         .unwrap();
 
         let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+            from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
         assert_eq!(config.buyer, new_buyer);
     }
 
     #[test]
     fn test_update_price() {
-        let deps = mock_dependencies();
         let api = MockApi::default();
         let admin_addr = api.addr_make("admin");
-        
-        let mut deps = deps;
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000_000);
         let env = mock_env();
         let info = MessageInfo {
             sender: Addr::unchecked("creator"),
@@ -646,21 +920,98 @@ mod tests {
         };
         instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
 
+        let cw20_info = MessageInfo {
+            sender: api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // $100 at $0.025 -> 4000 GNK
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
         let info = MessageInfo {
             sender: admin_addr,
             funds: vec![],
         };
-        execute(
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::UpdatePrice { price_usd: Uint128::zero() },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+
+        let res = execute(
             deps.as_mut(),
             env.clone(),
             info,
             ExecuteMsg::UpdatePrice { price_usd: Uint128::from(50000u128) },
         )
         .unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "old_price_usd" && a.value == "25000"));
+        assert!(res.attributes.iter().any(|a| a.key == "new_price_usd" && a.value == "50000"));
 
         let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
         assert_eq!(config.price_usd, Uint128::from(50000u128));
+        // The earlier purchase keeps the amount it was priced at
+        assert_eq!(config.total_tokens_sold, Uint128::from(4_000_000_000_000u128));
+
+        // $100 at $0.05 -> 2000 GNK
+        let res = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_purchased" && a.value == "2000000000000"));
+        assert!(res.attributes.iter().any(|a| a.key == "cumulative_sold" && a.value == "6000000000000"));
+    }
+
+    #[test]
+    fn test_min_tokens_out() {
+        let api = MockApi::default();
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let purchase = |min_tokens_out: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: buyer.to_string(),
+                amount: Uint128::from(100_000_000u128),
+                msg: to_json_binary(&PurchaseTokenMsg { min_tokens_out: Some(Uint128::from(min_tokens_out)) }).unwrap(),
+            })
+        };
+
+        // $100 at $0.025 -> exactly 4000 GNK meets the minimum
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase(4_000_000_000_000)).unwrap();
+
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase(4_000_000_000_001)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::SlippageExceeded { min_tokens_out, tokens_out }
+                if min_tokens_out == Uint128::from(4_000_000_000_001u128) && tokens_out == Uint128::from(4_000_000_000_000u128)
+        ));
+
+        // A misspelled field fails instead of buying without the minimum
+        let typo = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(100_000_000u128),
+            msg: Binary::from(br#"{"min_token_out":"1"}"#.as_slice()),
+        });
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), typo).unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{}", err);
+
+        // Clients that predate min_tokens_out send a bare {}
+        let legacy = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(100_000_000u128),
+            msg: Binary::from(b"{}".as_slice()),
+        });
+        execute(deps.as_mut(), env, cw20_info, legacy).unwrap();
     }
 
     #[test]
@@ -678,7 +1029,7 @@ mod tests {
 
         let usd_amount = Uint128::from(100_000_000u128); // $100
         let response: TokenCalculationResponse = from_json(
-            &query(deps.as_ref(), env, QueryMsg::CalculateTokens { usd_amount }).unwrap(),
+            query(deps.as_ref(), env, QueryMsg::CalculateTokens { usd_amount }).unwrap(),
         )
         .unwrap();
 
@@ -714,4 +1065,215 @@ mod tests {
         .unwrap_err();
         assert!(matches!(err, ContractError::Unauthorized {}));
     }
+
+    #[test]
+    fn test_purchase_with_additional_accepted_token() {
+        let api = MockApi::default();
+        let admin_addr = api.addr_make("admin");
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDC, 1_000_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        // Accept W(USDC) at 2 micro-USD per unit to exercise the rate conversion
+        let info = MessageInfo {
+            sender: admin_addr,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::SetAcceptedToken {
+                chain_id: "ethereum".to_string(),
+                eth_contract: USDC.to_uppercase(),
+                usd_rate: Decimal::from_atomics(2u128, 0).unwrap(),
+            },
+        )
+        .unwrap();
+
+        let tokens: AcceptedTokensResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::AcceptedTokens {}).unwrap()).unwrap();
+        assert_eq!(tokens.tokens.len(), 2);
+
+        let info = MessageInfo {
+            sender: api.addr_make("wusdc"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env, info, purchase_msg(&buyer, 50_000_000)).unwrap();
+
+        // 50 units * 2 = $100 -> 4000 GNK at $0.025
+        assert_eq!(res.messages.len(), 2);
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                assert_eq!(to_address, buyer.as_str());
+                assert_eq!(amount, &coins(4_000_000_000_000, "ngonka"));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(res.attributes.iter().any(|a| a.key == "usd_amount" && a.value == "100000000"));
+
+        let attr = |key: &str| res.attributes.iter().find(|a| a.key == key).map(|a| a.value.clone());
+        assert_eq!(attr("buyer"), Some(buyer.to_string()));
+        assert_eq!(attr("cw20_contract"), Some(api.addr_make("wusdc").to_string()));
+        assert_eq!(attr("usd_received"), Some("100000000".to_string()));
+        assert_eq!(attr("tokens_purchased"), Some("4000000000000".to_string()));
+        assert_eq!(attr("price_usd"), Some("25000".to_string()));
+        assert_eq!(attr("cumulative_sold"), Some("4000000000000".to_string()));
+    }
+
+    #[test]
+    fn test_purchase_with_18_decimal_token() {
+        const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+        let api = MockApi::default();
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", WETH, 1_000_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        // $2,000 per token of 18 decimals is 2e9 micro-USD per 1e18 wei: 2e-9 per base unit
+        let info = MessageInfo {
+            sender: api.addr_make("admin"),
+            funds: vec![],
+        };
+        let rate = Decimal::from_atomics(2u128, 9).unwrap();
+        let msg = ExecuteMsg::SetAcceptedToken { chain_id: "ethereum".to_string(), eth_contract: WETH.to_string(), usd_rate: rate };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let tokens: AcceptedTokensResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::AcceptedTokens {}).unwrap()).unwrap();
+        assert!(tokens.tokens.iter().any(|t| t.eth_contract == WETH && t.usd_rate == rate));
+
+        // 0.05 WETH = $100 -> 4000 GNK at $0.025
+        let info = MessageInfo {
+            sender: api.addr_make("weth"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env, info, purchase_msg(&buyer, 50_000_000_000_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "usd_received" && a.value == "100000000"));
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_purchased" && a.value == "4000000000000"));
+    }
+
+    #[test]
+    fn test_accepted_token_rates_stored_as_integers_still_load() {
+        let mut deps = mock_dependencies();
+        let legacy_rates: cw_storage_plus::Map<(&str, &str), Uint128> = cw_storage_plus::Map::new("accepted_tokens");
+        legacy_rates.save(deps.as_mut().storage, ("ethereum", USDC), &Uint128::from(2u128)).unwrap();
+
+        let res: AcceptedTokensResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::AcceptedTokens {}).unwrap()).unwrap();
+        assert_eq!(res.tokens[0].usd_rate, Decimal::from_atomics(2u128, 0).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_token_not_accepted() {
+        let api = MockApi::default();
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDC, 1_000_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        let info = MessageInfo {
+            sender: api.addr_make("wusdc"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env, info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert_eq!(err.to_string(), format!("Token not accepted: ethereum:{}", USDC));
+    }
+
+    #[test]
+    fn test_migrate_converts_single_token_config() {
+        use crate::state::LegacyConfig;
+
+        let api = MockApi::default();
+        let buyer = api.addr_make("buyer");
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000_000);
+        let legacy = LegacyConfig {
+            admin: api.addr_make("admin").to_string(),
+            buyer: buyer.to_string(),
+            accepted_chain_id: "ethereum".to_string(),
+            accepted_eth_contract: USDT.to_uppercase(),
+            price_usd: Uint128::from(25000u128),
+            native_denom: "ngonka".to_string(),
+            is_paused: false,
+            total_tokens_sold: Uint128::from(5u128),
+        };
+        LEGACY_CONFIG.save(deps.as_mut().storage, &legacy).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        migrate(deps.as_mut(), mock_env(), Binary::default()).unwrap();
+
+        let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.total_tokens_sold, Uint128::from(5u128));
+        assert_eq!(config.guardian, None);
+        let res: AcceptedTokensResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::AcceptedTokens {}).unwrap()).unwrap();
+        assert_eq!(res.tokens.len(), 1);
+        assert_eq!(res.tokens[0].eth_contract, USDT);
+        assert_eq!(res.tokens[0].usd_rate, Decimal::one());
+
+        // The previously accepted token still buys at the stablecoin rate
+        let info = MessageInfo {
+            sender: api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_purchased" && a.value == "4000000000000"));
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(ContractError::Std(StdError::msg("x")).code(), 0);
+        assert_eq!(ContractError::Unauthorized {}.code(), 1);
+        assert_eq!(ContractError::ContractPaused {}.code(), 2);
+        assert_eq!(ContractError::TokenNotAccepted { token: "x".to_string() }.code(), 9);
+        assert_eq!(ContractError::BuyerNotAllowed { buyer: "x".to_string() }.code(), 101);
+        assert_eq!(
+            ContractError::SlippageExceeded { min_tokens_out: Uint128::one(), tokens_out: Uint128::zero() }.code(),
+            102
+        );
+    }
+
+    #[test]
+    fn test_underfunded_sale_forwards_nothing() {
+        let api = MockApi::default();
+        let buyer = api.addr_make("buyer");
+        // Holds 1000 GNK; a $100 purchase needs 4000
+        let mut deps = mock_deps_with_bridge("ethereum", USDT, 1_000_000_000_000);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(&api)).unwrap();
+
+        let info = MessageInfo {
+            sender: api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        match err {
+            ContractError::InsufficientBalance { available, needed } => {
+                assert_eq!(available, 1_000_000_000_000);
+                assert_eq!(needed, 4_000_000_000_000);
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // The failed purchase left no trace: nothing sold, so no CW20 was kept or forwarded
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.total_tokens_sold, Uint128::zero());
+    }
 }
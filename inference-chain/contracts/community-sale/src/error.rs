@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -27,11 +27,27 @@ pub enum ContractError {
     #[error("Buyer not allowed: {buyer}")]
     BuyerNotAllowed { buyer: String },
 
-    #[error("Wrong token: expected {expected_chain}:{expected_contract}, got {got_chain}:{got_contract}")]
-    WrongToken {
-        expected_chain: String,
-        expected_contract: String,
-        got_chain: String,
-        got_contract: String,
-    },
+    #[error("Slippage exceeded: {tokens_out} tokens out, below the minimum {min_tokens_out}")]
+    SlippageExceeded { min_tokens_out: Uint128, tokens_out: Uint128 },
+}
+
+impl ContractError {
+    /// Stable numeric code for clients that need to branch on the failure without parsing the
+    /// message text. Codes are shared with the liquidity-pool contract for equivalent errors and
+    /// are never reassigned; `0` covers wrapped `StdError`s. Failed executions discard their
+    /// events, so the code cannot be attached as an attribute; this match is the canonical mapping.
+    pub fn code(&self) -> u32 {
+        match self {
+            ContractError::Std(_) => 0,
+            ContractError::Unauthorized {} => 1,
+            ContractError::ContractPaused {} => 2,
+            ContractError::InvalidToken { .. } => 4,
+            ContractError::ZeroAmount {} => 6,
+            ContractError::InsufficientBalance { .. } => 7,
+            ContractError::TokenNotAccepted { .. } => 9,
+            // Community-sale specific codes start at 101
+            ContractError::BuyerNotAllowed { .. } => 101,
+            ContractError::SlippageExceeded { .. } => 102,
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Coin, Uint128};
+use cosmwasm_std::{Binary, Coin, Decimal, Uint128};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -7,9 +7,9 @@ pub struct InstantiateMsg {
     pub admin: String,
     /// Designated buyer address (only address allowed to purchase)
     pub buyer: String,
-    /// Accepted chain ID (e.g., "ethereum")
+    /// Initially accepted chain ID (e.g., "ethereum"), accepted at a 1:1 USD rate
     pub accepted_chain_id: String,
-    /// Accepted contract address on external chain (e.g., "0xdac17f958d2ee523a2206206994597c13d831ec7" for USDT)
+    /// Initially accepted contract address on external chain (e.g., "0xdac17f958d2ee523a2206206994597c13d831ec7" for USDT)
     pub accepted_eth_contract: String,
     /// Fixed price per 1 GNK in micro-USD (6 decimals, e.g., 25000 = $0.025/GNK)
     pub price_usd: Uint128,
@@ -19,10 +19,12 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Receive CW20 wrapped bridge tokens to purchase native tokens
     Receive(Cw20ReceiveMsg),
-    /// Admin: Pause the contract
+    /// Admin or guardian: Pause the contract
     Pause {},
     /// Admin: Resume the contract
     Resume {},
+    /// Admin: Set or clear the guardian, who may pause but not resume or withdraw
+    UpdateGuardian { guardian: Option<String> },
     /// Admin: Update buyer address
     UpdateBuyer { buyer: String },
     /// Admin: Update fixed price
@@ -31,6 +33,14 @@ pub enum ExecuteMsg {
     WithdrawNativeTokens { amount: Uint128, recipient: String },
     /// Admin: Emergency withdraw all funds
     EmergencyWithdraw { recipient: String },
+    /// Admin: Accept a bridged token (or update its rate)
+    SetAcceptedToken {
+        chain_id: String,
+        eth_contract: String,
+        usd_rate: Decimal, // micro-USD per base unit, e.g. "0.000000000001" for an 18-decimal $1 token
+    },
+    /// Admin: Stop accepting a bridged token
+    RemoveAcceptedToken { chain_id: String, eth_contract: String },
 }
 
 #[cw_serde]
@@ -40,8 +50,20 @@ pub struct Cw20ReceiveMsg {
     pub msg: Binary,
 }
 
+/// Receive-hook payload for a purchase. Forward-compatibility contract for integrators: every
+/// field is optional with a serde default, so the empty `{}` keeps deserializing as fields are
+/// added. Unknown fields are rejected, so a client must not send a field before the deployed
+/// contract knows it.
 #[cw_serde]
-pub struct PurchaseTokenMsg {}
+#[derive(Default)]
+#[serde(deny_unknown_fields)]
+#[schemaifier(mute_warnings)]
+pub struct PurchaseTokenMsg {
+    /// Reject the purchase if it would deliver fewer tokens, e.g. after a price update
+    /// lands between signing and execution
+    #[serde(default)]
+    pub min_tokens_out: Option<Uint128>,
+}
 
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -49,9 +71,15 @@ pub enum QueryMsg {
     /// Get contract configuration
     #[returns(ConfigResponse)]
     Config {},
+    /// Whether purchases are paused; a cheap alternative to Config for health checks
+    #[returns(bool)]
+    Paused {},
     /// Get contract's native token balance
     #[returns(NativeBalanceResponse)]
     NativeBalance {},
+    /// List accepted bridged tokens and their USD rates
+    #[returns(AcceptedTokensResponse)]
+    AcceptedTokens {},
     /// Calculate how many tokens can be bought with given USD amount
     #[returns(TokenCalculationResponse)]
     CalculateTokens { usd_amount: Uint128 },
@@ -61,6 +89,9 @@ pub enum QueryMsg {
     /// Return the current block height
     #[returns(BlockHeightResponse)]
     BlockHeight {},
+    /// Get the contract name and version recorded by cw2
+    #[returns(cw2::ContractVersion)]
+    ContractVersion {},
     /// Test gRPC call to fetch approved tokens for trade
     #[returns(ApprovedTokensForTradeJson)]
     TestApprovedTokens {},
@@ -70,12 +101,24 @@ pub enum QueryMsg {
 pub struct ConfigResponse {
     pub admin: String,
     pub buyer: String,
-    pub accepted_chain_id: String,
-    pub accepted_eth_contract: String,
     pub price_usd: Uint128,
     pub native_denom: String,
     pub is_paused: bool,
     pub total_tokens_sold: Uint128,
+    pub guardian: Option<String>,
+}
+
+#[cw_serde]
+pub struct AcceptedToken {
+    pub chain_id: String,
+    pub eth_contract: String,
+    /// micro-USD per base unit
+    pub usd_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct AcceptedTokensResponse {
+    pub tokens: Vec<AcceptedToken>,
 }
 
 #[cw_serde]
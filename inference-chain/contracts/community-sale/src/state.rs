@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Uint128;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Decimal, Uint128};
+use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
@@ -8,10 +8,6 @@ pub struct Config {
     pub admin: String,
     /// Designated buyer address (only address allowed to purchase)
     pub buyer: String,
-    /// Accepted chain ID (e.g., "ethereum")
-    pub accepted_chain_id: String,
-    /// Accepted contract address on external chain (e.g., "0xdac17f958d2ee523a2206206994597c13d831ec7" for USDT)
-    pub accepted_eth_contract: String,
     /// Fixed price per 1 GNK in micro-USD (6 decimals, e.g., 25000 = $0.025/GNK)
     pub price_usd: Uint128,
     /// Native token denomination
@@ -20,11 +16,50 @@ pub struct Config {
     pub is_paused: bool,
     /// Total tokens sold
     pub total_tokens_sold: Uint128,
+    /// May pause for incident response, but never resume or withdraw; none while unset
+    #[serde(default)]
+    pub guardian: Option<String>,
+}
+
+/// Config layout before multiple accepted tokens, when a single bridged token was accepted.
+/// Only read by `migrate` to convert existing state.
+#[cw_serde]
+pub struct LegacyConfig {
+    pub admin: String,
+    pub buyer: String,
+    pub accepted_chain_id: String,
+    pub accepted_eth_contract: String,
+    pub price_usd: Uint128,
+    pub native_denom: String,
+    pub is_paused: bool,
+    pub total_tokens_sold: Uint128,
+}
+
+impl From<LegacyConfig> for Config {
+    fn from(legacy: LegacyConfig) -> Self {
+        Config {
+            admin: legacy.admin,
+            buyer: legacy.buyer,
+            price_usd: legacy.price_usd,
+            native_denom: legacy.native_denom,
+            is_paused: legacy.is_paused,
+            total_tokens_sold: legacy.total_tokens_sold,
+            guardian: None,
+        }
+    }
 }
 
 /// Contract configuration
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Same storage key as CONFIG, read with the single-token layout during migration
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// Accepted bridged tokens keyed by (chain_id, lowercase external contract) -> micro-USD per base
+/// unit. A Decimal so 18-decimal tokens (1e-12 micro-USD per wei at $1) can be priced; it
+/// serializes like the Uint128 rates stored before, so those still load.
+pub const ACCEPTED_TOKENS: Map<(&str, &str), Decimal> = Map::new("accepted_tokens");
+
 /// Calculate how many tokens can be bought with given USD amount at fixed price
 pub fn calculate_tokens_for_usd(usd_amount: Uint128, price_per_token: Uint128) -> Uint128 {
     if price_per_token.is_zero() {
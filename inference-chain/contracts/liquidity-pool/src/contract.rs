@@ -1,22 +1,28 @@
 use cosmwasm_std::{
-    entry_point, from_json, to_json_binary, to_json_vec, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
-    StdError, StdResult, Uint128, QueryRequest, StakingQuery, GrpcQuery, ContractResult, SystemResult, WasmMsg,
+    entry_point, from_json, to_json_binary, to_json_vec, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Uint128, Uint256, QueryRequest, GrpcQuery, ContractResult, SystemResult, WasmMsg,
+    Reply, SubMsg, SubMsgResult,
 };
+use cosmwasm_schema::cw_serde;
 use prost::Message; // For proto encoding/decoding
 use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
 
-use crate::error::ContractError;
+use crate::error::{ContractError, GrpcErrorKind, GrpcQueryError};
 use crate::msg::{
-    ConfigResponse, Cw20ReceiveMsg, DailyStatsResponse, ExecuteMsg, InstantiateMsg,
-    NativeBalanceResponse, PricingInfoResponse, PurchaseTokenMsg, QueryMsg, 
-    TestBridgeValidationResponse, TokenCalculationResponse, BlockHeightResponse,
-    ApprovedTokensForTradeJson, ApprovedTokenJson,
+    AdminRoleResponse, ConfigResponse, Cw20Amount, Cw20ReceiveMsg, DailyStatsForDayResponse, DailyStatsResponse, ExecuteMsg, InstantiateMsg,
+    BuyerPurchased, BuyersPurchasedResponse, IsAllowlistedResponse, LastPurchaseIdResponse, PurchasesTodayResponse,
+    NativeBalanceResponse, PaymentTokenEntry, PricingInfoHumanResponse, PricingInfoResponse, PurchaseHookMsg, PurchaseTokenMsg, QueryMsg, 
+    TestBridgeValidationResponse, TierBoundariesResponse, TierBoundary, TierSale, TierSalesResponse, TokenCalculationResponse, UsdToNextTierResponse, BlockHeightResponse,
+    ApprovedTokensForTradeJson, ApprovedTokenJson, PaymentTokensResponse, ReceivedFromResponse, CanPurchaseResponse, TrustedCw20sResponse, RejectionStatsResponse, ApprovedTokenStatus, ApprovedTokensWithStatusResponse, ForwardedTotalsResponse, QuoteForCw20Response, SaleProgressResponse, SellableBalanceResponse, TwapResponse, InventoryValueUsdResponse, CostForTokensResponse, ClampedTokenCalculationResponse, TokenLimit,
 };
 use crate::state::{
-    calculate_current_price, calculate_current_tier, calculate_tokens_for_usd, calculate_multi_tier_purchase,
-    Config, DailyStats, PricingConfig,
-    CONFIG, DAILY_STATS, PRICING_CONFIG,
+    calculate_average_price, calculate_cost_for_tokens, calculate_current_price, calculate_current_tier, calculate_tokens_for_usd, calculate_multi_tier_purchase,
+    calculate_tier_remainder, calculate_usd_for_tokens, default_fallback_denom, default_native_decimals, native_scale, split_tokens_by_tier, stats_day,
+    AdminRole, BuyerTotals, Config, DailyStats, LimitBasis, PauseFlags, PricingConfig, TwapObservation, TWAP_OBSERVATIONS,
+    ADMIN_ROLES, ALLOWLIST, BUYER_DAILY_PURCHASES, BUYER_TOTALS, CONFIG, DAILY_STATS, DAILY_STATS_HISTORY, FORWARDED_TOTALS, PAYMENT_TOKENS, PRICING_CONFIG, RECEIVED_FROM, LEGACY_CONFIG, TIER_SALES, TRUSTED_CW20S, REJECTION_STATS, PAYMENT_TOKEN_DECIMALS, REJECTION_DAILY_PARTIAL,
 };
+use crate::util::{format_decimal, MAX_NATIVE_DECIMALS, MAX_EVENT_PREFIX_LEN, MAX_ROLLOVER_GRACE_SECONDS, MAX_TWAP_LOOKBACK_SECONDS, MAX_TWAP_PRUNE_PER_PURCHASE, MULTIPLIER_DECIMALS, RECEIVED_TOLERANCE_BP, USD_DECIMALS};
 
 // Proto message types for gRPC query
 #[derive(Clone, PartialEq, Message)]
@@ -53,13 +59,40 @@ pub struct EmptyRequest {}
 // Proto types for bank TotalSupply query (to get base denom)
 #[derive(Clone, PartialEq, Message)]
 pub struct QueryTotalSupplyRequest {
-    // Pagination is optional and omitted - we just need the first coin
+    #[prost(message, optional, tag = "1")]
+    pub pagination: Option<PageRequest>,
+}
+
+// cosmos.base.query.v1beta1.PageRequest
+#[derive(Clone, PartialEq, Message)]
+pub struct PageRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    #[prost(bool, tag = "4")]
+    pub count_total: bool,
+    #[prost(bool, tag = "5")]
+    pub reverse: bool,
 }
 
 #[derive(Clone, PartialEq, Message)]
 pub struct QueryTotalSupplyResponse {
     #[prost(message, repeated, tag = "1")]
     pub supply: ::prost::alloc::vec::Vec<CoinProto>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageResponse>,
+}
+
+// cosmos.base.query.v1beta1.PageResponse
+#[derive(Clone, PartialEq, Message)]
+pub struct PageResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub next_key: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -70,17 +103,55 @@ pub struct CoinProto {
     pub amount: String,
 }
 
+// Reply id of the CW20 forward to admin, which replies only on error
+const FORWARD_CW20_REPLY_ID: u64 = 1;
+
+// Reply id of an optional purchase hook, which replies only on error
+const PURCHASE_HOOK_REPLY_ID: u64 = 2;
+
+/// Carried on the forward submessage so a failure can say what was being forwarded
+#[cw_serde]
+struct ForwardCw20Payload {
+    cw20_contract: String,
+    amount: Uint128,
+}
+
 const CONTRACT_NAME: &str = "inference-liquidity-pool";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Pagination defaults for list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// Upper bound on payouts in a single WithdrawNativeBatch
+const MAX_BATCH_PAYOUTS: usize = 20;
+
+// Bank supply pages read after the first when looking for the native denom, and their size
+const MAX_SUPPLY_PAGES: usize = 10;
+const SUPPLY_PAGE_LIMIT: u64 = 100;
+
+// Upper bound on tokens in a single AddPaymentTokens, each costing a bridge-validation gRPC query
+const MAX_BATCH_PAYMENT_TOKENS: usize = 20;
+
+// CW20 payment tokens are given as "cw20:<bech32>" or a raw bech32 address; anything else is a native denom
+fn is_cw20_identifier(deps: Deps, token_identifier: &str) -> bool {
+    token_identifier.starts_with("cw20:") || deps.api.addr_validate(token_identifier).is_ok()
+}
+
 // Helper function to validate if a token is a legitimate bridge token for trading
 // Accepts either a raw CW20 address (bech32) or a value prefixed with "cw20:"
+// Native denoms are not bridge tokens, so the chain check is skipped for them
 fn validate_wrapped_token_for_trade(deps: Deps, token_identifier: &str) -> Result<bool, ContractError> {
     deps.api.debug(&format!(
         "LP: validate_wrapped_token_for_trade start token_identifier={}",
         token_identifier
     ));
 
+    if !is_cw20_identifier(deps, token_identifier) {
+        deps.api.debug("LP: native denom, skipping bridge validation");
+        return Ok(true);
+    }
+
     // For compatibility: allow both "cw20:<bech32>" and raw bech32 addresses
     let contract_address = token_identifier
         .strip_prefix("cw20:")
@@ -90,6 +161,13 @@ fn validate_wrapped_token_for_trade(deps: Deps, token_identifier: &str) -> Resul
         contract_address
     ));
 
+    // Malformed addresses can never be approved, so don't spend a gRPC round-trip on them
+    if deps.api.addr_validate(contract_address).is_err() {
+        return Err(ContractError::InvalidToken {
+            token: format!("CW20 contract address {} is not a valid bech32 address", contract_address),
+        });
+    }
+
     // Construct the proto request and send via generic helper
     let request = QueryValidateWrappedTokenForTradeRequest {
         contract_address: contract_address.to_string(),
@@ -99,8 +177,7 @@ fn validate_wrapped_token_for_trade(deps: Deps, token_identifier: &str) -> Resul
         deps,
         "/inference.inference.Query/ValidateWrappedTokenForTrade",
         &request,
-    )
-    .map_err(|e| ContractError::Std(e))?;
+    )?;
     deps.api.debug(&format!(
         "LP: ValidateWrappedTokenForTrade response is_valid={}",
         response.is_valid
@@ -110,31 +187,53 @@ fn validate_wrapped_token_for_trade(deps: Deps, token_identifier: &str) -> Resul
 }
 
 // Helper function to get native denomination from bank module
-fn get_native_denom(deps: Deps) -> Result<String, ContractError> {
-    // Query the bank module's total supply to get the base/native denomination
-    // The first coin in total supply is typically the native/base denom
-    let request = QueryTotalSupplyRequest {};
-    
-    match query_proto::<QueryTotalSupplyRequest, QueryTotalSupplyResponse>(
-        deps,
-        "/cosmos.bank.v1beta1.Query/TotalSupply",
-        &request,
-    ) {
-        Ok(response) => {
-            // Get the first coin from total supply, which is the native/base denom
-            if let Some(coin) = response.supply.first() {
-                if !coin.denom.is_empty() {
-                    return Ok(coin.denom.clone());
+fn get_native_denom(deps: Deps, fallback_denom: &str) -> Result<String, ContractError> {
+    // Find the base denom in the bank module's total supply, which is ordered by denom, so
+    // bridged and module denoms ("factory/...", "ibc/...") can come before it. The first page
+    // holds a single coin, enough on a chain with only its own token: decoding the response is
+    // the part of this query that grows with the chain's denoms: the one-coin page is 31 bytes,
+    // the full list ~4.6 KB on a chain with 50 IBC denoms (measured in
+    // test_native_denom_first_page_is_one_coin). Later pages are larger, up to MAX_SUPPLY_PAGES.
+    let mut page = PageRequest {
+        limit: 1,
+        ..Default::default()
+    };
+    for _ in 0..=MAX_SUPPLY_PAGES {
+        let request = QueryTotalSupplyRequest { pagination: Some(page) };
+        let response = match query_proto::<QueryTotalSupplyRequest, QueryTotalSupplyResponse>(
+            deps,
+            "/cosmos.bank.v1beta1.Query/TotalSupply",
+            &request,
+        ) {
+            Ok(response) => response,
+            // Fall back to the configured denom if query fails
+            Err(_) => return Ok(fallback_denom.to_string()),
+        };
+        if let Some(coin) = response.supply.iter().find(|coin| looks_like_base_denom(&coin.denom)) {
+            return Ok(coin.denom.clone());
+        }
+        // A node that ignores pagination sends everything and no next key
+        match response.pagination.map(|p| p.next_key).filter(|key| !key.is_empty()) {
+            Some(next_key) => {
+                page = PageRequest {
+                    key: next_key,
+                    limit: SUPPLY_PAGE_LIMIT,
+                    ..Default::default()
                 }
             }
-            // Fall back to default if supply is empty or denom is empty
-            Ok("ngonka".to_string())
-        },
-        Err(_) => {
-            // Fall back to default if query fails
-            Ok("ngonka".to_string())
+            None => break,
         }
     }
+    // Fall back to the configured denom if no coin qualifies
+    Ok(fallback_denom.to_string())
+}
+
+// A chain's own base denom is a plain name like "ngonka". Denoms with a path ("ibc/...",
+// "factory/...") or other punctuation come from bridges or modules and are never the native token.
+fn looks_like_base_denom(denom: &str) -> bool {
+    (3..=128).contains(&denom.len())
+        && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && denom.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
 }
 
 // Helper function to create CW20 transfer message
@@ -156,6 +255,47 @@ fn create_cw20_transfer_msg(
     })
 }
 
+fn query_cw20_balance(deps: Deps, cw20_contract: &str, address: &str) -> Result<Uint128, ContractError> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Cw20QueryMsg {
+        Balance { address: String },
+    }
+    #[derive(serde::Deserialize)]
+    struct Cw20BalanceResponse {
+        balance: Uint128,
+    }
+
+    let response: Cw20BalanceResponse = deps
+        .querier
+        .query_wasm_smart(cw20_contract, &Cw20QueryMsg::Balance { address: address.to_string() })
+        .map_err(|e| ContractError::Std(StdError::msg(format!("query cw20 balance: {}", e))))?;
+    Ok(response.balance)
+}
+
+// Decimals a CW20 payment token reports in its TokenInfo; None for native denoms or when the
+// contract doesn't answer
+fn query_cw20_decimals(deps: Deps, token_identifier: &str) -> Option<u8> {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Cw20QueryMsg {
+        TokenInfo {},
+    }
+    #[derive(serde::Deserialize)]
+    struct Cw20TokenInfoResponse {
+        decimals: u8,
+    }
+
+    if !is_cw20_identifier(deps, token_identifier) {
+        return None;
+    }
+    let contract_address = token_identifier.strip_prefix("cw20:").unwrap_or(token_identifier);
+    deps.querier
+        .query_wasm_smart::<Cw20TokenInfoResponse>(contract_address, &Cw20QueryMsg::TokenInfo {})
+        .ok()
+        .map(|info| info.decimals)
+}
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -166,6 +306,12 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
         .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(e.to_string())))?;
 
+    // Reported so operators can tell from the tx log whether any pricing default took effect
+    let used_defaults = msg.daily_limit_bp.is_none()
+        || msg.base_price_usd.is_none()
+        || msg.tokens_per_tier.is_none()
+        || msg.tier_multiplier.is_none();
+
     // Validate daily limit
     let daily_limit_bp = msg.daily_limit_bp.unwrap_or(Uint128::from(100u128));
     if daily_limit_bp.is_zero() || daily_limit_bp > Uint128::from(10000u128) {
@@ -181,7 +327,21 @@ pub fn instantiate(
     };
 
     // Get native denomination from chain
-    let native_denom = get_native_denom(deps.as_ref())?;
+    let fallback_denom = msg.fallback_denom.unwrap_or_else(default_fallback_denom);
+    if fallback_denom.is_empty() {
+        return Err(ContractError::InvalidToken { token: "fallback_denom cannot be empty".to_string() });
+    }
+    let native_denom = get_native_denom(deps.as_ref(), &fallback_denom)?;
+
+    let native_decimals = msg.native_decimals.unwrap_or_else(default_native_decimals);
+    if native_decimals > MAX_NATIVE_DECIMALS {
+        return Err(ContractError::InvalidNativeDecimals { decimals: native_decimals, max: MAX_NATIVE_DECIMALS });
+    }
+
+    let rollover_grace_seconds = msg.rollover_grace_seconds.unwrap_or(0);
+    if rollover_grace_seconds > MAX_ROLLOVER_GRACE_SECONDS {
+        return Err(ContractError::InvalidRolloverGrace { seconds: rollover_grace_seconds, max: MAX_ROLLOVER_GRACE_SECONDS });
+    }
 
     // Use provided total_supply or default to 0
     let total_supply = msg.total_supply.unwrap_or(Uint128::zero());
@@ -189,10 +349,35 @@ pub fn instantiate(
     let config = Config {
         admin: admin.clone(),
         native_denom: native_denom.clone(),
-        daily_limit_bp: daily_limit_bp,
-        is_paused: false,
-        total_supply: total_supply,
+        daily_limit_bp,
+        pause_flags: PauseFlags::default(),
+        total_supply,
         total_tokens_sold: Uint128::zero(),
+        total_usd_received: Uint128::zero(),
+        fee_bp: Uint128::zero(),
+        fee_recipient: None,
+        fallback_denom,
+        native_decimals,
+        purchase_id: 0,
+        require_allowlist: msg.require_allowlist.unwrap_or(false),
+        rollover_grace_seconds,
+        limit_basis: LimitBasis::TotalSupply,
+        circulating_supply: None,
+        max_purchase_usd: None,
+        trust_cache_only: false,
+        auto_pause_price_usd: None,
+        daily_usd_limit: None,
+        verify_received: false,
+        purchases_auto_resume_at: None,
+        allow_daily_stats_reset: msg.allow_daily_stats_reset.unwrap_or(false),
+        max_purchases_per_buyer_per_day: None,
+        twap_price_cumulative: Uint256::zero(),
+        twap_last_update: 0,
+        twap_last_price: Uint128::zero(),
+        cw20_proceeds_recipient: None,
+        purchase_hook: None,
+        hook_required: false,
+        event_prefix: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -202,12 +387,14 @@ pub fn instantiate(
         base_price_usd: msg.base_price_usd.unwrap_or(Uint128::from(25000u128)),
         tokens_per_tier: msg.tokens_per_tier.unwrap_or(Uint128::from(3_000_000_000_000_000u128)),
         tier_multiplier: msg.tier_multiplier.unwrap_or(Uint128::from(1300u128)),
+        max_tier: msg.max_tier,
     };
 
+    validate_tier_multiplier(&pricing_config)?;
     PRICING_CONFIG.save(deps.storage, &pricing_config)?;
 
     // Initialize daily stats
-    let current_day = env.block.time.seconds() / 86400;
+    let current_day = stats_day(env.block.time.seconds(), rollover_grace_seconds);
     let daily_stats = DailyStats {
         current_day,
         usd_received_today: Uint128::zero(),
@@ -219,11 +406,38 @@ pub fn instantiate(
         .add_attribute("method", "instantiate")
         .add_attribute("admin", admin)
         .add_attribute("native_denom", native_denom)
-        .add_attribute("total_supply", total_supply))
+        .add_attribute("total_supply", total_supply)
+        .add_attribute("base_price_usd", pricing_config.base_price_usd)
+        .add_attribute("tokens_per_tier", pricing_config.tokens_per_tier)
+        .add_attribute("tier_multiplier", pricing_config.tier_multiplier)
+        .add_attribute("daily_limit_bp", daily_limit_bp)
+        .add_attribute("used_defaults", used_defaults.to_string()))
 }
 
 #[entry_point]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let res = dispatch_execute(deps.branch(), env, info, msg)?;
+    // Loaded afterwards so UpdateEventPrefix already reports under the new prefix
+    let config = CONFIG.load(deps.storage)?;
+    Ok(with_event_prefix(res, config.event_prefix.as_deref()))
+}
+
+/// Prefixes the response's attribute keys with `{prefix}_`; leaves them as they are without a prefix
+fn with_event_prefix(mut res: Response, prefix: Option<&str>) -> Response {
+    if let Some(prefix) = prefix {
+        for attr in res.attributes.iter_mut() {
+            attr.key = format!("{}_{}", prefix, attr.key);
+        }
+    }
+    res
+}
+
+fn dispatch_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -231,30 +445,84 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::Pause {} => pause_contract(deps, info),
-        ExecuteMsg::Resume {} => resume_contract(deps, info),
+        ExecuteMsg::PurchaseWithNative {} => purchase_with_native(deps, env, info),
+        ExecuteMsg::Pause { flags, auto_resume_after } => pause_contract(deps, env, info, flags, auto_resume_after),
+        ExecuteMsg::Resume { flags } => resume_contract(deps, info, flags),
         ExecuteMsg::UpdateDailyLimit { daily_limit_bp } => {
             update_daily_limit(deps, info, daily_limit_bp)
         }
+        ExecuteMsg::UpdateCirculatingSupply { circulating_supply } => {
+            update_circulating_supply(deps, info, circulating_supply)
+        }
+        ExecuteMsg::UpdateLimitBasis { limit_basis } => update_limit_basis(deps, info, limit_basis),
+        ExecuteMsg::UpdateMaxPurchase { max_purchase_usd } => update_max_purchase(deps, info, max_purchase_usd),
+        ExecuteMsg::UpdateMaxPurchasesPerDay { max_purchases_per_buyer_per_day } => {
+            update_max_purchases_per_day(deps, info, max_purchases_per_buyer_per_day)
+        }
+        ExecuteMsg::UpdateAutoPausePrice { auto_pause_price_usd } => {
+            update_auto_pause_price(deps, info, auto_pause_price_usd)
+        }
+        ExecuteMsg::UpdateDailyUsdLimit { daily_usd_limit } => update_daily_usd_limit(deps, info, daily_usd_limit),
+        ExecuteMsg::ResetDailyStats {} => reset_daily_stats(deps, env, info),
+        ExecuteMsg::SetVerifyReceived { verify_received } => set_verify_received(deps, info, verify_received),
+        ExecuteMsg::UpdateRolloverGrace { rollover_grace_seconds } => {
+            update_rollover_grace(deps, info, rollover_grace_seconds)
+        }
+        ExecuteMsg::UpdateTotalSupply { total_supply } => {
+            update_total_supply(deps, info, total_supply)
+        }
+        ExecuteMsg::UpdateFee { fee_bp, fee_recipient } => {
+            update_fee(deps, info, fee_bp, fee_recipient)
+        }
+        ExecuteMsg::UpdateProceedsRecipient { cw20_proceeds_recipient } => {
+            update_proceeds_recipient(deps, info, cw20_proceeds_recipient)
+        }
+        ExecuteMsg::UpdatePurchaseHook { purchase_hook, hook_required } => {
+            update_purchase_hook(deps, info, purchase_hook, hook_required)
+        }
+        ExecuteMsg::UpdateEventPrefix { event_prefix } => update_event_prefix(deps, info, event_prefix),
         ExecuteMsg::WithdrawNativeTokens { amount, recipient } => {
             withdraw_native_tokens(deps, info, amount, recipient)
         }
+        ExecuteMsg::RecoverMisdirectedNative { recipient, amount } => {
+            recover_misdirected_native(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::BurnUnsold { amount } => burn_unsold(deps, env, info, amount),
+        ExecuteMsg::WithdrawNativeBatch { payouts } => withdraw_native_batch(deps, env, info, payouts),
         ExecuteMsg::EmergencyWithdraw { recipient } => emergency_withdraw(deps, env, info, recipient),
+        ExecuteMsg::SweepCw20 { cw20_contract } => sweep_cw20(deps, env, info, cw20_contract),
+        ExecuteMsg::ForceReturnCw20 {
+            cw20_contract,
+            recipient,
+            amount,
+        } => force_return_cw20(deps, info, cw20_contract, recipient, amount),
         ExecuteMsg::UpdatePricingConfig {
             base_price_usd,
             tokens_per_tier,
             tier_multiplier,
-        } => update_pricing_config(deps, info, base_price_usd, tokens_per_tier, tier_multiplier),
-        ExecuteMsg::AddPaymentToken { denom, usd_rate } => {
-            add_payment_token(deps, info, denom, usd_rate)
+            max_tier,
+        } => update_pricing_config(deps, info, base_price_usd, tokens_per_tier, tier_multiplier, max_tier),
+        ExecuteMsg::AddPaymentToken { denom, usd_rate, decimals } => {
+            add_payment_token(deps, info, denom, usd_rate, decimals)
         }
+        ExecuteMsg::AddPaymentTokens { tokens } => add_payment_tokens(deps, info, tokens),
         ExecuteMsg::RemovePaymentToken { denom } => remove_payment_token(deps, info, denom),
+        ExecuteMsg::SetRequireAllowlist { require_allowlist } => {
+            set_require_allowlist(deps, info, require_allowlist)
+        }
+        ExecuteMsg::GrantRole { address, role } => update_admin_role(deps, info, address, role, true),
+        ExecuteMsg::RevokeRole { address, role } => update_admin_role(deps, info, address, role, false),
+        ExecuteMsg::AddToAllowlist { address } => update_allowlist(deps, info, address, true),
+        ExecuteMsg::RemoveFromAllowlist { address } => update_allowlist(deps, info, address, false),
+        ExecuteMsg::AddTrustedCw20 { address } => update_trusted_cw20(deps, info, address, true),
+        ExecuteMsg::RemoveTrustedCw20 { address } => update_trusted_cw20(deps, info, address, false),
+        ExecuteMsg::SetTrustCacheOnly { trust_cache_only } => set_trust_cache_only(deps, info, trust_cache_only),
     }
 }
 
 // Handle receiving CW20 tokens (wrapped bridge tokens only)
 fn receive_cw20(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
@@ -267,12 +535,16 @@ fn receive_cw20(
         cw20_msg.msg.len()
     ));
     let config = CONFIG.load(deps.storage)?;
-    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
 
-    if config.is_paused {
+    if config.purchases_paused(env.block.time.seconds()) {
         return Err(ContractError::ContractPaused {});
     }
 
+    // Without a total supply the daily limit is zero and every purchase would fail as over-limit
+    if config.total_supply.is_zero() {
+        return Err(ContractError::TotalSupplyNotConfigured {});
+    }
+
     // The sender (info.sender) is the CW20 contract address
     let cw20_contract = info.sender.to_string();
     deps.api.debug(&format!(
@@ -280,8 +552,8 @@ fn receive_cw20(
         cw20_contract
     ));
     
-    // CRITICAL: Validate this is a legitimate bridge token for trading by checking the cosmos module
-    if !validate_wrapped_token_for_trade(deps.as_ref(), &cw20_contract)? {
+    // CRITICAL: Validate this is a legitimate bridge token for trading (trusted locally or via the cosmos module)
+    if !is_cw20_accepted(deps.as_ref(), &config, &info.sender)? {
         deps.api.debug("LP: validate_wrapped_token_for_trade returned false");
         return Err(ContractError::TokenNotAccepted {
             token: format!("CW20 contract {} is not a legitimate bridge token approved for trading", cw20_contract),
@@ -291,36 +563,404 @@ fn receive_cw20(
 
     // Parse the message to determine what action to take
     deps.api.debug("LP: parsing inner purchase msg");
-    let _purchase_msg: PurchaseTokenMsg = from_json(&cw20_msg.msg)?;
+    let purchase_msg: PurchaseTokenMsg = from_json(&cw20_msg.msg)?;
     
     // The actual sender of the tokens (the user)
     let buyer = cw20_msg.sender;
     let token_amount = cw20_msg.amount;
+    check_allowlist(deps.as_ref(), &config, &buyer)?;
+    count_buyer_purchase(deps.branch(), &env, &config, &buyer)?;
+    if config.verify_received {
+        check_received_amount(deps.as_ref(), &env, &cw20_contract, token_amount)?;
+    }
+
+    // For wrapped bridge tokens, treat amount as micro-USD (1:1 with amount)
+    // This assumes wrapped tokens like USDT have 6 decimals and are USD-pegged
+    let usd_value = token_amount;
+
+    let outcome = process_purchase(deps.branch(), &env, config, usd_value, purchase_msg.allow_daily_partial)?;
+    let tokens_to_buy = outcome.tokens_to_buy;
+    let usd_spent = outcome.usd_spent;
+    let updated_config = outcome.config;
+    add_buyer_totals(deps.branch(), &buyer, tokens_to_buy, usd_spent)?;
+
+    // A partial daily fill spends less than was sent; only the spent CW20 is kept
+    let refunded_cw20 = token_amount.checked_sub(usd_spent).unwrap_or_default();
+    let kept_cw20 = token_amount - refunded_cw20;
+
+    // Send native tokens to buyer (and the protocol fee to its recipient)
+    let mut response = Response::new().add_messages(payout_msgs(&updated_config, &buyer, tokens_to_buy, outcome.fee_amount));
+
+    if !refunded_cw20.is_zero() {
+        response = response.add_message(create_cw20_transfer_msg(cw20_contract.clone(), buyer.clone(), refunded_cw20)?);
+    }
+    if let Some(price) = outcome.auto_paused_price {
+        response = response.add_attribute("auto_paused_price", price);
+    }
+    if let Some(reason) = outcome.soft_rejection {
+        response = response.add_attribute("soft_rejection", reason);
+    }
+
+    // Forward received CW20 tokens to the proceeds recipient, or the governance module (admin)
+    let forward_to = updated_config.proceeds_destination().map(str::to_string);
+    if let Some(forward_to) = &forward_to {
+        let transfer_cw20_msg = create_cw20_transfer_msg(
+            cw20_contract.clone(),
+            forward_to.clone(),
+            kept_cw20,
+        )?;
+        let payload = to_json_binary(&ForwardCw20Payload {
+            cw20_contract: cw20_contract.clone(),
+            amount: kept_cw20,
+        })?;
+        response = response.add_submessage(
+            SubMsg::reply_on_error(transfer_cw20_msg, FORWARD_CW20_REPLY_ID).with_payload(payload),
+        );
+        FORWARDED_TOTALS.update(
+            deps.storage,
+            cw20_contract.as_str(),
+            |forwarded| -> StdResult<_> { Ok(forwarded.unwrap_or_default().checked_add(kept_cw20)?) },
+        )?;
+        deps.api.debug(&format!(
+            "LP: forwarding CW20 tokens to {} amount={}",
+            forward_to,
+            kept_cw20
+        ));
+    } else {
+        // Record who the retained CW20 came from so it can be reconciled once a recipient is set
+        let buyer_addr = deps.api.addr_validate(&buyer)?;
+        RECEIVED_FROM.update(
+            deps.storage,
+            (&buyer_addr, cw20_contract.as_str()),
+            |received| -> StdResult<_> { Ok(received.unwrap_or_default().checked_add(kept_cw20)?) },
+        )?;
+        deps.api.debug("LP: no admin set, CW20 tokens remain in contract");
+    }
+
+    if let Some(hook) = purchase_hook_msg(&updated_config, &buyer, tokens_to_buy, usd_spent, outcome.end_tier)? {
+        response = response.add_submessage(hook);
+    }
+
+    deps.api.debug("LP: building success response with native send and CW20 forward");
+    
+    Ok(response
+        .add_attribute("method", "purchase_with_wrapped_token")
+        .add_attribute("purchase_id", updated_config.purchase_id.to_string())
+        .add_attribute("buyer", buyer)
+        .add_attribute("wrapped_token_contract", cw20_contract)
+        .add_attribute("wrapped_token_amount", token_amount)
+        .add_attribute("tokens_purchased", tokens_to_buy)
+        .add_attribute("usd_received", usd_value)
+        .add_attribute("usd_spent", usd_spent)
+        .add_attribute("filled_tokens", tokens_to_buy)
+        .add_attribute("refunded_cw20", refunded_cw20)
+        .add_attribute("start_tier", outcome.start_tier.to_string())
+        .add_attribute("end_tier", outcome.end_tier.to_string())
+        .add_attribute("average_price_paid", outcome.average_price)
+        .add_attribute("tokens_available_today", outcome.tokens_available_today)
+        .add_attribute("fee_amount", outcome.fee_amount)
+        .add_attribute("fee_recipient", updated_config.fee_recipient.clone().unwrap_or_default())
+        .add_attribute("cw20_forwarded_to", forward_to.unwrap_or_default()))
+}
+
+// Handle a purchase paid in an accepted native denom attached as funds
+fn purchase_with_native(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.purchases_paused(env.block.time.seconds()) {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    // Same guard as receive_cw20: without a total supply every purchase would fail as over-limit
+    if config.total_supply.is_zero() {
+        return Err(ContractError::TotalSupplyNotConfigured {});
+    }
+
+    let payment = match info.funds.as_slice() {
+        [coin] => coin.clone(),
+        _ => {
+            return Err(ContractError::InvalidToken {
+                token: "exactly one native payment coin must be attached".to_string(),
+            })
+        }
+    };
+
+    // CW20 identifiers and the sold denom itself can never be native payment tokens
+    if is_cw20_identifier(deps.as_ref(), &payment.denom) || payment.denom == config.native_denom {
+        return Err(ContractError::TokenNotAccepted { token: payment.denom });
+    }
+    let usd_rate = PAYMENT_TOKENS
+        .may_load(deps.storage, &payment.denom)?
+        .ok_or_else(|| ContractError::TokenNotAccepted { token: payment.denom.clone() })?;
+
+    let payment_amount: Uint128 = payment
+        .amount
+        .try_into()
+        .map_err(|_| ContractError::Std(cosmwasm_std::StdError::msg("payment amount exceeds Uint128")))?;
+    if payment_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let usd_value = payment_amount
+        .checked_mul(usd_rate)
+        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(format!("overflow: {}", e))))?;
+
+    let buyer = info.sender.to_string();
+    check_allowlist(deps.as_ref(), &config, &buyer)?;
+    count_buyer_purchase(deps.branch(), &env, &config, &buyer)?;
+    let outcome = process_purchase(deps.branch(), &env, config, usd_value, false)?;
+    let tokens_to_buy = outcome.tokens_to_buy;
+    let updated_config = outcome.config;
+    add_buyer_totals(deps.branch(), &buyer, tokens_to_buy, outcome.usd_spent)?;
+
+    let mut response = Response::new().add_messages(payout_msgs(&updated_config, &buyer, tokens_to_buy, outcome.fee_amount));
+
+    if let Some(price) = outcome.auto_paused_price {
+        response = response.add_attribute("auto_paused_price", price);
+    }
+
+    // Forward the payment to the proceeds recipient, or the governance module (admin), as the
+    // CW20 path does; with neither it stays in the contract
+    if let Some(forward_to) = updated_config.proceeds_destination() {
+        response = response
+            .add_message(BankMsg::Send {
+                to_address: forward_to.to_string(),
+                amount: vec![payment.clone()],
+            })
+            .add_attribute("payment_forwarded_to", forward_to);
+    }
+    if let Some(hook) = purchase_hook_msg(&updated_config, &buyer, tokens_to_buy, outcome.usd_spent, outcome.end_tier)? {
+        response = response.add_submessage(hook);
+    }
+
+    Ok(response
+        .add_attribute("method", "purchase_with_native")
+        .add_attribute("purchase_id", updated_config.purchase_id.to_string())
+        .add_attribute("buyer", buyer)
+        .add_attribute("payment_denom", payment.denom)
+        .add_attribute("payment_amount", payment_amount)
+        .add_attribute("tokens_purchased", tokens_to_buy)
+        .add_attribute("usd_received", usd_value)
+        .add_attribute("start_tier", outcome.start_tier.to_string())
+        .add_attribute("end_tier", outcome.end_tier.to_string())
+        .add_attribute("average_price_paid", outcome.average_price)
+        .add_attribute("tokens_available_today", outcome.tokens_available_today)
+        .add_attribute("fee_amount", outcome.fee_amount)
+        .add_attribute("fee_recipient", updated_config.fee_recipient.clone().unwrap_or_default()))
+}
+
+/// Whether a CW20 may be used to purchase: trusted CW20s skip the chain query, and with
+/// `trust_cache_only` nothing else is accepted
+fn is_cw20_accepted(deps: Deps, config: &Config, cw20_contract: &Addr) -> Result<bool, ContractError> {
+    if TRUSTED_CW20S.has(deps.storage, cw20_contract) {
+        deps.api.debug(&format!("LP: cw20={} trusted locally, skipping chain validation", cw20_contract));
+        return Ok(true);
+    }
+    if config.trust_cache_only {
+        return Ok(false);
+    }
+    validate_wrapped_token_for_trade(deps, cw20_contract.as_str())
+}
+
+/// Reject a receive whose reported amount the pool's CW20 balance doesn't cover, as happens when the
+/// token takes a fee on transfer. The hook runs after the transfer, so there is no pre-transfer
+/// balance to diff against: CW20 the pool already held (e.g. retained while no admin is set) masks
+/// a shortfall, making this a sanity check rather than exact fee accounting.
+fn check_received_amount(deps: Deps, env: &Env, cw20_contract: &str, reported: Uint128) -> Result<(), ContractError> {
+    let held = query_cw20_balance(deps, cw20_contract, env.contract.address.as_str())?;
+    let shortfall = reported.checked_sub(held).unwrap_or_default();
+    if shortfall > reported.multiply_ratio(RECEIVED_TOLERANCE_BP, 10000u128) {
+        return Err(ContractError::ReceivedAmountMismatch { reported, held });
+    }
+    Ok(())
+}
+
+// Reject buyers missing from the allowlist while it is required
+fn check_allowlist(deps: Deps, config: &Config, buyer: &str) -> Result<(), ContractError> {
+    if !config.require_allowlist {
+        return Ok(());
+    }
+    let buyer_addr = deps.api.addr_validate(buyer)?;
+    if !ALLOWLIST.may_load(deps.storage, &buyer_addr)?.unwrap_or(false) {
+        return Err(ContractError::BuyerNotAllowed { buyer: buyer.to_string() });
+    }
+    Ok(())
+}
+
+// Day a purchase now is counted on, held at the stored stats day like the daily limit so a
+// raised rollover grace can't move a buyer back to a fresh per-day count
+fn purchase_day(storage: &dyn Storage, env: &Env, config: &Config) -> StdResult<u64> {
+    Ok(DAILY_STATS.load(storage)?.day_at(env.block.time.seconds(), config.rollover_grace_seconds))
+}
+
+// Purchases `buyer` has made on `day`
+fn purchases_on_day(storage: &dyn Storage, buyer: &Addr, day: u64) -> StdResult<u32> {
+    Ok(BUYER_DAILY_PURCHASES.may_load(storage, (buyer, day))?.unwrap_or_default())
+}
+
+fn check_purchases_today(config: &Config, made: u32) -> Result<(), ContractError> {
+    match config.max_purchases_per_buyer_per_day {
+        Some(max) if made >= max => Err(ContractError::TooManyPurchasesToday { max }),
+        _ => Ok(()),
+    }
+}
+
+// Count one more purchase by `buyer` today, rejecting it once the daily cap is reached
+fn count_buyer_purchase(deps: DepsMut, env: &Env, config: &Config, buyer: &str) -> Result<(), ContractError> {
+    let buyer_addr = deps.api.addr_validate(buyer)?;
+    let day = purchase_day(deps.storage, env, config)?;
+    let made = purchases_on_day(deps.storage, &buyer_addr, day)?;
+    check_purchases_today(config, made)?;
+    BUYER_DAILY_PURCHASES.save(deps.storage, (&buyer_addr, day), &(made + 1))?;
+    Ok(())
+}
+
+fn add_buyer_totals(deps: DepsMut, buyer: &str, tokens: Uint128, usd: Uint128) -> Result<(), ContractError> {
+    let buyer_addr = deps.api.addr_validate(buyer)?;
+    BUYER_TOTALS.update(deps.storage, &buyer_addr, |totals| -> StdResult<_> {
+        let totals = totals.unwrap_or_default();
+        Ok(BuyerTotals {
+            tokens: totals.tokens.checked_add(tokens)?,
+            usd: totals.usd.checked_add(usd)?,
+        })
+    })?;
+    Ok(())
+}
+
+// USD that can still be received today, or None without a daily USD cap
+fn daily_usd_available(config: &Config, usd_received_today: Uint128) -> Option<Uint128> {
+    config
+        .daily_usd_limit
+        .map(|limit| limit.checked_sub(usd_received_today).unwrap_or_default())
+}
+
+/// Native balance purchases may draw on. Nothing is carved out of it yet, so this is the whole
+/// balance; the purchase checks and `SellableBalance` share it so they cannot drift apart.
+fn sellable_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint256> {
+    Ok(deps
+        .querier
+        .query_balance(env.contract.address.to_string(), config.native_denom.as_str())?
+        .amount)
+}
+
+fn check_daily_usd_limit(config: &Config, usd_received_today: Uint128, usd_value: Uint128) -> Result<(), ContractError> {
+    match daily_usd_available(config, usd_received_today) {
+        Some(available) if usd_value > available => Err(ContractError::DailyUsdLimitExceeded {
+            available: available.u128(),
+            requested: usd_value.u128(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Per-transaction cap on the USD offered, checked before any partial daily fill
+fn check_max_purchase(config: &Config, usd_value: Uint128) -> Result<(), ContractError> {
+    match config.max_purchase_usd {
+        Some(max) if usd_value > max => Err(ContractError::AboveMaximumPurchase { max, got: usd_value }),
+        _ => Ok(()),
+    }
+}
+
+/// Accounting result of a purchase shared by the CW20 and native payment paths
+struct PurchaseOutcome {
+    tokens_to_buy: Uint128,
+    /// USD actually charged; below the offered amount only for a partial daily fill
+    usd_spent: Uint128,
+    /// Part of tokens_to_buy routed to the fee recipient instead of the buyer
+    fee_amount: Uint128,
+    start_tier: u32,
+    end_tier: u32,
+    average_price: Uint128,
+    tokens_available_today: Uint128,
+    /// Price that tripped the auto-pause ceiling, when this purchase paused further purchases
+    auto_paused_price: Option<Uint128>,
+    /// Why the purchase was trimmed, when it was
+    soft_rejection: Option<&'static str>,
+    config: Config,
+}
+
+// Archive a day's stats, adding to any record already kept for that day by ResetDailyStats
+fn archive_daily_stats(storage: &mut dyn Storage, stats: &DailyStats) -> StdResult<()> {
+    DAILY_STATS_HISTORY.update(storage, stats.current_day, |archived| -> StdResult<_> {
+        Ok(match archived {
+            Some(archived) => DailyStats {
+                current_day: stats.current_day,
+                usd_received_today: archived.usd_received_today.saturating_add(stats.usd_received_today),
+                tokens_sold_today: archived.tokens_sold_today.saturating_add(stats.tokens_sold_today),
+            },
+            None => stats.clone(),
+        })
+    })?;
+    Ok(())
+}
+
+/// Adds the outgoing price's time-in-effect to the TWAP accumulator and records the new price
+fn record_twap_observation(storage: &mut dyn Storage, config: &mut Config, now: u64, price: Uint128) -> StdResult<()> {
+    if config.twap_last_update != 0 {
+        let elapsed = now.saturating_sub(config.twap_last_update);
+        config.twap_price_cumulative = config
+            .twap_price_cumulative
+            .checked_add(Uint256::from(config.twap_last_price).checked_mul(Uint256::from(elapsed))?)?;
+    }
+    config.twap_last_update = now;
+    config.twap_last_price = price;
+    TWAP_OBSERVATIONS.save(
+        storage,
+        now,
+        &TwapObservation { price_cumulative: config.twap_price_cumulative, price },
+    )?;
+
+    // Drop observations older than the longest lookback, but keep the newest of them: it anchors
+    // a window starting at the cutoff. Taking one extra key means that one is never removed.
+    let cutoff = now.saturating_sub(MAX_TWAP_LOOKBACK_SECONDS);
+    let stale = TWAP_OBSERVATIONS
+        .keys(storage, None, Some(Bound::inclusive(cutoff)), Order::Ascending)
+        .take(MAX_TWAP_PRUNE_PER_PURCHASE + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    for observed_at in stale.iter().take(stale.len().saturating_sub(1)) {
+        TWAP_OBSERVATIONS.remove(storage, *observed_at);
+    }
+    Ok(())
+}
+
+// Run the tiered pricing, daily limit and balance checks for a USD-valued purchase and persist the sale.
+// With `allow_daily_partial`, a purchase over today's capacity is shrunk to fit instead of rejected.
+fn process_purchase(
+    deps: DepsMut,
+    env: &Env,
+    config: Config,
+    mut usd_value: Uint128,
+    allow_daily_partial: bool,
+) -> Result<PurchaseOutcome, ContractError> {
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
 
-    let current_day = env.block.time.seconds() / 86400;
     let mut daily_stats = DAILY_STATS.load(deps.storage)?;
+    let current_day = daily_stats.day_at(env.block.time.seconds(), config.rollover_grace_seconds);
 
-    // Reset daily stats if it's a new day
+    // Archive the previous day and reset daily stats if it's a new day
     if daily_stats.current_day != current_day {
+        archive_daily_stats(deps.storage, &daily_stats)?;
         daily_stats.current_day = current_day;
         daily_stats.usd_received_today = Uint128::zero();
         daily_stats.tokens_sold_today = Uint128::zero();
     }
 
-    // For wrapped bridge tokens, treat amount as micro-USD (1:1 with amount)
-    // This assumes wrapped tokens like USDT have 6 decimals and are USD-pegged
-    let usd_value = token_amount;
-
     if usd_value.is_zero() {
         return Err(ContractError::ZeroAmount {});
     }
+    check_max_purchase(&config, usd_value)?;
 
     // Calculate multi-tier purchase: handles purchases spanning multiple tiers
-    let (tokens_to_buy, actual_usd_to_spend, start_tier, end_tier, average_price) = calculate_multi_tier_purchase(
+    let (mut tokens_to_buy, actual_usd_to_spend, mut start_tier, mut end_tier, mut average_price) = calculate_multi_tier_purchase(
         usd_value,
         config.total_tokens_sold,
         &pricing_config,
-    );
+        config.native_decimals,
+    )?;
 
     // Verify we can spend ALL the USD received (no partial spending allowed)
     if actual_usd_to_spend != usd_value {
@@ -341,8 +981,8 @@ fn receive_cw20(
 
     // Check daily limit - pure token-based approach
     let daily_token_limit = match config
-        .total_supply
-        .checked_mul(Uint128::from(config.daily_limit_bp))
+        .daily_limit_base()
+        .checked_mul(config.daily_limit_bp)
     {
         Ok(amount) => match amount.checked_div(Uint128::from(10000u128)) {
             Ok(limit) => limit,
@@ -359,32 +999,47 @@ fn receive_cw20(
         .checked_sub(daily_stats.tokens_sold_today)
         .unwrap_or_default();
 
-    // Check daily limit: reject if exceeds available (no partial fills in CW20)
-    if tokens_to_buy > tokens_available_today {
+    // Shrink to today's capacity when the buyer opted into partial fills
+    let mut soft_rejection = None;
+    if tokens_to_buy > tokens_available_today && allow_daily_partial {
+        let usd_for_available = calculate_usd_for_tokens(
+            tokens_available_today,
+            config.total_tokens_sold,
+            &pricing_config,
+            config.native_decimals,
+        )?;
+        let (tokens, usd, start, end, average) = calculate_multi_tier_purchase(
+            usd_for_available,
+            config.total_tokens_sold,
+            &pricing_config,
+            config.native_decimals,
+        )?;
+        tokens_to_buy = tokens.min(tokens_available_today);
+        usd_value = usd;
+        start_tier = start;
+        end_tier = end;
+        average_price = average;
+        soft_rejection = Some(REJECTION_DAILY_PARTIAL);
+    }
+
+    // Check daily limit: reject if exceeds available, or if a partial fill can't buy anything
+    if tokens_to_buy > tokens_available_today || tokens_to_buy.is_zero() {
         return Err(ContractError::DailyLimitExceeded {
             available: tokens_available_today.u128(),
             requested: tokens_to_buy.u128(),
         });
     }
-
-    // We're spending ALL the USD received (verified above)
-    let usd_amount_to_track = usd_value;
+    check_daily_usd_limit(&config, daily_stats.usd_received_today, usd_value)?;
 
     // Check contract balance
     deps.api.debug("LP: querying contract native balance");
-    let contract_balance = deps
-        .querier
-        .query_balance(env.contract.address.to_string(), config.native_denom.as_str())?;
+    let sellable = sellable_balance(deps.as_ref(), env, &config)?;
 
-    // Convert Uint256 balance to Uint128 for comparison
-    let contract_balance_amount_128: Uint128 = contract_balance
-        .amount
-        .try_into()
-        .map_err(|_| ContractError::Std(cosmwasm_std::StdError::msg("contract balance exceeds Uint128")))?;
-
-    if tokens_to_buy > contract_balance_amount_128 {
+    // Compare in Uint256: a large-supply native token can hold more than Uint128::MAX
+    if Uint256::from(tokens_to_buy) > sellable {
         return Err(ContractError::InsufficientBalance {
-            available: contract_balance_amount_128.u128(),
+            // Below tokens_to_buy here, so it always fits in a u128
+            available: Uint128::try_from(sellable).unwrap_or(Uint128::MAX).u128(),
             needed: tokens_to_buy.u128(),
         });
     }
@@ -392,7 +1047,7 @@ fn receive_cw20(
     // Update daily stats with both USD and token tracking
     daily_stats.usd_received_today = daily_stats
         .usd_received_today
-        .checked_add(usd_amount_to_track)
+        .checked_add(usd_value)
         .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(format!("overflow: {}", e))))?;
     
     daily_stats.tokens_sold_today = daily_stats
@@ -400,88 +1055,223 @@ fn receive_cw20(
         .checked_add(tokens_to_buy)
         .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(format!("overflow: {}", e))))?;
     
+    let config_tokens_sold_before = config.total_tokens_sold;
     let mut updated_config = config;
     // Update total tokens sold (for tier calculation)
     updated_config.total_tokens_sold = updated_config
         .total_tokens_sold
         .checked_add(tokens_to_buy)
         .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(format!("overflow: {}", e))))?;
+    // Next purchase id, emitted as a dedup key for indexers
+    updated_config.purchase_id = updated_config
+        .purchase_id
+        .checked_add(1)
+        .ok_or_else(|| ContractError::Std(cosmwasm_std::StdError::msg("purchase id overflow")))?;
+    // Update lifetime USD received (for average price reporting)
+    updated_config.total_usd_received = updated_config
+        .total_usd_received
+        .checked_add(usd_value)
+        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(format!("overflow: {}", e))))?;
+
+    let price_after = calculate_current_price(
+        pricing_config.base_price_usd,
+        calculate_current_tier(updated_config.total_tokens_sold, pricing_config.tokens_per_tier),
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+    record_twap_observation(deps.storage, &mut updated_config, env.block.time.seconds(), price_after)?;
+
+    // The purchase that crosses the ceiling completes; later ones wait for an admin unpause
+    let auto_paused_price = updated_config
+        .auto_pause_price_usd
+        .and_then(|ceiling| (price_after > ceiling).then_some(price_after));
+    if auto_paused_price.is_some() {
+        updated_config.pause_flags.purchases = true;
+        // A ceiling pause waits for the admin, whatever an earlier pause's timer said
+        updated_config.purchases_auto_resume_at = None;
+    }
+
+    for (tier, tokens) in split_tokens_by_tier(config_tokens_sold_before, tokens_to_buy, pricing_config.tokens_per_tier) {
+        TIER_SALES.update(deps.storage, tier, |sold| -> StdResult<_> {
+            Ok(sold.unwrap_or_default().checked_add(tokens)?)
+        })?;
+    }
 
     DAILY_STATS.save(deps.storage, &daily_stats)?;
     CONFIG.save(deps.storage, &updated_config)?;
+    if let Some(reason) = soft_rejection {
+        REJECTION_STATS.update(deps.storage, reason, |count| -> StdResult<_> {
+            Ok(count.unwrap_or_default().saturating_add(1))
+        })?;
+    }
 
-    // Send native tokens to buyer
-    let send_native_msg = BankMsg::Send {
-        to_address: buyer.clone(),
+    // The fee is carved out of tokens_to_buy, so fee + buyer amount stays within the checked balance
+    let fee_amount = if updated_config.fee_recipient.is_some() {
+        tokens_to_buy.multiply_ratio(updated_config.fee_bp, 10000u128)
+    } else {
+        Uint128::zero()
+    };
+
+    Ok(PurchaseOutcome {
+        tokens_to_buy,
+        usd_spent: usd_value,
+        fee_amount,
+        start_tier,
+        end_tier,
+        average_price,
+        tokens_available_today,
+        auto_paused_price,
+        soft_rejection,
+        config: updated_config,
+    })
+}
+
+// Native token payouts for a purchase: the buyer's share plus the protocol fee, if any
+fn payout_msgs(config: &Config, buyer: &str, tokens_to_buy: Uint128, fee_amount: Uint128) -> Vec<BankMsg> {
+    let mut msgs = vec![BankMsg::Send {
+        to_address: buyer.to_string(),
         amount: vec![Coin {
-            denom: updated_config.native_denom.clone(),
-            amount: tokens_to_buy.into(),
+            denom: config.native_denom.clone(),
+            amount: (tokens_to_buy - fee_amount).into(),
         }],
+    }];
+    if let Some(fee_recipient) = &config.fee_recipient {
+        if !fee_amount.is_zero() {
+            msgs.push(BankMsg::Send {
+                to_address: fee_recipient.clone(),
+                amount: vec![Coin {
+                    denom: config.native_denom.clone(),
+                    amount: fee_amount.into(),
+                }],
+            });
+        }
+    }
+    msgs
+}
+
+/// Notification for the purchase hook, if one is set. An optional hook replies on error so its
+/// failure can be swallowed; a required one fails the whole transaction.
+fn purchase_hook_msg(config: &Config, buyer: &str, tokens: Uint128, usd: Uint128, tier: u32) -> StdResult<Option<SubMsg>> {
+    let Some(hook) = &config.purchase_hook else {
+        return Ok(None);
     };
+    let msg = WasmMsg::Execute {
+        contract_addr: hook.clone(),
+        msg: to_json_binary(&PurchaseHookMsg::OnPurchase { buyer: buyer.to_string(), tokens, usd, tier })?,
+        funds: vec![],
+    };
+    Ok(Some(if config.hook_required {
+        SubMsg::new(msg)
+    } else {
+        SubMsg::reply_on_error(msg, PURCHASE_HOOK_REPLY_ID)
+    }))
+}
 
-    // Forward received CW20 tokens to governance module (admin)
-    let mut response = Response::new().add_message(send_native_msg);
-    
-    if !updated_config.admin.is_empty() {
-        let transfer_cw20_msg = create_cw20_transfer_msg(
-            cw20_contract.clone(),
-            updated_config.admin.clone(),
-            token_amount,
-        )?;
-        response = response.add_message(transfer_cw20_msg);
-        deps.api.debug(&format!(
-            "LP: forwarding CW20 tokens to governance admin={} amount={}",
-            updated_config.admin,
-            token_amount
-        ));
+/// The config admin (super-admin) holds every capability; other admins only those granted to them
+fn check_capability(deps: Deps, config: &Config, sender: &Addr, capability: AdminRole) -> Result<(), ContractError> {
+    if !config.admin.is_empty() && sender.as_str() == config.admin {
+        return Ok(());
+    }
+    let role = ADMIN_ROLES.may_load(deps.storage, sender)?.unwrap_or_default();
+    if role.contains(capability) {
+        Ok(())
     } else {
-        deps.api.debug("LP: no admin set, CW20 tokens remain in contract");
+        Err(ContractError::Unauthorized {})
     }
+}
 
-    deps.api.debug("LP: building success response with native send and CW20 forward");
-    
-    Ok(response
-        .add_attribute("method", "purchase_with_wrapped_token")
-        .add_attribute("buyer", buyer)
-        .add_attribute("wrapped_token_contract", cw20_contract)
-        .add_attribute("wrapped_token_amount", token_amount)
-        .add_attribute("tokens_purchased", tokens_to_buy)
-        .add_attribute("usd_received", usd_value)
-        .add_attribute("usd_spent", usd_amount_to_track)
-        .add_attribute("start_tier", start_tier.to_string())
-        .add_attribute("end_tier", end_tier.to_string())
-        .add_attribute("average_price_paid", average_price)
-        .add_attribute("tokens_available_today", tokens_available_today)
-        .add_attribute("cw20_forwarded_to", updated_config.admin))
+fn update_admin_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: AdminRole,
+    grant: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.admin.is_empty() || info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if role.is_empty() || !AdminRole::ALL.contains(role) {
+        return Err(ContractError::InvalidAdminRole { bits: role.0 });
+    }
+
+    let addr = deps.api.addr_validate(&address)?;
+    let held = ADMIN_ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    let updated = if grant { AdminRole(held.0 | role.0) } else { AdminRole(held.0 & !role.0) };
+    if updated.is_empty() {
+        ADMIN_ROLES.remove(deps.storage, &addr);
+    } else {
+        ADMIN_ROLES.save(deps.storage, &addr, &updated)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", if grant { "grant_role" } else { "revoke_role" })
+        .add_attribute("address", addr)
+        .add_attribute("role", updated.0.to_string())
+        .add_attribute("admin", info.sender))
 }
 
-fn pause_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+fn pause_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    flags: Option<PauseFlags>,
+    auto_resume_after: Option<u64>,
+) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::PAUSE)?;
+
+    let now = env.block.time.seconds();
+    if let Some(at) = auto_resume_after {
+        if at <= now {
+            return Err(ContractError::InvalidAutoResume { at, now });
+        }
     }
 
-    config.is_paused = true;
+    let flags = flags.unwrap_or_else(PauseFlags::purchases_only);
+    if flags.purchases {
+        // A lapsed pause is still flagged in storage; pausing again starts from the new timer
+        config.purchases_auto_resume_at = auto_resume_after;
+    }
+    config.pause_flags.purchases |= flags.purchases;
+    config.pause_flags.withdrawals |= flags.withdrawals;
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
         .add_attribute("method", "pause")
+        .add_attribute("purchases_paused", config.pause_flags.purchases.to_string())
+        .add_attribute("withdrawals_paused", config.pause_flags.withdrawals.to_string())
+        .add_attribute(
+            "auto_resume_after",
+            config.purchases_auto_resume_at.map_or_else(|| "none".to_string(), |at| at.to_string()),
+        )
         .add_attribute("admin", info.sender))
 }
 
-fn resume_contract(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+fn resume_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    flags: Option<PauseFlags>,
+) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::PAUSE)?;
 
-    config.is_paused = false;
+    let flags = flags.unwrap_or_else(PauseFlags::purchases_only);
+    if flags.purchases {
+        config.purchases_auto_resume_at = None;
+    }
+    config.pause_flags.purchases &= !flags.purchases;
+    config.pause_flags.withdrawals &= !flags.withdrawals;
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
         .add_attribute("method", "resume")
+        .add_attribute("purchases_paused", config.pause_flags.purchases.to_string())
+        .add_attribute("withdrawals_paused", config.pause_flags.withdrawals.to_string())
         .add_attribute("admin", info.sender))
 }
 
@@ -492,9 +1282,7 @@ fn update_daily_limit(
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
 
     let daily_limit_bp = daily_limit_bp.unwrap_or(Uint128::from(100u128));
     if daily_limit_bp.is_zero() || daily_limit_bp > Uint128::from(10000u128) {
@@ -512,560 +1300,5396 @@ fn update_daily_limit(
         .add_attribute("admin", info.sender))
 }
 
-fn withdraw_native_tokens(
+fn update_circulating_supply(
     deps: DepsMut,
     info: MessageInfo,
-    amount: Uint128,
-    recipient: String,
+    circulating_supply: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let mut config = CONFIG.load(deps.storage)?;
 
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    match circulating_supply {
+        Some(supply) if supply.is_zero() => return Err(ContractError::ZeroAmount {}),
+        None if config.limit_basis == LimitBasis::CirculatingOverride => {
+            return Err(ContractError::MissingCirculatingSupply {});
+        }
+        _ => {}
     }
 
-    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    config.circulating_supply = circulating_supply;
+    CONFIG.save(deps.storage, &config)?;
 
-    if amount.is_zero() {
-        return Err(ContractError::ZeroAmount {});
+    Ok(Response::new()
+        .add_attribute("method", "update_circulating_supply")
+        .add_attribute(
+            "circulating_supply",
+            circulating_supply.map_or_else(|| "none".to_string(), |supply| supply.to_string()),
+        )
+        .add_attribute("admin", info.sender))
+}
+
+fn update_limit_basis(deps: DepsMut, info: MessageInfo, limit_basis: LimitBasis) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if limit_basis == LimitBasis::CirculatingOverride && config.circulating_supply.is_none() {
+        return Err(ContractError::MissingCirculatingSupply {});
     }
 
-    let send_msg = BankMsg::Send {
-        to_address: recipient_addr.to_string(),
-        amount: vec![Coin {
-            denom: config.native_denom,
-            amount: amount.into(),
-        }],
-    };
+    config.limit_basis = limit_basis;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
-        .add_message(send_msg)
-        .add_attribute("method", "withdraw_native_tokens")
-        .add_attribute("amount", amount)
-        .add_attribute("recipient", recipient)
+        .add_attribute("method", "update_limit_basis")
+        .add_attribute("daily_token_limit", compute_daily_token_limit(&config))
         .add_attribute("admin", info.sender))
 }
 
-fn emergency_withdraw(
+fn update_max_purchase(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    recipient: String,
+    max_purchase_usd: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
-
-    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let mut config = CONFIG.load(deps.storage)?;
 
-    // Get all balances (only native denom is used here)
-    let balance = deps
-        .querier
-        .query_balance(env.contract.address.to_string(), config.native_denom.clone())?;
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
 
-    if balance.amount.is_zero() {
-        return Ok(Response::new()
-            .add_attribute("method", "emergency_withdraw")
-            .add_attribute("message", "no_funds_to_withdraw"));
+    if max_purchase_usd.is_some_and(|max| max.is_zero()) {
+        return Err(ContractError::ZeroAmount {});
     }
 
-    let send_msg = BankMsg::Send {
-        to_address: recipient_addr.to_string(),
-        amount: vec![balance.clone()],
-    };
+    config.max_purchase_usd = max_purchase_usd;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
-        .add_message(send_msg)
-        .add_attribute("method", "emergency_withdraw")
-        .add_attribute("recipient", recipient)
-        .add_attribute("withdrawn_funds", format!("{:?}", balance))
+        .add_attribute("method", "update_max_purchase")
+        .add_attribute(
+            "max_purchase_usd",
+            max_purchase_usd.map_or_else(|| "none".to_string(), |max| max.to_string()),
+        )
         .add_attribute("admin", info.sender))
 }
 
-fn update_pricing_config(
+fn update_max_purchases_per_day(
     deps: DepsMut,
     info: MessageInfo,
-    base_price_usd: Option<Uint128>,
-    tokens_per_tier: Option<Uint128>,
-    tier_multiplier: Option<Uint128>,
+    max_purchases_per_buyer_per_day: Option<u32>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let mut config = CONFIG.load(deps.storage)?;
 
-    if config.admin.is_empty() || info.sender.as_str() != config.admin {
-        return Err(ContractError::Unauthorized {});
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if max_purchases_per_buyer_per_day == Some(0) {
+        return Err(ContractError::ZeroAmount {});
     }
 
-    let mut pricing_config = PRICING_CONFIG.load(deps.storage)?;
+    config.max_purchases_per_buyer_per_day = max_purchases_per_buyer_per_day;
+    CONFIG.save(deps.storage, &config)?;
 
-    if let Some(price) = base_price_usd {
-        if price.is_zero() {
-            return Err(ContractError::ZeroAmount {});
-        }
-        pricing_config.base_price_usd = price;
-    }
+    Ok(Response::new()
+        .add_attribute("method", "update_max_purchases_per_day")
+        .add_attribute(
+            "max_purchases_per_buyer_per_day",
+            max_purchases_per_buyer_per_day.map_or_else(|| "none".to_string(), |max| max.to_string()),
+        )
+        .add_attribute("admin", info.sender))
+}
 
-    if let Some(tokens) = tokens_per_tier {
-        if tokens.is_zero() {
-            return Err(ContractError::ZeroAmount {});
-        }
-        pricing_config.tokens_per_tier = tokens;
-    }
+fn update_auto_pause_price(
+    deps: DepsMut,
+    info: MessageInfo,
+    auto_pause_price_usd: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
 
-    if let Some(multiplier) = tier_multiplier {
-        if multiplier.is_zero() {
-            return Err(ContractError::InvalidExchangeRate {
-                token: "tier_multiplier must be > 0 (1.0x)".to_string(),
-            });
-        }
-        pricing_config.tier_multiplier = multiplier;
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if auto_pause_price_usd.is_some_and(|price| price.is_zero()) {
+        return Err(ContractError::ZeroAmount {});
     }
 
-    PRICING_CONFIG.save(deps.storage, &pricing_config)?;
+    config.auto_pause_price_usd = auto_pause_price_usd;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
-        .add_attribute("method", "update_pricing_config")
+        .add_attribute("method", "update_auto_pause_price")
+        .add_attribute(
+            "auto_pause_price_usd",
+            auto_pause_price_usd.map_or_else(|| "none".to_string(), |price| price.to_string()),
+        )
         .add_attribute("admin", info.sender))
 }
 
-fn add_payment_token(
+fn update_daily_usd_limit(
     deps: DepsMut,
     info: MessageInfo,
-    denom: String,
-    usd_rate: Uint128,
+    daily_usd_limit: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if daily_usd_limit.is_some_and(|limit| limit.is_zero()) {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    config.daily_usd_limit = daily_usd_limit;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_daily_usd_limit")
+        .add_attribute(
+            "daily_usd_limit",
+            daily_usd_limit.map_or_else(|| "none".to_string(), |limit| limit.to_string()),
+        )
+        .add_attribute("admin", info.sender))
+}
+
+fn reset_daily_stats(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     if config.admin.is_empty() || info.sender.as_str() != config.admin {
         return Err(ContractError::Unauthorized {});
     }
 
-    if usd_rate.is_zero() {
-        return Err(ContractError::InvalidExchangeRate { token: denom });
-    }
-
-    // CRITICAL SECURITY CHECK: Verify this is a legitimate bridge token for trading
-    if !validate_wrapped_token_for_trade(deps.as_ref(), &denom)? {
-        return Err(ContractError::TokenNotAccepted {
-            token: format!("Token {} is not a legitimate bridge token approved for trading", denom),
-        });
+    if !config.allow_daily_stats_reset {
+        return Err(ContractError::DailyStatsResetDisabled {});
     }
 
-    // PAYMENT_TOKENS.save(deps.storage, denom.clone(), &usd_rate)?; // This line is removed
+    let cleared = DAILY_STATS.load(deps.storage)?;
+    archive_daily_stats(deps.storage, &cleared)?;
+    let current_day = cleared.day_at(env.block.time.seconds(), config.rollover_grace_seconds);
+    DAILY_STATS.save(
+        deps.storage,
+        &DailyStats {
+            current_day,
+            usd_received_today: Uint128::zero(),
+            tokens_sold_today: Uint128::zero(),
+        },
+    )?;
 
     Ok(Response::new()
-        .add_attribute("method", "add_payment_token")
-        .add_attribute("token", denom)
-        .add_attribute("usd_rate", usd_rate)
-        .add_attribute("bridge_token_validated", "true")
+        .add_attribute("method", "reset_daily_stats")
+        .add_attribute("archived_day", cleared.current_day.to_string())
+        .add_attribute("usd_received_cleared", cleared.usd_received_today)
+        .add_attribute("tokens_sold_cleared", cleared.tokens_sold_today)
         .add_attribute("admin", info.sender))
 }
 
-fn remove_payment_token(
+fn set_verify_received(
     deps: DepsMut,
     info: MessageInfo,
-    denom: String,
+    verify_received: bool,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let mut config = CONFIG.load(deps.storage)?;
 
     if config.admin.is_empty() || info.sender.as_str() != config.admin {
         return Err(ContractError::Unauthorized {});
     }
 
-    // PAYMENT_TOKENS.remove(deps.storage, denom.clone()); // This line is removed
+    config.verify_received = verify_received;
+    CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
-        .add_attribute("method", "remove_payment_token")
-        .add_attribute("token", denom)
+        .add_attribute("method", "set_verify_received")
+        .add_attribute("verify_received", verify_received.to_string())
         .add_attribute("admin", info.sender))
 }
 
-#[entry_point]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        QueryMsg::DailyStats {} => to_json_binary(&query_daily_stats(deps, env)?),
-        QueryMsg::NativeBalance {} => to_json_binary(&query_native_balance(deps, env)?),
-        QueryMsg::PricingInfo {} => to_json_binary(&query_pricing_info(deps)?),
-        QueryMsg::CalculateTokens { usd_amount } => {
-            to_json_binary(&query_calculate_tokens(deps, usd_amount)?)
-        }
-        QueryMsg::TestBridgeValidation { cw20_contract } => {
-            to_json_binary(&query_test_bridge_validation(deps, cw20_contract)?)
-        }
-        QueryMsg::BlockHeight {} => {
-            to_json_binary(&query_block_height(env)?)
-        }
-        QueryMsg::TestApprovedTokens {} => {
-            to_json_binary(&query_test_approved_tokens(deps)?)
-        }
+fn update_rollover_grace(
+    deps: DepsMut,
+    info: MessageInfo,
+    rollover_grace_seconds: u64,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if rollover_grace_seconds > MAX_ROLLOVER_GRACE_SECONDS {
+        return Err(ContractError::InvalidRolloverGrace { seconds: rollover_grace_seconds, max: MAX_ROLLOVER_GRACE_SECONDS });
     }
+
+    config.rollover_grace_seconds = rollover_grace_seconds;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_rollover_grace")
+        .add_attribute("rollover_grace_seconds", rollover_grace_seconds.to_string())
+        .add_attribute("admin", info.sender))
 }
 
-#[entry_point]
-pub fn migrate(
+fn update_total_supply(
     deps: DepsMut,
-    _env: Env,
-    _msg: Binary,
+    info: MessageInfo,
+    total_supply: Uint128,
 ) -> Result<Response, ContractError> {
-    let old = get_contract_version(deps.storage)
-        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(e.to_string())))?;
-    if old.contract != CONTRACT_NAME {
-        return Err(ContractError::Std(StdError::msg(format!(
-            "wrong contract: expected {} got {}",
-            CONTRACT_NAME, old.contract
-        ))));
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    if total_supply < config.total_tokens_sold {
+        return Err(ContractError::InvalidTotalSupply {
+            total_supply,
+            total_tokens_sold: config.total_tokens_sold,
+        });
     }
 
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
-        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(e.to_string())))?;
+    let old_total_supply = config.total_supply;
+    config.total_supply = total_supply;
+    CONFIG.save(deps.storage, &config)?;
 
-    // Update stored native_denom to the correct value from chain
-    // This fixes any incorrect stored values and avoids expensive queries on every execution
+    Ok(Response::new()
+        .add_attribute("method", "update_total_supply")
+        .add_attribute("old_total_supply", old_total_supply)
+        .add_attribute("new_total_supply", total_supply)
+        .add_attribute("admin", info.sender))
+}
+
+fn update_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bp: Uint128,
+    fee_recipient: Option<String>,
+) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
-    let correct_native_denom = get_native_denom(deps.as_ref())?;
-    if config.native_denom != correct_native_denom {
-        config.native_denom = correct_native_denom.clone();
-        CONFIG.save(deps.storage, &config)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    // The buyer must always receive something
+    if fee_bp >= Uint128::from(10000u128) {
+        return Err(ContractError::InvalidBasisPoints { value: fee_bp });
     }
 
+    let fee_recipient = fee_recipient
+        .map(|recipient| deps.api.addr_validate(&recipient).map(|addr| addr.to_string()))
+        .transpose()?;
+
+    config.fee_bp = fee_bp;
+    config.fee_recipient = fee_recipient;
+    CONFIG.save(deps.storage, &config)?;
+
     Ok(Response::new()
-        .add_attribute("action", "migrate")
-        .add_attribute("from_version", old.version)
-        .add_attribute("to_version", CONTRACT_VERSION))
+        .add_attribute("method", "update_fee")
+        .add_attribute("fee_bp", fee_bp)
+        .add_attribute("fee_recipient", config.fee_recipient.unwrap_or_default())
+        .add_attribute("admin", info.sender))
 }
 
-fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    Ok(ConfigResponse {
-        admin: config.admin,
-        native_denom: config.native_denom,
-        daily_limit_bp: config.daily_limit_bp,
-        is_paused: config.is_paused,
-        total_tokens_sold: config.total_tokens_sold,
-    })
-}
+fn update_proceeds_recipient(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_proceeds_recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
 
-fn query_test_bridge_validation(deps: Deps, cw20_contract: String) -> StdResult<TestBridgeValidationResponse> {
-    // Accept either raw cw20 address or prefixed cw20:<addr>
-    let denom = if cw20_contract.starts_with("cw20:") {
-        cw20_contract
-    } else {
-        format!("cw20:{}", cw20_contract)
+    if config.admin.is_empty() || info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.cw20_proceeds_recipient = cw20_proceeds_recipient
+        .map(|recipient| deps.api.addr_validate(&recipient).map(|addr| addr.to_string()))
+        .transpose()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_proceeds_recipient")
+        .add_attribute(
+            "cw20_proceeds_recipient",
+            config.cw20_proceeds_recipient.unwrap_or_else(|| "none".to_string()),
+        )
+        .add_attribute("admin", info.sender))
+}
+
+fn update_purchase_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    purchase_hook: Option<String>,
+    hook_required: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.admin.is_empty() || info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.purchase_hook = purchase_hook
+        .map(|hook| deps.api.addr_validate(&hook).map(|addr| addr.to_string()))
+        .transpose()?;
+    config.hook_required = hook_required;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_purchase_hook")
+        .add_attribute("purchase_hook", config.purchase_hook.unwrap_or_else(|| "none".to_string()))
+        .add_attribute("hook_required", hook_required.to_string())
+        .add_attribute("admin", info.sender))
+}
+
+fn update_event_prefix(deps: DepsMut, info: MessageInfo, event_prefix: Option<String>) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.admin.is_empty() || info.sender.as_str() != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(prefix) = &event_prefix {
+        let valid = !prefix.is_empty()
+            && prefix.len() <= MAX_EVENT_PREFIX_LEN
+            && prefix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+        if !valid {
+            return Err(ContractError::InvalidEventPrefix {
+                prefix: prefix.clone(),
+                max: MAX_EVENT_PREFIX_LEN,
+            });
+        }
+    }
+
+    config.event_prefix = event_prefix;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_event_prefix")
+        .add_attribute("event_prefix", config.event_prefix.unwrap_or_else(|| "none".to_string()))
+        .add_attribute("admin", info.sender))
+}
+
+fn withdraw_native_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    if config.pause_flags.withdrawals {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let send_msg = BankMsg::Send {
+        to_address: recipient_addr.to_string(),
+        amount: vec![Coin {
+            denom: config.native_denom,
+            amount: amount.into(),
+        }],
     };
-    let is_valid = validate_wrapped_token_for_trade(deps, &denom).unwrap_or(false);
-    Ok(TestBridgeValidationResponse { is_valid })
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_attribute("method", "withdraw_native_tokens")
+        .add_attribute("amount", amount)
+        .add_attribute("recipient", recipient)
+        .add_attribute("admin", info.sender))
 }
 
-fn query_block_height(env: Env) -> StdResult<BlockHeightResponse> {
-    Ok(BlockHeightResponse { height: env.block.height })
+fn recover_misdirected_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    if config.pause_flags.withdrawals {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // Only the balance above the unsold supply is excess; tokens still for sale stay put
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.native_denom)?
+        .amount;
+    let unsold = Uint256::from(config.total_supply.checked_sub(config.total_tokens_sold).unwrap_or_default());
+    let excess = balance.checked_sub(unsold).unwrap_or_default();
+    if Uint256::from(amount) > excess {
+        return Err(ContractError::InsufficientBalance {
+            // Below amount here, so it always fits in a u128
+            available: Uint128::try_from(excess).unwrap_or(Uint128::MAX).u128(),
+            needed: amount.u128(),
+        });
+    }
+
+    let send_msg = BankMsg::Send {
+        to_address: recipient_addr.to_string(),
+        amount: vec![Coin {
+            denom: config.native_denom,
+            amount: amount.into(),
+        }],
+    };
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_attribute("method", "recover_misdirected_native")
+        .add_attribute("amount", amount)
+        .add_attribute("excess", excess)
+        .add_attribute("recipient", recipient)
+        .add_attribute("admin", info.sender))
 }
 
-fn query_test_approved_tokens(deps: Deps) -> StdResult<ApprovedTokensForTradeJson> {
-    // Empty request protobuf
-    let decoded: QueryApprovedTokensForTradeResponseProto = query_proto(
-        deps,
-        "/inference.inference.Query/ApprovedTokensForTrade",
-        &EmptyRequest::default(),
-    )?;
-    let approved_tokens = decoded
-        .approved_tokens
-        .into_iter()
-        .map(|t| ApprovedTokenJson { chain_id: t.chain_id, contract_address: t.contract_address })
-        .collect();
-    Ok(ApprovedTokensForTradeJson { approved_tokens })
+fn burn_unsold(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    if config.pause_flags.withdrawals {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let sellable = sellable_balance(deps.as_ref(), &env, &config)?;
+    if Uint256::from(amount) > sellable {
+        return Err(ContractError::InsufficientBalance {
+            // Below amount here, so it always fits in a u128
+            available: Uint128::try_from(sellable).unwrap_or(Uint128::MAX).u128(),
+            needed: amount.u128(),
+        });
+    }
+
+    let burn_msg = BankMsg::Burn {
+        amount: vec![Coin {
+            denom: config.native_denom,
+            amount: amount.into(),
+        }],
+    };
+
+    Ok(Response::new()
+        .add_message(burn_msg)
+        .add_attribute("method", "burn_unsold")
+        .add_attribute("burned", amount)
+        .add_attribute("admin", info.sender))
 }
 
-// Generic helpers for gRPC queries using raw_query serialization pattern
-fn query_grpc(deps: Deps, path: &str, data: Binary) -> StdResult<Binary> {
-    let request = QueryRequest::Grpc(GrpcQuery {
-        path: path.to_string(),
-        data,
-    });
-    query_raw(deps, &request)
+fn withdraw_native_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payouts: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    if config.pause_flags.withdrawals {
+        return Err(ContractError::ContractPaused {});
+    }
+
+    if payouts.is_empty() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if payouts.len() > MAX_BATCH_PAYOUTS {
+        return Err(ContractError::TooManyPayouts { count: payouts.len(), max: MAX_BATCH_PAYOUTS });
+    }
+
+    let mut total = Uint128::zero();
+    let mut messages = Vec::with_capacity(payouts.len());
+    for (recipient, amount) in &payouts {
+        let recipient_addr = deps.api.addr_validate(recipient)?;
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount {});
+        }
+        total = total
+            .checked_add(*amount)
+            .map_err(|e| ContractError::Std(StdError::msg(format!("overflow: {}", e))))?;
+        messages.push(BankMsg::Send {
+            to_address: recipient_addr.to_string(),
+            amount: vec![Coin {
+                denom: config.native_denom.clone(),
+                amount: (*amount).into(),
+            }],
+        });
+    }
+
+    // Check the whole batch up front so it fails with a clear error instead of on a later send
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address.to_string(), config.native_denom.as_str())?
+        .amount;
+    if Uint256::from(total) > balance {
+        return Err(ContractError::InsufficientBalance {
+            available: Uint128::try_from(balance).unwrap_or(Uint128::MAX).u128(),
+            needed: total.u128(),
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "withdraw_native_batch")
+        .add_attribute("payouts", payouts.len().to_string())
+        .add_attribute("total_amount", total)
+        .add_attribute("admin", info.sender))
 }
 
-fn query_raw(deps: Deps, request: &QueryRequest<GrpcQuery>) -> StdResult<Binary> {
-    let raw = to_json_vec(request)
-        .map_err(|e| StdError::msg(format!("Serializing QueryRequest: {e}")))?;
-    match deps.querier.raw_query(&raw) {
-        SystemResult::Err(system_err) => Err(StdError::msg(format!(
-            "Querier system error: {system_err}"
-        ))),
-        SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::msg(
-            format!("Querier contract error: {contract_err}")
-        )),
-        SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+fn emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+
+    // Get all balances (only native denom is used here)
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address.to_string(), config.native_denom.clone())?;
+
+    if balance.amount.is_zero() {
+        return Ok(Response::new()
+            .add_attribute("method", "emergency_withdraw")
+            .add_attribute("message", "no_funds_to_withdraw"));
+    }
+
+    let send_msg = BankMsg::Send {
+        to_address: recipient_addr.to_string(),
+        amount: vec![balance.clone()],
+    };
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_attribute("method", "emergency_withdraw")
+        .add_attribute("recipient", recipient)
+        .add_attribute("withdrawn_funds", format!("{:?}", balance))
+        .add_attribute("admin", info.sender))
+}
+
+fn sweep_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_contract: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    // Swept CW20 goes where purchase proceeds are forwarded; a role admin can't sweep an
+    // admin-less pool with no proceeds recipient
+    let recipient = config
+        .proceeds_destination()
+        .ok_or_else(|| StdError::msg("No admin or proceeds recipient set to receive swept CW20"))?
+        .to_string();
+
+    let cw20_addr = deps.api.addr_validate(&cw20_contract)?;
+    let balance = query_cw20_balance(deps.as_ref(), cw20_addr.as_str(), env.contract.address.as_str())?;
+
+    if balance.is_zero() {
+        return Ok(Response::new()
+            .add_attribute("method", "sweep_cw20")
+            .add_attribute("message", "no_funds_to_sweep"));
+    }
+
+    let transfer_msg = create_cw20_transfer_msg(cw20_addr.to_string(), recipient.clone(), balance)?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg)
+        .add_attribute("method", "sweep_cw20")
+        .add_attribute("cw20_contract", cw20_addr)
+        .add_attribute("swept_amount", balance)
+        .add_attribute("recipient", recipient)
+        .add_attribute("admin", info.sender))
+}
+
+fn force_return_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_contract: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::WITHDRAW)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let cw20_addr = deps.api.addr_validate(&cw20_contract)?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+
+    // Deliberately skips validate_wrapped_token_for_trade: this is for tokens that can no
+    // longer pass it. Leave a loud trace so the bypass is obvious when auditing.
+    deps.api.debug(&format!(
+        "LP: WARNING force_return_cw20 bypassing bridge validation cw20_contract={} recipient={} amount={}",
+        cw20_addr, recipient_addr, amount
+    ));
+
+    let transfer_msg = create_cw20_transfer_msg(cw20_addr.to_string(), recipient_addr.to_string(), amount)?;
+
+    Ok(Response::new()
+        .add_message(transfer_msg)
+        .add_attribute("method", "force_return_cw20")
+        .add_attribute("validation_bypassed", "true")
+        .add_attribute("cw20_contract", cw20_addr)
+        .add_attribute("recipient", recipient_addr)
+        .add_attribute("amount", amount)
+        .add_attribute("admin", info.sender))
+}
+
+/// Reject a multiplier whose first tier step (`base_price_usd * tier_multiplier`) would overflow,
+/// which calculate_current_price would otherwise silently swallow by freezing the price
+fn validate_tier_multiplier(pricing_config: &PricingConfig) -> Result<(), ContractError> {
+    if pricing_config.base_price_usd.checked_mul(pricing_config.tier_multiplier).is_err() {
+        return Err(ContractError::TierMultiplierOverflow {
+            base_price_usd: pricing_config.base_price_usd,
+            tier_multiplier: pricing_config.tier_multiplier,
+        });
+    }
+    Ok(())
+}
+
+fn update_pricing_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    base_price_usd: Option<Uint128>,
+    tokens_per_tier: Option<Uint128>,
+    tier_multiplier: Option<Uint128>,
+    max_tier: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::UPDATE_PRICING)?;
+
+    let mut pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    if let Some(price) = base_price_usd {
+        if price.is_zero() {
+            return Err(ContractError::ZeroAmount {});
+        }
+        pricing_config.base_price_usd = price;
+    }
+
+    if let Some(tokens) = tokens_per_tier {
+        if tokens.is_zero() {
+            return Err(ContractError::ZeroAmount {});
+        }
+        pricing_config.tokens_per_tier = tokens;
+    }
+
+    if let Some(multiplier) = tier_multiplier {
+        if multiplier.is_zero() {
+            return Err(ContractError::InvalidExchangeRate {
+                token: "tier_multiplier must be > 0 (1.0x)".to_string(),
+            });
+        }
+        pricing_config.tier_multiplier = multiplier;
+    }
+
+    if max_tier.is_some() {
+        pricing_config.max_tier = max_tier;
+    }
+
+    // Checked against the resulting config, so lowering the multiplier can fix a raised base price
+    validate_tier_multiplier(&pricing_config)?;
+
+    PRICING_CONFIG.save(deps.storage, &pricing_config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_pricing_config")
+        .add_attribute("admin", info.sender))
+}
+
+fn add_payment_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    usd_rate: Uint128,
+    decimals: Option<u8>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    let decimals = check_payment_token(deps.as_ref(), &config, &denom, usd_rate, decimals)?;
+
+    PAYMENT_TOKENS.save(deps.storage, &denom, &usd_rate)?;
+    let mut response = Response::new()
+        .add_attribute("method", "add_payment_token")
+        .add_attribute("token", denom.clone())
+        .add_attribute("usd_rate", usd_rate);
+    if let Some((decimals, decimals_source)) = decimals {
+        PAYMENT_TOKEN_DECIMALS.save(deps.storage, &denom, &decimals)?;
+        response = response
+            .add_attribute("decimals", decimals.to_string())
+            .add_attribute("decimals_source", decimals_source);
+    }
+
+    Ok(response
+        .add_attribute("bridge_token_validated", "true")
+        .add_attribute("admin", info.sender))
+}
+
+fn add_payment_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<PaymentTokenEntry>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    if tokens.is_empty() || tokens.len() > MAX_BATCH_PAYMENT_TOKENS {
+        return Err(ContractError::InvalidPaymentTokenBatch { count: tokens.len(), max: MAX_BATCH_PAYMENT_TOKENS });
+    }
+
+    // Validate every token before storing any
+    let mut validated = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let decimals = check_payment_token(deps.as_ref(), &config, &token.denom, token.usd_rate, token.decimals)?;
+        validated.push((token.denom, token.usd_rate, decimals));
+    }
+
+    for (denom, usd_rate, decimals) in &validated {
+        PAYMENT_TOKENS.save(deps.storage, denom, usd_rate)?;
+        if let Some((decimals, _)) = decimals {
+            PAYMENT_TOKEN_DECIMALS.save(deps.storage, denom, decimals)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "add_payment_tokens")
+        .add_attribute("tokens_added", validated.len().to_string())
+        .add_attribute("admin", info.sender))
+}
+
+/// Checks `denom` may be accepted at `usd_rate` and returns its decimals with where they came from
+fn check_payment_token(
+    deps: Deps,
+    config: &Config,
+    denom: &str,
+    usd_rate: Uint128,
+    decimals: Option<u8>,
+) -> Result<Option<(u8, &'static str)>, ContractError> {
+    if usd_rate.is_zero() {
+        return Err(ContractError::InvalidExchangeRate { token: denom.to_string() });
+    }
+
+    // The sold denom can't be used to pay for itself
+    if denom == config.native_denom {
+        return Err(ContractError::TokenNotAccepted { token: denom.to_string() });
+    }
+
+    // CRITICAL SECURITY CHECK: Verify this is a legitimate bridge token for trading
+    if !validate_wrapped_token_for_trade(deps, denom)? {
+        return Err(ContractError::TokenNotAccepted {
+            token: format!("Token {} is not a legitimate bridge token approved for trading", denom),
+        });
+    }
+
+    // The token's own TokenInfo wins over a hand-entered value; with neither, none is recorded
+    Ok(query_cw20_decimals(deps, denom)
+        .map(|queried| (queried, "token_info"))
+        .or(decimals.map(|given| (given, "explicit"))))
+}
+
+fn remove_payment_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    PAYMENT_TOKENS.remove(deps.storage, &denom);
+    PAYMENT_TOKEN_DECIMALS.remove(deps.storage, &denom);
+
+    Ok(Response::new()
+        .add_attribute("method", "remove_payment_token")
+        .add_attribute("token", denom)
+        .add_attribute("admin", info.sender))
+}
+
+fn set_require_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    require_allowlist: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    config.require_allowlist = require_allowlist;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_require_allowlist")
+        .add_attribute("require_allowlist", require_allowlist.to_string())
+        .add_attribute("admin", info.sender))
+}
+
+fn update_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    let method = if allowed {
+        ALLOWLIST.save(deps.storage, &addr, &true)?;
+        "add_to_allowlist"
+    } else {
+        ALLOWLIST.remove(deps.storage, &addr);
+        "remove_from_allowlist"
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", method)
+        .add_attribute("address", addr)
+        .add_attribute("admin", info.sender))
+}
+
+fn update_trusted_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    trusted: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    let addr = deps.api.addr_validate(&address)?;
+    let method = if trusted {
+        TRUSTED_CW20S.save(deps.storage, &addr, &true)?;
+        "add_trusted_cw20"
+    } else {
+        TRUSTED_CW20S.remove(deps.storage, &addr);
+        "remove_trusted_cw20"
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", method)
+        .add_attribute("address", addr)
+        .add_attribute("admin", info.sender))
+}
+
+fn set_trust_cache_only(
+    deps: DepsMut,
+    info: MessageInfo,
+    trust_cache_only: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    check_capability(deps.as_ref(), &config, &info.sender, AdminRole::MANAGE_TOKENS)?;
+
+    config.trust_cache_only = trust_cache_only;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_trust_cache_only")
+        .add_attribute("trust_cache_only", trust_cache_only.to_string())
+        .add_attribute("admin", info.sender))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps, env)?),
+        QueryMsg::Paused {} => to_json_binary(&query_paused(deps, env)?),
+        QueryMsg::DailyStats {} => to_json_binary(&query_daily_stats(deps, env)?),
+        QueryMsg::DailyStatsForDay { day } => to_json_binary(&query_daily_stats_for_day(deps, day)?),
+        QueryMsg::ReceivedFrom { buyer } => to_json_binary(&query_received_from(deps, buyer)?),
+        QueryMsg::AdminRole { address } => to_json_binary(&query_admin_role(deps, address)?),
+        QueryMsg::IsAllowlisted { address } => to_json_binary(&query_is_allowlisted(deps, address)?),
+        QueryMsg::BuyersPurchased { addresses } => to_json_binary(&query_buyers_purchased(deps, addresses)?),
+        QueryMsg::PurchasesToday { buyer } => to_json_binary(&query_purchases_today(deps, env, buyer)?),
+        QueryMsg::LastPurchaseId {} => to_json_binary(&query_last_purchase_id(deps)?),
+        QueryMsg::TrustedCw20s {} => to_json_binary(&query_trusted_cw20s(deps)?),
+        QueryMsg::NativeBalance {} => to_json_binary(&query_native_balance(deps, env)?),
+        QueryMsg::SaleProgress {} => to_json_binary(&query_sale_progress(deps, env)?),
+        QueryMsg::SellableBalance {} => to_json_binary(&query_sellable_balance(deps, env)?),
+        QueryMsg::InventoryValueUsd {} => to_json_binary(&query_inventory_value_usd(deps, env)?),
+        QueryMsg::PricingInfo {} => to_json_binary(&query_pricing_info(deps)?),
+        QueryMsg::Twap { lookback_seconds } => to_json_binary(&query_twap(deps, env, lookback_seconds)?),
+        QueryMsg::PricingInfoHuman {} => to_json_binary(&query_pricing_info_human(deps)?),
+        QueryMsg::TierSales { start_tier, limit } => {
+            to_json_binary(&query_tier_sales(deps, start_tier, limit)?)
+        }
+        QueryMsg::TierBoundaries { count } => to_json_binary(&query_tier_boundaries(deps, count)?),
+        QueryMsg::UsdToNextTier {} => to_json_binary(&query_usd_to_next_tier(deps)?),
+        QueryMsg::CalculateTokensClamped { usd_amount } => {
+            to_json_binary(&query_calculate_tokens_clamped(deps, env, usd_amount)?)
+        }
+        QueryMsg::CostForTokens { token_amount } => to_json_binary(&query_cost_for_tokens(deps, token_amount)?),
+        QueryMsg::CalculateTokens { usd_amount } => {
+            to_json_binary(&query_calculate_tokens(deps, usd_amount)?)
+        }
+        QueryMsg::PaymentTokens {} => to_json_binary(&query_payment_tokens(deps)?),
+        QueryMsg::QuoteForCw20 { cw20_contract, amount } => {
+            to_json_binary(&query_quote_for_cw20(deps, env, cw20_contract, amount)?)
+        }
+        QueryMsg::CanPurchase { buyer, cw20_contract, amount } => {
+            to_json_binary(&query_can_purchase(deps, env, buyer, cw20_contract, amount))
+        }
+        QueryMsg::RejectionStats {} => to_json_binary(&query_rejection_stats(deps)?),
+        QueryMsg::ForwardedTotals {} => to_json_binary(&query_forwarded_totals(deps)?),
+        QueryMsg::TestBridgeValidation { cw20_contract } => {
+            to_json_binary(&query_test_bridge_validation(deps, cw20_contract)?)
+        }
+        #[cfg(feature = "debug_grpc")]
+        QueryMsg::RawGrpc { path, data_base64 } => to_json_binary(&query_raw_grpc(deps, &path, &data_base64)?),
+        QueryMsg::BlockHeight {} => {
+            to_json_binary(&query_block_height(env)?)
+        }
+        QueryMsg::ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
+        QueryMsg::TestApprovedTokens {} => {
+            to_json_binary(&query_test_approved_tokens(deps)?)
+        }
+        QueryMsg::ApprovedTokensWithStatus {} => to_json_binary(&query_approved_tokens_with_status(deps)?),
+    }
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        (FORWARD_CW20_REPLY_ID, SubMsgResult::Err(reason)) => {
+            // Still revert the purchase, but with an error naming the forward rather than the bare CW20 failure
+            let payload: ForwardCw20Payload = from_json(&msg.payload)?;
+            deps.api.debug(&format!(
+                "LP: CW20 forward to proceeds recipient failed cw20={} amount={} reason={}",
+                payload.cw20_contract, payload.amount, reason
+            ));
+            Err(ContractError::Cw20ForwardFailed {
+                cw20_contract: payload.cw20_contract,
+                amount: payload.amount,
+                reason,
+            })
+        }
+        (PURCHASE_HOOK_REPLY_ID, SubMsgResult::Err(reason)) => {
+            // Optional hook: the purchase stands, only the hook's own effects were rolled back
+            deps.api.debug(&format!("LP: optional purchase hook failed reason={}", reason));
+            let config = CONFIG.load(deps.storage)?;
+            let res = Response::new().add_attribute("purchase_hook_failed", reason);
+            Ok(with_event_prefix(res, config.event_prefix.as_deref()))
+        }
+        (id, _) => Err(ContractError::Std(StdError::msg(format!("unknown reply id: {}", id)))),
+    }
+}
+
+#[entry_point]
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    _msg: Binary,
+) -> Result<Response, ContractError> {
+    let old = get_contract_version(deps.storage)
+        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(e.to_string())))?;
+    if old.contract != CONTRACT_NAME {
+        return Err(ContractError::Std(StdError::msg(format!(
+            "wrong contract: expected {} got {}",
+            CONTRACT_NAME, old.contract
+        ))));
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
+        .map_err(|e| ContractError::Std(cosmwasm_std::StdError::msg(e.to_string())))?;
+
+    // Convert configs stored before pause flags existed
+    let mut config = match CONFIG.load(deps.storage) {
+        Ok(config) => config,
+        Err(_) => {
+            let config: Config = LEGACY_CONFIG.load(deps.storage)?.into();
+            CONFIG.save(deps.storage, &config)?;
+            config
+        }
+    };
+
+    // Update stored native_denom to the correct value from chain
+    // This fixes any incorrect stored values and avoids expensive queries on every execution
+    let correct_native_denom = get_native_denom(deps.as_ref(), &config.fallback_denom)?;
+    if config.native_denom != correct_native_denom {
+        config.native_denom = correct_native_denom.clone();
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    // The single admin carries over as the super-admin; no other admin holds a role until granted
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("super_admin", config.admin)
+        .add_attribute("from_version", old.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    // Report the effective pause: a lapsed purchases pause reads as resumed
+    let purchases_paused = config.purchases_paused(env.block.time.seconds());
+    Ok(ConfigResponse {
+        admin: config.admin,
+        native_denom: config.native_denom,
+        daily_limit_bp: config.daily_limit_bp,
+        is_paused: purchases_paused,
+        pause_flags: PauseFlags { purchases: purchases_paused, ..config.pause_flags },
+        auto_resume_after: config.purchases_auto_resume_at.filter(|_| purchases_paused),
+        total_tokens_sold: config.total_tokens_sold,
+        fee_bp: config.fee_bp,
+        fee_recipient: config.fee_recipient,
+        native_decimals: config.native_decimals,
+        require_allowlist: config.require_allowlist,
+        rollover_grace_seconds: config.rollover_grace_seconds,
+        limit_basis: config.limit_basis,
+        circulating_supply: config.circulating_supply,
+        max_purchase_usd: config.max_purchase_usd,
+        trust_cache_only: config.trust_cache_only,
+        auto_pause_price_usd: config.auto_pause_price_usd,
+        daily_usd_limit: config.daily_usd_limit,
+        verify_received: config.verify_received,
+        allow_daily_stats_reset: config.allow_daily_stats_reset,
+        max_purchases_per_buyer_per_day: config.max_purchases_per_buyer_per_day,
+        cw20_proceeds_recipient: config.cw20_proceeds_recipient,
+        purchase_hook: config.purchase_hook,
+        hook_required: config.hook_required,
+        event_prefix: config.event_prefix,
+    })
+}
+
+fn query_admin_role(deps: Deps, address: String) -> StdResult<AdminRoleResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let addr = deps.api.addr_validate(&address)?;
+    if !config.admin.is_empty() && addr.as_str() == config.admin {
+        return Ok(AdminRoleResponse { super_admin: true, role: AdminRole::ALL });
+    }
+    let role = ADMIN_ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+    Ok(AdminRoleResponse { super_admin: false, role })
+}
+
+fn query_is_allowlisted(deps: Deps, address: String) -> StdResult<IsAllowlistedResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let allowlisted = ALLOWLIST.may_load(deps.storage, &addr)?.unwrap_or(false);
+    Ok(IsAllowlistedResponse { allowlisted })
+}
+
+fn query_buyers_purchased(deps: Deps, addresses: Vec<String>) -> StdResult<BuyersPurchasedResponse> {
+    if addresses.len() > MAX_LIMIT as usize {
+        return Err(StdError::msg(format!("at most {} addresses per query", MAX_LIMIT)));
+    }
+    let buyers = addresses
+        .into_iter()
+        .map(|address| {
+            let addr = deps.api.addr_validate(&address)?;
+            let totals = BUYER_TOTALS.may_load(deps.storage, &addr)?.unwrap_or_default();
+            Ok(BuyerPurchased { address, total_tokens: totals.tokens, total_usd: totals.usd })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(BuyersPurchasedResponse { buyers })
+}
+
+fn query_purchases_today(deps: Deps, env: Env, buyer: String) -> StdResult<PurchasesTodayResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let buyer_addr = deps.api.addr_validate(&buyer)?;
+    let day = purchase_day(deps.storage, &env, &config)?;
+    Ok(PurchasesTodayResponse {
+        purchases: purchases_on_day(deps.storage, &buyer_addr, day)?,
+        max_purchases_per_buyer_per_day: config.max_purchases_per_buyer_per_day,
+    })
+}
+
+fn query_last_purchase_id(deps: Deps) -> StdResult<LastPurchaseIdResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(LastPurchaseIdResponse { purchase_id: config.purchase_id })
+}
+
+fn query_trusted_cw20s(deps: Deps) -> StdResult<TrustedCw20sResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let addresses = TRUSTED_CW20S
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|addr| addr.map(|addr| addr.to_string()))
+        .collect::<StdResult<_>>()?;
+    Ok(TrustedCw20sResponse { addresses, trust_cache_only: config.trust_cache_only })
+}
+
+fn query_paused(deps: Deps, env: Env) -> StdResult<bool> {
+    Ok(CONFIG.load(deps.storage)?.purchases_paused(env.block.time.seconds()))
+}
+
+#[cfg(feature = "debug_grpc")]
+fn query_raw_grpc(deps: Deps, path: &str, data_base64: &str) -> StdResult<Binary> {
+    let data = Binary::from_base64(data_base64)?;
+    query_grpc(deps, path, data).map_err(|e| StdError::msg(e.to_string()))
+}
+
+fn query_test_bridge_validation(deps: Deps, cw20_contract: String) -> StdResult<TestBridgeValidationResponse> {
+    // Accept either raw cw20 address or prefixed cw20:<addr>
+    let denom = if cw20_contract.starts_with("cw20:") {
+        cw20_contract
+    } else {
+        format!("cw20:{}", cw20_contract)
+    };
+    // Malformed addresses and failed chain queries both report as not valid; the latter with the cause
+    let (is_valid, error) = match validate_wrapped_token_for_trade(deps, &denom) {
+        Ok(is_valid) => (is_valid, None),
+        Err(ContractError::Grpc(e)) => (false, Some(e)),
+        Err(_) => (false, None),
+    };
+    Ok(TestBridgeValidationResponse { is_valid, error })
+}
+
+fn query_block_height(env: Env) -> StdResult<BlockHeightResponse> {
+    Ok(BlockHeightResponse { height: env.block.height })
+}
+
+fn query_test_approved_tokens(deps: Deps) -> StdResult<ApprovedTokensForTradeJson> {
+    // Empty request protobuf
+    let decoded: QueryApprovedTokensForTradeResponseProto = query_proto(
+        deps,
+        "/inference.inference.Query/ApprovedTokensForTrade",
+        &EmptyRequest::default(),
+    )
+    .map_err(|e| StdError::msg(e.to_string()))?;
+    let approved_tokens = decoded
+        .approved_tokens
+        .into_iter()
+        .map(|t| ApprovedTokenJson { chain_id: t.chain_id, contract_address: t.contract_address })
+        .collect();
+    Ok(ApprovedTokensForTradeJson { approved_tokens })
+}
+
+fn query_approved_tokens_with_status(deps: Deps) -> StdResult<ApprovedTokensWithStatusResponse> {
+    let approved = query_test_approved_tokens(deps)?.approved_tokens;
+    let truncated = approved.len() > MAX_LIMIT as usize;
+
+    // One validation gRPC per token, so the list is bounded
+    let tokens = approved
+        .into_iter()
+        .take(MAX_LIMIT as usize)
+        .map(|token| {
+            // The chain validates CW20s on this chain; an external-chain address can't be checked
+            let validation = if is_cw20_identifier(deps, &token.contract_address) {
+                validate_wrapped_token_for_trade(deps, &token.contract_address)
+            } else {
+                Err(ContractError::InvalidToken {
+                    token: format!("{} is not a CW20 address on this chain", token.contract_address),
+                })
+            };
+            let (is_valid, error) = match validation {
+                Ok(is_valid) => (is_valid, None),
+                Err(e) => (false, Some(e.to_string())),
+            };
+            ApprovedTokenStatus {
+                chain_id: token.chain_id,
+                contract_address: token.contract_address,
+                is_valid,
+                error,
+            }
+        })
+        .collect();
+
+    Ok(ApprovedTokensWithStatusResponse { tokens, truncated })
+}
+
+// Generic helpers for gRPC queries using raw_query serialization pattern
+fn query_grpc(deps: Deps, path: &str, data: Binary) -> Result<Binary, ContractError> {
+    let request = QueryRequest::Grpc(GrpcQuery {
+        path: path.to_string(),
+        data,
+    });
+    // Name the path so it's clear which of several gRPC queries failed
+    query_raw(deps, &request).map_err(|e| match e {
+        ContractError::Grpc(err) => ContractError::Grpc(GrpcQueryError {
+            message: format!("{}: {}", path, err.message),
+            ..err
+        }),
+        other => other,
+    })
+}
+
+fn query_raw(deps: Deps, request: &QueryRequest<GrpcQuery>) -> Result<Binary, ContractError> {
+    let raw = to_json_vec(request)
+        .map_err(|e| StdError::msg(format!("Serializing QueryRequest: {e}")))?;
+    match deps.querier.raw_query(&raw) {
+        SystemResult::Err(system_err) => Err(ContractError::Grpc(GrpcQueryError::new(
+            GrpcErrorKind::System,
+            format!("Querier system error: {system_err}"),
+        ))),
+        SystemResult::Ok(ContractResult::Err(contract_err)) => Err(ContractError::Grpc(GrpcQueryError::new(
+            GrpcErrorKind::Contract,
+            format!("Querier contract error: {contract_err}"),
+        ))),
+        SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+    }
+}
+
+// Generic helper: encode request proto and decode response proto
+fn query_proto<TRequest, TResponse>(deps: Deps, path: &str, request: &TRequest) -> Result<TResponse, ContractError>
+where
+    TRequest: prost::Message,
+    TResponse: prost::Message + Default,
+{
+    let mut buf = Vec::new();
+    request
+        .encode(&mut buf)
+        .map_err(|e| StdError::msg(format!("Encode request for {}: {}", path, e)))?;
+    let bytes = query_grpc(deps, path, Binary::from(buf))?;
+    Ok(TResponse::decode(bytes.as_slice())
+        .map_err(|e| StdError::msg(format!("Decode response from {}: {}", path, e)))?)
+}
+
+fn query_daily_stats(deps: Deps, env: Env) -> StdResult<DailyStatsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut daily_stats = DAILY_STATS.load(deps.storage)?;
+
+    let current_day = daily_stats.day_at(env.block.time.seconds(), config.rollover_grace_seconds);
+
+    // Reset if new day
+    if daily_stats.current_day != current_day {
+        daily_stats.current_day = current_day;
+        daily_stats.usd_received_today = Uint128::zero();
+        daily_stats.tokens_sold_today = Uint128::zero();
+    }
+
+    let daily_token_limit = compute_daily_token_limit(&config);
+
+    let tokens_available_today = daily_token_limit
+        .checked_sub(daily_stats.tokens_sold_today)
+        .unwrap_or_default();
+
+    let daily_limit_used_bp = if daily_token_limit.is_zero() {
+        Uint128::zero()
+    } else {
+        daily_stats
+            .tokens_sold_today
+            .multiply_ratio(10000u128, daily_token_limit)
+    };
+
+    Ok(DailyStatsResponse {
+        current_day: daily_stats.current_day,
+        usd_received_today: daily_stats.usd_received_today,
+        tokens_sold_today: daily_stats.tokens_sold_today,
+        tokens_available_today,
+        daily_token_limit,
+        total_supply: config.total_supply,
+        daily_limit_used_bp,
+        configured: !config.total_supply.is_zero(),
+        usd_available_today: daily_usd_available(&config, daily_stats.usd_received_today),
+    })
+}
+
+// Daily token limit derived from the current config (saturates to zero on overflow)
+fn compute_daily_token_limit(config: &Config) -> Uint128 {
+    config
+        .daily_limit_base()
+        .checked_mul(config.daily_limit_bp)
+        .map(|x| x.checked_div(Uint128::from(10000u128)).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+fn query_daily_stats_for_day(deps: Deps, day: u64) -> StdResult<DailyStatsForDayResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let current = DAILY_STATS.load(deps.storage)?;
+
+    // The live record covers its own day; earlier days come from the archive
+    let stats = if current.current_day == day {
+        Some(current)
+    } else {
+        DAILY_STATS_HISTORY.may_load(deps.storage, day)?
+    };
+
+    let (usd_received, tokens_sold) = stats
+        .map(|s| (s.usd_received_today, s.tokens_sold_today))
+        .unwrap_or_default();
+
+    Ok(DailyStatsForDayResponse {
+        day,
+        usd_received,
+        tokens_sold,
+        daily_token_limit: compute_daily_token_limit(&config),
+    })
+}
+
+fn query_received_from(deps: Deps, buyer: String) -> StdResult<ReceivedFromResponse> {
+    let buyer_addr = deps.api.addr_validate(&buyer)?;
+    let received = RECEIVED_FROM
+        .prefix(&buyer_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(cw20_contract, amount)| Cw20Amount { cw20_contract, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ReceivedFromResponse { buyer, received })
+}
+
+fn query_payment_tokens(deps: Deps) -> StdResult<PaymentTokensResponse> {
+    let tokens = PAYMENT_TOKENS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    let decimals = PAYMENT_TOKEN_DECIMALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    Ok(PaymentTokensResponse { tokens, decimals })
+}
+
+fn query_forwarded_totals(deps: Deps) -> StdResult<ForwardedTotalsResponse> {
+    let totals = FORWARDED_TOTALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    Ok(ForwardedTotalsResponse { totals })
+}
+
+fn query_rejection_stats(deps: Deps) -> StdResult<RejectionStatsResponse> {
+    let reasons = REJECTION_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+    Ok(RejectionStatsResponse { reasons })
+}
+
+fn query_native_balance(deps: Deps, env: Env) -> StdResult<NativeBalanceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.native_denom)?;
+
+    Ok(NativeBalanceResponse { balance })
+}
+
+fn query_sellable_balance(deps: Deps, env: Env) -> StdResult<SellableBalanceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let sellable = sellable_balance(deps, &env, &config)?;
+    Ok(SellableBalanceResponse { sellable })
+}
+
+// Values every sellable token at today's tier price, ignoring that later tiers price higher
+fn query_inventory_value_usd(deps: Deps, env: Env) -> StdResult<InventoryValueUsdResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+    let sellable = sellable_balance(deps, &env, &config)?;
+
+    let current_price_usd = calculate_current_price(
+        pricing_config.base_price_usd,
+        calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier),
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+    let value_usd = sellable.checked_mul(Uint256::from(current_price_usd))? / Uint256::from(native_scale(config.native_decimals));
+
+    Ok(InventoryValueUsdResponse { sellable, current_price_usd, value_usd })
+}
+
+fn query_pricing_info(deps: Deps) -> StdResult<PricingInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
+    let current_price = calculate_current_price(
+        pricing_config.base_price_usd,
+        current_tier,
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+
+    // Calculate next tier info - token count needed for next tier
+    let next_tier_at = pricing_config.tokens_per_tier.checked_mul(Uint128::from((current_tier + 1) as u128)).unwrap_or(Uint128::zero());
+    let next_tier_price = calculate_current_price(
+        pricing_config.base_price_usd,
+        current_tier + 1,
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+
+    Ok(PricingInfoResponse {
+        current_tier,
+        current_price_usd: current_price,
+        total_tokens_sold: config.total_tokens_sold,
+        tokens_per_tier: pricing_config.tokens_per_tier,
+        base_price_usd: pricing_config.base_price_usd,
+        tier_multiplier: pricing_config.tier_multiplier,
+        max_tier: pricing_config.max_tier,
+        next_tier_at,
+        next_tier_price,
+        total_usd_received: config.total_usd_received,
+        lifetime_average_price: calculate_average_price(config.total_usd_received, config.total_tokens_sold, config.native_decimals)?,
+        configured: !config.total_supply.is_zero(),
+    })
+}
+
+fn query_twap(deps: Deps, env: Env, lookback_seconds: u64) -> StdResult<TwapResponse> {
+    if lookback_seconds == 0 || lookback_seconds > MAX_TWAP_LOOKBACK_SECONDS {
+        return Err(StdError::msg(format!(
+            "lookback_seconds must be between 1 and {}",
+            MAX_TWAP_LOOKBACK_SECONDS
+        )));
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if config.twap_last_update == 0 {
+        return Err(StdError::msg("No purchases recorded yet"));
+    }
+
+    // Accumulator extended from the last observation to now
+    let now = env.block.time.seconds().max(config.twap_last_update);
+    let cumulative_at = |observed_at: u64, observation: &TwapObservation, at: u64| -> StdResult<Uint256> {
+        let elapsed = Uint256::from(at - observed_at);
+        Ok(observation.price_cumulative.checked_add(Uint256::from(observation.price).checked_mul(elapsed)?)?)
+    };
+    let latest = TwapObservation { price_cumulative: config.twap_price_cumulative, price: config.twap_last_price };
+    let cumulative_now = cumulative_at(config.twap_last_update, &latest, now)?;
+
+    // Start from the observation in effect at the window start, or the first one if the window predates it
+    let wanted_start = now.saturating_sub(lookback_seconds);
+    let (start, cumulative_start) = match TWAP_OBSERVATIONS
+        .range(deps.storage, None, Some(Bound::inclusive(wanted_start)), Order::Descending)
+        .next()
+        .transpose()?
+    {
+        Some((observed_at, observation)) => (wanted_start, cumulative_at(observed_at, &observation, wanted_start)?),
+        None => {
+            let (first_at, first) = TWAP_OBSERVATIONS
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .transpose()?
+                .ok_or_else(|| StdError::msg("No purchases recorded yet"))?;
+            (first_at, first.price_cumulative)
+        }
+    };
+
+    let window_seconds = now - start;
+    if window_seconds == 0 {
+        return Ok(TwapResponse { twap_price_usd: config.twap_last_price, window_seconds });
+    }
+    let average = (cumulative_now - cumulative_start) / Uint256::from(window_seconds);
+    Ok(TwapResponse {
+        // An average of Uint128 prices always fits back into a Uint128
+        twap_price_usd: Uint128::try_from(average)?,
+        window_seconds,
+    })
+}
+
+fn query_sale_progress(deps: Deps, env: Env) -> StdResult<SaleProgressResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
+    let current_price_usd = calculate_current_price(
+        pricing_config.base_price_usd,
+        current_tier,
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+    let percent_sold_bp = if config.total_supply.is_zero() {
+        Uint128::zero()
+    } else {
+        config.total_tokens_sold.multiply_ratio(10000u128, config.total_supply)
+    };
+    let native_balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.native_denom)?
+        .amount;
+
+    Ok(SaleProgressResponse {
+        total_supply: config.total_supply,
+        total_tokens_sold: config.total_tokens_sold,
+        percent_sold_bp,
+        current_tier,
+        current_price_usd,
+        native_balance,
+    })
+}
+
+fn query_pricing_info_human(deps: Deps) -> StdResult<PricingInfoHumanResponse> {
+    let info = query_pricing_info(deps)?;
+    let token_decimals = u32::from(CONFIG.load(deps.storage)?.native_decimals);
+
+    Ok(PricingInfoHumanResponse {
+        current_tier: info.current_tier,
+        current_price_usd_display: format_decimal(info.current_price_usd, USD_DECIMALS),
+        total_tokens_sold_display: format_decimal(info.total_tokens_sold, token_decimals),
+        tokens_per_tier_display: format_decimal(info.tokens_per_tier, token_decimals),
+        base_price_usd_display: format_decimal(info.base_price_usd, USD_DECIMALS),
+        tier_multiplier_display: format_decimal(info.tier_multiplier, MULTIPLIER_DECIMALS),
+        next_tier_at_display: format_decimal(info.next_tier_at, token_decimals),
+        next_tier_price_display: format_decimal(info.next_tier_price, USD_DECIMALS),
+        total_usd_received_display: format_decimal(info.total_usd_received, USD_DECIMALS),
+        lifetime_average_price_display: format_decimal(info.lifetime_average_price, USD_DECIMALS),
+    })
+}
+
+fn query_tier_boundaries(deps: Deps, count: u32) -> StdResult<TierBoundariesResponse> {
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+    let count = count.min(MAX_LIMIT);
+
+    let tiers = (0..count)
+        .map(|tier| {
+            Ok(TierBoundary {
+                tier,
+                starts_at_tokens: pricing_config.tokens_per_tier.checked_mul(Uint128::from(tier))?,
+                price_usd: calculate_current_price(
+                    pricing_config.base_price_usd,
+                    tier,
+                    pricing_config.tier_multiplier,
+                    pricing_config.max_tier,
+                )?,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(TierBoundariesResponse { tiers })
+}
+
+fn query_tier_sales(deps: Deps, start_tier: Option<u32>, limit: Option<u32>) -> StdResult<TierSalesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_tier.map(Bound::inclusive);
+
+    // Read one past the page so the last page can report no cursor
+    let mut tiers = TIER_SALES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| item.map(|(tier, tokens_sold)| TierSale { tier, tokens_sold }))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_tier = if tiers.len() > limit {
+        tiers.truncate(limit);
+        // start_tier is inclusive, so the cursor is the tier after the last one returned
+        tiers.last().map(|t| t.tier + 1)
+    } else {
+        None
+    };
+
+    Ok(TierSalesResponse { tiers, next_start_tier })
+}
+
+fn query_usd_to_next_tier(deps: Deps) -> StdResult<UsdToNextTierResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
+    let current_price = calculate_current_price(
+        pricing_config.base_price_usd,
+        current_tier,
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+    let (tokens_remaining_in_tier, usd_to_next_tier) = calculate_tier_remainder(
+        config.total_tokens_sold,
+        pricing_config.tokens_per_tier,
+        current_price,
+        config.native_decimals,
+    )?;
+
+    Ok(UsdToNextTierResponse {
+        current_tier,
+        current_price_usd: current_price,
+        usd_to_next_tier,
+        tokens_remaining_in_tier,
+    })
+}
+
+fn query_can_purchase(deps: Deps, env: Env, buyer: String, cw20_contract: String, amount: Uint128) -> CanPurchaseResponse {
+    match check_cw20_purchase(deps, &env, &buyer, &cw20_contract, amount) {
+        Ok(tokens_out) => CanPurchaseResponse { ok: true, reason: None, tokens_out },
+        Err(e) => CanPurchaseResponse { ok: false, reason: Some(e.to_string()), tokens_out: Uint128::zero() },
+    }
+}
+
+/// The checks receive_cw20 and process_purchase run, in the same order and with the same errors,
+/// without writing state. Returns the buyer's share of the purchased tokens.
+fn check_cw20_purchase(
+    deps: Deps,
+    env: &Env,
+    buyer: &str,
+    cw20_contract: &str,
+    amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    if config.purchases_paused(env.block.time.seconds()) {
+        return Err(ContractError::ContractPaused {});
+    }
+    if config.total_supply.is_zero() {
+        return Err(ContractError::TotalSupplyNotConfigured {});
+    }
+
+    let cw20_addr = deps.api.addr_validate(cw20_contract)?;
+    if !is_cw20_accepted(deps, &config, &cw20_addr)? {
+        return Err(ContractError::TokenNotAccepted {
+            token: format!("CW20 contract {} is not a legitimate bridge token approved for trading", cw20_addr),
+        });
+    }
+    let buyer_addr = deps.api.addr_validate(buyer)?;
+    check_allowlist(deps, &config, buyer)?;
+    let day = purchase_day(deps.storage, env, &config)?;
+    check_purchases_today(&config, purchases_on_day(deps.storage, &buyer_addr, day)?)?;
+
+    // 1 wrapped-token unit = 1 micro-USD, as in receive_cw20
+    let usd_value = amount;
+    if usd_value.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    check_max_purchase(&config, usd_value)?;
+
+    let (tokens_to_buy, actual_usd_to_spend, _, _, _) =
+        calculate_multi_tier_purchase(usd_value, config.total_tokens_sold, &pricing_config, config.native_decimals)?;
+    if actual_usd_to_spend != usd_value {
+        return Err(ContractError::Std(StdError::msg(format!(
+            "Cannot process full USD amount: requested {}, can only process {}",
+            usd_value, actual_usd_to_spend
+        ))));
+    }
+    if tokens_to_buy.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let daily_stats = DAILY_STATS.load(deps.storage)?;
+    let (sold_today, received_today) =
+        if daily_stats.current_day == daily_stats.day_at(env.block.time.seconds(), config.rollover_grace_seconds) {
+            (daily_stats.tokens_sold_today, daily_stats.usd_received_today)
+        } else {
+            (Uint128::zero(), Uint128::zero())
+        };
+    let tokens_available_today = compute_daily_token_limit(&config).checked_sub(sold_today).unwrap_or_default();
+    if tokens_to_buy > tokens_available_today {
+        return Err(ContractError::DailyLimitExceeded {
+            available: tokens_available_today.u128(),
+            requested: tokens_to_buy.u128(),
+        });
+    }
+    check_daily_usd_limit(&config, received_today, usd_value)?;
+
+    let balance = sellable_balance(deps, env, &config)?;
+    if Uint256::from(tokens_to_buy) > balance {
+        return Err(ContractError::InsufficientBalance {
+            available: Uint128::try_from(balance).unwrap_or(Uint128::MAX).u128(),
+            needed: tokens_to_buy.u128(),
+        });
+    }
+
+    let fee_amount = if config.fee_recipient.is_some() {
+        tokens_to_buy.multiply_ratio(config.fee_bp, 10000u128)
+    } else {
+        Uint128::zero()
+    };
+    Ok(tokens_to_buy - fee_amount)
+}
+
+fn query_quote_for_cw20(deps: Deps, env: Env, cw20_contract: String, amount: Uint128) -> StdResult<QuoteForCw20Response> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let cw20_addr = deps.api.addr_validate(&cw20_contract)?;
+    let accepted = is_cw20_accepted(deps, &config, &cw20_addr).map_err(|e| StdError::msg(e.to_string()))?;
+    if !accepted {
+        return Err(StdError::msg(
+            ContractError::TokenNotAccepted {
+                token: format!("CW20 contract {} is not a legitimate bridge token approved for trading", cw20_addr),
+            }
+            .to_string(),
+        ));
+    }
+
+    // Same conversion as receive_cw20: wrapped tokens are 6-decimal USD pegs, 1 unit = 1 micro-USD
+    let usd_value = amount;
+    let (tokens, _, _, _, _) =
+        calculate_multi_tier_purchase(usd_value, config.total_tokens_sold, &pricing_config, config.native_decimals)?;
+
+    // A new day starts with nothing sold, as process_purchase would reset it
+    let daily_stats = DAILY_STATS.load(deps.storage)?;
+    let sold_today = if daily_stats.current_day == daily_stats.day_at(env.block.time.seconds(), config.rollover_grace_seconds) {
+        daily_stats.tokens_sold_today
+    } else {
+        Uint128::zero()
+    };
+    let daily_limit = compute_daily_token_limit(&config);
+    let fits_daily_limit = !tokens.is_zero() && tokens <= daily_limit.checked_sub(sold_today).unwrap_or_default();
+
+    Ok(QuoteForCw20Response {
+        tokens,
+        usd_value,
+        fits_daily_limit,
+    })
+}
+
+fn query_calculate_tokens_clamped(deps: Deps, env: Env, usd_amount: Uint128) -> StdResult<ClampedTokenCalculationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let (unclamped_tokens, _, _, _, _) =
+        calculate_multi_tier_purchase(usd_amount, config.total_tokens_sold, &pricing_config, config.native_decimals)?;
+
+    // A new day starts with nothing sold, as process_purchase would reset it
+    let daily_stats = DAILY_STATS.load(deps.storage)?;
+    let sold_today = if daily_stats.current_day == daily_stats.day_at(env.block.time.seconds(), config.rollover_grace_seconds) {
+        daily_stats.tokens_sold_today
+    } else {
+        Uint128::zero()
+    };
+    let available_today = compute_daily_token_limit(&config).checked_sub(sold_today).unwrap_or_default();
+    // Above Uint128::MAX the balance can't be the binding limit
+    let sellable = Uint128::try_from(sellable_balance(deps, &env, &config)?).unwrap_or(Uint128::MAX);
+
+    let (tokens, limited_by) = if unclamped_tokens <= available_today.min(sellable) {
+        (unclamped_tokens, TokenLimit::None)
+    } else if available_today <= sellable {
+        (available_today, TokenLimit::DailyLimit)
+    } else {
+        (sellable, TokenLimit::Balance)
+    };
+
+    Ok(ClampedTokenCalculationResponse {
+        tokens,
+        unclamped_tokens,
+        clamped: tokens < unclamped_tokens,
+        limited_by,
+    })
+}
+
+fn query_cost_for_tokens(deps: Deps, token_amount: Uint128) -> StdResult<CostForTokensResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let (usd_cost, start_tier, end_tier, average_price) =
+        calculate_cost_for_tokens(token_amount, config.total_tokens_sold, &pricing_config, config.native_decimals)?;
+    // The tier walk stops after 50 tiers, so a longer span would be under-priced
+    if end_tier.saturating_sub(start_tier) >= 50 {
+        return Err(StdError::msg("token_amount spans more than 50 tiers"));
+    }
+
+    Ok(CostForTokensResponse { usd_cost, start_tier, end_tier, average_price })
+}
+
+fn query_calculate_tokens(deps: Deps, usd_amount: Uint128) -> StdResult<TokenCalculationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+
+    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
+    let current_price = calculate_current_price(
+        pricing_config.base_price_usd,
+        current_tier,
+        pricing_config.tier_multiplier,
+        pricing_config.max_tier,
+    )?;
+
+    let tokens = calculate_tokens_for_usd(usd_amount, current_price, config.native_decimals)?;
+
+    Ok(TokenCalculationResponse {
+        tokens,
+        current_price,
+        current_tier,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::TOKEN_DECIMALS;
+    use cosmwasm_std::testing::{mock_dependencies, mock_dependencies_with_balance, mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, Addr, CosmosMsg, Empty, MessageInfo, OwnedDeps, Querier, QuerierResult, ReplyOn, SystemError, Timestamp, WasmQuery};
+    use std::marker::PhantomData;
+
+    /// Answers the chain's bridge-validation gRPC as valid; everything else goes to the base mock querier
+    struct PoolQuerier {
+        base: MockQuerier,
+    }
+
+    impl Querier for PoolQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = match from_json(bin_request) {
+                Ok(r) => r,
+                Err(e) => {
+                    return SystemResult::Err(SystemError::InvalidRequest {
+                        error: e.to_string(),
+                        request: bin_request.into(),
+                    })
+                }
+            };
+            match request {
+                QueryRequest::Grpc(GrpcQuery { path, .. })
+                    if path == "/inference.inference.Query/ValidateWrappedTokenForTrade" =>
+                {
+                    let mut buf = Vec::new();
+                    QueryValidateWrappedTokenForTradeResponse { is_valid: true }
+                        .encode(&mut buf)
+                        .unwrap();
+                    SystemResult::Ok(ContractResult::Ok(Binary::from(buf)))
+                }
+                // Lets tests see a module-side (contract) gRPC failure
+                QueryRequest::Grpc(GrpcQuery { path, .. })
+                    if path == "/inference.inference.Query/ApprovedTokensForTrade" =>
+                {
+                    SystemResult::Ok(ContractResult::Err("approved tokens unavailable".to_string()))
+                }
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    /// Dependencies where bridge validation passes and the pool holds `balance` native tokens
+    fn mock_deps_with_balance(balance: u128) -> OwnedDeps<MockStorage, MockApi, PoolQuerier> {
+        let base = MockQuerier::new(&[(mock_env().contract.address.as_str(), &coins(balance, "ngonka"))]);
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PoolQuerier { base },
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn mock_instantiate_msg(admin: Option<String>) -> InstantiateMsg {
+        InstantiateMsg {
+            admin,
+            daily_limit_bp: Some(Uint128::from(1000u128)), // 10%
+            base_price_usd: Some(Uint128::from(25000u128)), // $0.025
+            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3M tokens
+            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        }
+    }
+
+    fn purchase_msg(buyer: &Addr, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&PurchaseTokenMsg { allow_daily_partial: false }).unwrap(),
+        })
+    }
+
+    #[test]
+    fn proper_instantiation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            admin: Some(deps.api.addr_make("admin").to_string()),
+            daily_limit_bp: Some(Uint128::from(100u128)), // 1%
+            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
+            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens (9 decimals)
+            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        };
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![], // same as &[] before
+        };
+        let res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(res.attributes.len(), 9);
+        assert!(res.attributes.iter().any(|a| a.key == "used_defaults" && a.value == "false"));
+
+        let version: cw2::ContractVersion =
+            from_json(query(deps.as_ref(), env, QueryMsg::ContractVersion {}).unwrap()).unwrap();
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn test_instantiate_reports_resolved_defaults() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            admin: None,
+            daily_limit_bp: None,
+            base_price_usd: None,
+            tokens_per_tier: None,
+            tier_multiplier: None,
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)),
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        };
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let attr = |key: &str| res.attributes.iter().find(|a| a.key == key).map(|a| a.value.as_str());
+        assert_eq!(attr("base_price_usd"), Some("25000"));
+        assert_eq!(attr("tokens_per_tier"), Some("3000000000000000"));
+        assert_eq!(attr("tier_multiplier"), Some("1300"));
+        assert_eq!(attr("daily_limit_bp"), Some("100"));
+        assert_eq!(attr("used_defaults"), Some("true"));
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Instantiate
+        let msg = InstantiateMsg {
+            admin: Some(deps.api.addr_make("admin").to_string()),
+            daily_limit_bp: Some(Uint128::from(100u128)),
+            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
+            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens (9 decimals)
+            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        };
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![], // same as &[] before
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Pause
+        let pause_msg = ExecuteMsg::Pause { flags: None, auto_resume_after: None };
+        let info = MessageInfo {
+            sender: deps.api.addr_make("admin"),
+            funds: vec![], // same as &[] before
+        };
+        execute(deps.as_mut(), env.clone(), info, pause_msg).unwrap();
+
+        // Check config
+        let config: ConfigResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.is_paused);
+
+        // Resume
+        let resume_msg = ExecuteMsg::Resume { flags: None };
+        let info = MessageInfo {
+            sender: deps.api.addr_make("admin"),
+            funds: vec![], // same as &[] before
+        };
+        execute(deps.as_mut(), env.clone(), info, resume_msg).unwrap();
+
+        // Check config
+        let config: ConfigResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(!config.is_paused);
+    }
+
+    #[test]
+    fn test_pause_auto_resumes() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let now = env.block.time.seconds();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::Pause { flags: None, auto_resume_after: Some(now) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAutoResume { .. }));
+
+        let resume_at = now + 3600;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::Pause { flags: None, auto_resume_after: Some(resume_at) },
+        )
+        .unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // Still paused at the resume time itself
+        env.block.time = Timestamp::from_seconds(resume_at);
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.is_paused);
+        assert_eq!(config.auto_resume_after, Some(resume_at));
+
+        // Past it, purchases go through with no admin action
+        env.block.time = Timestamp::from_seconds(resume_at + 1);
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(!config.is_paused);
+        assert!(!config.pause_flags.purchases);
+        let paused: bool = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert!(!paused);
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_pause_flags() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let withdrawals = Some(PauseFlags {
+            purchases: false,
+            withdrawals: true,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::Pause { flags: withdrawals.clone(), auto_resume_after: None },
+        )
+        .unwrap();
+
+        let withdraw = ExecuteMsg::WithdrawNativeTokens {
+            amount: Uint128::from(1_000u128),
+            recipient: admin.to_string(),
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), withdraw.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        // Purchases keep working while only withdrawals are paused
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        // Pause {} without flags still means "pause purchases"
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), ExecuteMsg::Pause { flags: None, auto_resume_after: None }).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::Resume { flags: withdrawals },
+        )
+        .unwrap();
+        execute(deps.as_mut(), env.clone(), admin_info, withdraw).unwrap();
+
+        let paused: bool = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert!(paused);
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.is_paused);
+        assert_eq!(config.pause_flags, PauseFlags::purchases_only());
+    }
+
+    #[test]
+    fn test_migrate_converts_legacy_pause() {
+        use crate::state::LegacyConfig;
+
+        let mut deps = mock_deps_with_balance(0);
+        let legacy = LegacyConfig {
+            admin: deps.api.addr_make("admin").to_string(),
+            native_denom: "ngonka".to_string(),
+            daily_limit_bp: Uint128::from(1000u128),
+            is_paused: true,
+            total_supply: Uint128::from(120_000_000_000_000_000u128),
+            total_tokens_sold: Uint128::from(5u128),
+            total_usd_received: Uint128::zero(),
+        };
+        LEGACY_CONFIG.save(deps.as_mut().storage, &legacy).unwrap();
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+
+        migrate(deps.as_mut(), mock_env(), Binary::default()).unwrap();
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert!(config.pause_flags.purchases);
+        assert!(!config.pause_flags.withdrawals);
+        assert_eq!(config.total_tokens_sold, Uint128::from(5u128));
+    }
+
+    #[test]
+    fn test_usd_based_tier_calculation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // Instantiate with known values
+        let msg = InstantiateMsg {
+            admin: Some(deps.api.addr_make("admin").to_string()),
+            daily_limit_bp: Some(Uint128::from(1000u128)), // 10%
+            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
+            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens per tier (9 decimals)
+            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        };
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![], // same as &[] before
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // Test tier calculation for $100 USD (100,000,000 micro-units)
+        let usd_amount = Uint128::from(100_000_000u128); // $100
+        let response: TokenCalculationResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::CalculateTokens { usd_amount }).unwrap()
+        ).unwrap();
+
+        // With $0.025 base price and 10M tokens per tier:
+        // USD per tier = 10,000,000 * 25,000 = 250,000,000,000 micro-USD = $250,000
+        // $100 should be in tier 0 (before first tier)
+        assert_eq!(response.current_tier, 0);
+        assert_eq!(response.current_price, Uint128::from(25000u128)); // $0.025
+        assert_eq!(response.tokens, Uint128::from(4_000_000_000_000u128)); // 4000 tokens for $100 (100,000,000 * 1,000,000,000 / 25,000)
+    }
+
+    #[test]
+    fn test_multi_tier_purchase() {
+        use crate::state::{calculate_multi_tier_purchase, PricingConfig};
+
+        // Test setup: 3M tokens per tier, $0.025 base price, 1.3x multiplier (token-based tiers)
+        let pricing_config = PricingConfig {
+            base_price_usd: Uint128::from(25000u128), // $0.025
+            tokens_per_tier: Uint128::from(3_000_000_000_000_000u128), // 3M tokens with 9 decimals
+            tier_multiplier: Uint128::from(1300u128), // 1.3x multiplier
+            max_tier: None,
+        };
+
+        // Test 1: Purchase within single tier
+        let (tokens, usd_spent, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
+            Uint128::from(100_000_000u128), // $100
+            Uint128::zero(), // No tokens sold yet
+            &pricing_config,
+            9,
+        ).unwrap();
+        // Should get 4000 tokens at $0.025 each
+        assert_eq!(tokens, Uint128::from(4_000_000_000_000u128)); // 4000 tokens (with 9 decimals)
+        assert_eq!(usd_spent, Uint128::from(100_000_000u128)); // $100
+        assert_eq!(start_tier, 0);
+        assert_eq!(end_tier, 0); // Still in same tier
+        assert_eq!(avg_price, Uint128::from(25000u128)); // $0.025
+
+        // Test 2: Purchase spanning two tiers
+        // Start with 2.5M tokens already sold (very close to tier boundary of 3M tokens)
+        // Use $20,000 to ensure we cross into tier 1
+        let (tokens, usd_spent, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
+            Uint128::from(20_000_000_000u128), // $20,000 purchase
+            Uint128::from(2_500_000_000_000_000u128), // 2.5M tokens already sold (with 9 decimals)
+            &pricing_config,
+            9,
+        ).unwrap();
+        
+        
+        // Should span two tiers:
+        // Tier 0: 0.5M tokens left at $0.025 = $12,500  
+        // Tier 1: $7,500 at $0.0325 = ~230,769 tokens
+        // Total: ~730,769 tokens
+        assert!(tokens > Uint128::from(700_000_000_000_000u128)); // > 700k tokens (9 decimals)  
+        assert!(tokens < Uint128::from(800_000_000_000_000u128)); // < 800k tokens (9 decimals)
+        assert_eq!(usd_spent, Uint128::from(20_000_000_000u128)); // Full $20,000 spent
+        assert_eq!(start_tier, 0); // Started in tier 0
+        assert_eq!(end_tier, 1); // Ended in tier 1
+        // Average price should be between $0.025 and $0.0325
+        assert!(avg_price > Uint128::from(25000u128)); // > $0.025
+        assert!(avg_price < Uint128::from(32500u128)); // < $0.0325
+    }
+
+    #[test]
+    fn test_native_decimals_scale_pricing() {
+        use crate::state::calculate_multi_tier_purchase;
+
+        // 3M tokens per tier in 6-decimal units: $100 at $0.025 buys 4000 tokens
+        let pricing_config = PricingConfig {
+            base_price_usd: Uint128::from(25000u128),
+            tokens_per_tier: Uint128::from(3_000_000_000_000u128),
+            tier_multiplier: Uint128::from(1300u128),
+            max_tier: None,
+        };
+        let (tokens, usd_spent, _, end_tier, avg_price) =
+            calculate_multi_tier_purchase(Uint128::from(100_000_000u128), Uint128::zero(), &pricing_config, 6).unwrap();
+        assert_eq!(tokens, Uint128::from(4_000_000_000u128));
+        assert_eq!(usd_spent, Uint128::from(100_000_000u128));
+        assert_eq!(end_tier, 0);
+        assert_eq!(avg_price, Uint128::from(25000u128));
+
+        // Same sale with an 18-decimal token, spanning into tier 1 after 3M tokens ($75,000)
+        let pricing_config = PricingConfig {
+            tokens_per_tier: Uint128::from(3_000_000_000_000_000_000_000_000u128),
+            ..pricing_config
+        };
+        let (tokens, usd_spent, _, end_tier, _) =
+            calculate_multi_tier_purchase(Uint128::from(75_000_000_000u128 + 32_500_000), Uint128::zero(), &pricing_config, 18).unwrap();
+        // 3M tokens in tier 0 plus $32.50 at $0.0325 = 1000 tokens in tier 1
+        assert_eq!(tokens, Uint128::from(3_001_000_000_000_000_000_000_000u128));
+        assert_eq!(usd_spent, Uint128::from(75_032_500_000u128));
+        assert_eq!(end_tier, 1);
+
+        // 10^21 tokens per 9-decimal tier at $1000: the tier's cost (tokens * price) exceeds
+        // Uint128 before scaling back down, and must not wrap to a zero-cost tier
+        let pricing_config = PricingConfig {
+            base_price_usd: Uint128::from(1_000_000_000u128),
+            tokens_per_tier: Uint128::from(10u128.pow(30)),
+            tier_multiplier: Uint128::from(1300u128),
+            max_tier: None,
+        };
+        let (tokens, usd_spent, _, end_tier, _) =
+            calculate_multi_tier_purchase(Uint128::from(100_000_000u128), Uint128::zero(), &pricing_config, 9).unwrap();
+        // $100 at $1000 buys 0.1 token
+        assert_eq!(tokens, Uint128::from(100_000_000u128));
+        assert_eq!(usd_spent, Uint128::from(100_000_000u128));
+        assert_eq!(end_tier, 0);
+
+        // Decimals beyond the supported range are rejected at instantiate
+        let mut deps = mock_dependencies();
+        let mut msg = mock_instantiate_msg(None);
+        msg.native_decimals = Some(19);
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidNativeDecimals { decimals: 19, max: 18 }));
+
+        let mut msg = mock_instantiate_msg(None);
+        msg.native_decimals = Some(18);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let response: TokenCalculationResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::CalculateTokens { usd_amount: Uint128::from(100_000_000u128) })
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response.tokens, Uint128::from(4_000_000_000_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_daily_partial_fill() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let wusdt = deps.api.addr_make("wusdt");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        // 40,000 tokens at 10% -> 4,000 tokens ($100) may be sold per day
+        let info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::UpdateTotalSupply {
+            total_supply: Uint128::from(40_000_000_000_000u128),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: wusdt.clone(),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 150_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::DailyLimitExceeded { .. }));
+
+        let partial = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(150_000_000u128),
+            msg: to_json_binary(&PurchaseTokenMsg { allow_daily_partial: true }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), partial.clone()).unwrap();
+
+        let attr = |key: &str| res.attributes.iter().find(|a| a.key == key).map(|a| a.value.clone());
+        assert_eq!(attr("filled_tokens"), Some("4000000000000".to_string()));
+        assert_eq!(attr("refunded_cw20"), Some("50000000".to_string()));
+        assert_eq!(attr("usd_spent"), Some("100000000".to_string()));
+        assert_eq!(attr("soft_rejection"), Some(REJECTION_DAILY_PARTIAL.to_string()));
+
+        // Native payout, $50 back to the buyer, $100 on to the admin
+        let cw20_transfers: Vec<_> = res
+            .messages
+            .iter()
+            .filter_map(|m| match &m.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                    assert_eq!(contract_addr, wusdt.as_str());
+                    Some(String::from_utf8(msg.to_vec()).unwrap())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            cw20_transfers,
+            vec![
+                format!(r#"{{"transfer":{{"recipient":"{}","amount":"50000000"}}}}"#, buyer),
+                format!(r#"{{"transfer":{{"recipient":"{}","amount":"100000000"}}}}"#, admin),
+            ]
+        );
+
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.tokens_available_today, Uint128::zero());
+        assert_eq!(stats.usd_received_today, Uint128::from(100_000_000u128));
+
+        // Nothing left today: even a partial purchase is rejected
+        let err = execute(deps.as_mut(), env.clone(), cw20_info, partial).unwrap_err();
+        assert!(matches!(err, ContractError::DailyLimitExceeded { .. }));
+
+        // Only the committed partial fill is counted; the outright rejections reverted
+        let rejections: RejectionStatsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::RejectionStats {}).unwrap()).unwrap();
+        assert_eq!(rejections.reasons.len(), 1);
+        assert_eq!(rejections.reasons.get(REJECTION_DAILY_PARTIAL), Some(&1));
+    }
+
+    #[test]
+    fn test_purchase_id_increments() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let last: LastPurchaseIdResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::LastPurchaseId {}).unwrap()).unwrap();
+        assert_eq!(last.purchase_id, 0);
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        for expected in ["1", "2"] {
+            let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+            assert!(res.attributes.iter().any(|a| a.key == "purchase_id" && a.value == expected));
+        }
+
+        let last: LastPurchaseIdResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::LastPurchaseId {}).unwrap()).unwrap();
+        assert_eq!(last.purchase_id, 2);
+    }
+
+    #[test]
+    fn test_forwarded_totals_accumulate() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let wusdt = deps.api.addr_make("wusdt");
+        let cw20_info = MessageInfo {
+            sender: wusdt.clone(),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 50_000_000)).unwrap();
+
+        let res: ForwardedTotalsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::ForwardedTotals {}).unwrap()).unwrap();
+        assert_eq!(res.totals.len(), 1);
+        assert_eq!(res.totals.get(wusdt.as_str()), Some(&Uint128::from(150_000_000u128)));
+    }
+
+    #[test]
+    fn test_overflowing_tier_multiplier_rejected() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        // 25000 * (u128::MAX / 1000) overflows on the very first tier step
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdatePricingConfig {
+                base_price_usd: None,
+                tokens_per_tier: None,
+                tier_multiplier: Some(Uint128::MAX / Uint128::from(1000u128)),
+                max_tier: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TierMultiplierOverflow { .. }));
+
+        // The stored config is untouched
+        let pricing: PricingInfoResponse = from_json(query(deps.as_ref(), env, QueryMsg::PricingInfo {}).unwrap()).unwrap();
+        assert_eq!(pricing.tier_multiplier, Uint128::from(1300u128));
+    }
+
+    #[test]
+    fn test_quote_for_cw20_matches_purchase() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+        let wusdt = deps.api.addr_make("wusdt");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let quote: QuoteForCw20Response = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::QuoteForCw20 { cw20_contract: wusdt.to_string(), amount: Uint128::from(100_000_000u128) },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(quote.usd_value, Uint128::from(100_000_000u128));
+        assert!(quote.fits_daily_limit);
+
+        let cw20_info = MessageInfo {
+            sender: wusdt.clone(),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        let purchased = res.attributes.iter().find(|a| a.key == "tokens_purchased").unwrap();
+        assert_eq!(purchased.value, quote.tokens.to_string());
+
+        // An amount beyond today's remaining capacity is quoted but flagged
+        let quote: QuoteForCw20Response = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::QuoteForCw20 { cw20_contract: wusdt.to_string(), amount: Uint128::from(1_000_000_000_000u128) },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(!quote.tokens.is_zero());
+        assert!(!quote.fits_daily_limit);
+    }
+
+    #[test]
+    fn test_purchase_with_balance_above_uint128() {
+        let base = MockQuerier::new(&[(
+            mock_env().contract.address.as_str(),
+            &[Coin::new(Uint256::from(u128::MAX) + Uint256::one(), "ngonka")],
+        )]);
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PoolQuerier { base },
+            custom_query_type: PhantomData,
+        };
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn test_sale_progress() {
+        let mut deps = mock_deps_with_balance(10_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // $30,000 at $0.025 buys 1.2M tokens, 1% of the 120M supply
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 30_000_000_000)).unwrap();
+
+        let progress: SaleProgressResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::SaleProgress {}).unwrap()).unwrap();
+        assert_eq!(progress.total_supply, Uint128::from(120_000_000_000_000_000u128));
+        assert_eq!(progress.total_tokens_sold, Uint128::from(1_200_000_000_000_000u128));
+        assert_eq!(progress.percent_sold_bp, Uint128::from(100u128));
+        assert_eq!(progress.current_tier, 0);
+        assert_eq!(progress.current_price_usd, Uint128::from(25000u128));
+        // The mock bank doesn't apply sends, so the balance is the starting one
+        assert_eq!(progress.native_balance, Uint256::from(10_000_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_rollover_grace_keeps_prior_day_bucket() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let buyer = deps.api.addr_make("buyer");
+        let midnight = 20_000 * 86400;
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(midnight - 1);
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        let mut msg = mock_instantiate_msg(None);
+        msg.rollover_grace_seconds = Some(60);
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // 23:59:59, then 00:00:10 inside the 60s grace window: both land in the same bucket
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        env.block.time = Timestamp::from_seconds(midnight + 10);
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let stats: DailyStatsResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.current_day, 19_999);
+        assert_eq!(stats.tokens_sold_today, Uint128::from(8_000_000_000_000u128));
+
+        // Past the grace window the new day starts fresh
+        env.block.time = Timestamp::from_seconds(midnight + 61);
+        let stats: DailyStatsResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.current_day, 20_000);
+        assert!(stats.tokens_sold_today.is_zero());
+
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        let archived: DailyStatsForDayResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::DailyStatsForDay { day: 19_999 }).unwrap()).unwrap();
+        assert_eq!(archived.tokens_sold, Uint128::from(8_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_raised_grace_keeps_buyer_purchase_count() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let midnight = 20_000 * 86400;
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(midnight + 600);
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let cap = ExecuteMsg::UpdateMaxPurchasesPerDay { max_purchases_per_buyer_per_day: Some(1) };
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), cap).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 1_000_000)).unwrap();
+
+        // A grace of an hour at 00:10 would put the block back on day 19_999, but the day's count stays
+        let grace = ExecuteMsg::UpdateRolloverGrace { rollover_grace_seconds: 3600 };
+        execute(deps.as_mut(), env.clone(), admin_info, grace).unwrap();
+        let today: PurchasesTodayResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::PurchasesToday { buyer: buyer.to_string() }).unwrap())
+                .unwrap();
+        assert_eq!(today.purchases, 1);
+        let err = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 1_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyPurchasesToday { max: 1 }));
+    }
+
+    #[test]
+    fn test_can_purchase() {
+        let mut deps = mock_deps_with_balance(10_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let wusdt = deps.api.addr_make("wusdt").to_string();
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+
+        let can_purchase = |deps: Deps, amount: u128| -> CanPurchaseResponse {
+            from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::CanPurchase {
+                        buyer: buyer.to_string(),
+                        cw20_contract: wusdt.clone(),
+                        amount: Uint128::from(amount),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // $100 buys 4,000 tokens
+        let res = can_purchase(deps.as_ref(), 100_000_000);
+        assert!(res.ok);
+        assert_eq!(res.reason, None);
+        assert_eq!(res.tokens_out, Uint128::from(4_000_000_000_000u128));
+
+        let res = can_purchase(deps.as_ref(), 0);
+        assert!(!res.ok);
+        assert_eq!(res.reason, Some(ContractError::ZeroAmount {}.to_string()));
+        assert!(res.tokens_out.is_zero());
+
+        // $300 needs 12,000 tokens but the pool holds 10,000
+        let res = can_purchase(deps.as_ref(), 300_000_000);
+        assert!(res.reason.unwrap().starts_with("Insufficient contract balance"));
+
+        // $1M buys well over the 12M daily limit (the first four tiers cost ~$464k)
+        let res = can_purchase(deps.as_ref(), 1_000_000_000_000);
+        assert!(res.reason.unwrap().starts_with("Daily limit exceeded"));
+
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), ExecuteMsg::SetRequireAllowlist { require_allowlist: true })
+            .unwrap();
+        let res = can_purchase(deps.as_ref(), 100_000_000);
+        assert_eq!(res.reason, Some(ContractError::BuyerNotAllowed { buyer: buyer.to_string() }.to_string()));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), ExecuteMsg::SetRequireAllowlist { require_allowlist: false })
+            .unwrap();
+
+        execute(deps.as_mut(), env, admin_info, ExecuteMsg::Pause { flags: None, auto_resume_after: None }).unwrap();
+        let res = can_purchase(deps.as_ref(), 100_000_000);
+        assert_eq!(res.reason, Some(ContractError::ContractPaused {}.to_string()));
+
+        // Without the chain's bridge module the token can't be validated
+        let mut deps = mock_dependencies();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg(None)).unwrap();
+        let res = can_purchase(deps.as_ref(), 100_000_000);
+        assert!(!res.ok);
+        assert!(res.reason.unwrap().starts_with("[grpc:system:"));
+    }
+
+    #[test]
+    fn test_circulating_supply_limit_basis() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+
+        // The override basis needs a circulating supply first
+        let select = ExecuteMsg::UpdateLimitBasis { limit_basis: LimitBasis::CirculatingOverride };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), select.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::MissingCirculatingSupply {}));
+
+        // 10% of a 50,000-token circulating supply: 5,000 tokens per day
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateCirculatingSupply { circulating_supply: Some(Uint128::from(50_000_000_000_000u128)) },
+        )
+        .unwrap();
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), select).unwrap();
+
+        let stats: DailyStatsResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.daily_token_limit, Uint128::from(5_000_000_000_000u128));
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // 4,000 tokens fit; another 4,000 would not
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::DailyLimitExceeded { available: 1_000_000_000_000, .. }));
+
+        // The override can't be cleared while it is the selected basis
+        let err = execute(
+            deps.as_mut(),
+            env,
+            admin_info,
+            ExecuteMsg::UpdateCirculatingSupply { circulating_supply: None },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MissingCirculatingSupply {}));
+    }
+
+    #[test]
+    fn test_max_purchase_usd() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdateMaxPurchase { max_purchase_usd: Some(Uint128::from(100_000_000u128)) },
+        )
+        .unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // Exactly $100 is allowed; one micro-USD more is not
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_001)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AboveMaximumPurchase { max, got } if max == Uint128::from(100_000_000u128) && got == Uint128::from(100_000_001u128)
+        ));
+
+        // The cap is per purchase: the same buyer can buy again
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_trusted_cw20_skips_chain_validation() {
+        // No gRPC support here: only trusted CW20s can be accepted
+        let mut deps = mock_dependencies_with_balance(&coins(1_000_000_000_000_000, "ngonka"));
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let wusdt = deps.api.addr_make("wusdt");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: wusdt.clone(),
+            funds: vec![],
+        };
+        assert!(execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).is_err());
+
+        // Only the admin manages the trusted set
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            MessageInfo { sender: buyer.clone(), funds: vec![] },
+            ExecuteMsg::AddTrustedCw20 { address: wusdt.to_string() },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::AddTrustedCw20 { address: wusdt.to_string() },
+        )
+        .unwrap();
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let trusted: TrustedCw20sResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::TrustedCw20s {}).unwrap()).unwrap();
+        assert_eq!(trusted.addresses, vec![wusdt.to_string()]);
+        assert!(!trusted.trust_cache_only);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::RemoveTrustedCw20 { address: wusdt.to_string() },
+        )
+        .unwrap();
+        let trusted: TrustedCw20sResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::TrustedCw20s {}).unwrap()).unwrap();
+        assert!(trusted.addresses.is_empty());
+        assert!(execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).is_err());
+    }
+
+    #[test]
+    fn test_trust_cache_only_rejects_untrusted_cw20() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::SetTrustCacheOnly { trust_cache_only: true },
+        )
+        .unwrap();
+
+        // The chain would approve this CW20, but it is not in the trusted set
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAccepted { .. }));
+    }
+
+    #[test]
+    fn test_zero_supply_pool_rejects_purchases() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        let msg = InstantiateMsg {
+            total_supply: None,
+            ..mock_instantiate_msg(Some(admin.to_string()))
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let pricing: PricingInfoResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::PricingInfo {}).unwrap()).unwrap();
+        assert!(!pricing.configured);
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert!(!stats.configured);
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::TotalSupplyNotConfigured {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let add_uusdc = ExecuteMsg::AddPaymentToken { denom: "uusdc".to_string(), usd_rate: Uint128::one(), decimals: Some(6) };
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), add_uusdc).unwrap();
+        let native_info = MessageInfo {
+            sender: buyer.clone(),
+            funds: coins(100_000_000, "uusdc"),
+        };
+        let err = execute(deps.as_mut(), env.clone(), native_info, ExecuteMsg::PurchaseWithNative {}).unwrap_err();
+        assert!(matches!(err, ContractError::TotalSupplyNotConfigured {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdateTotalSupply { total_supply: Uint128::from(120_000_000_000_000_000u128) },
+        )
+        .unwrap();
+
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert!(stats.configured);
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_auto_pause_at_price_ceiling() {
+        let mut deps = mock_deps_with_balance(10_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        // $0.03: tier 0 ($0.025) stays open, tier 1 ($0.0325) is above the ceiling
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateAutoPausePrice { auto_pause_price_usd: Some(Uint128::from(30_000u128)) },
+        )
+        .unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert!(!res.attributes.iter().any(|a| a.key == "auto_paused_price"));
+
+        // $80k buys past the 3M-token tier 0 ($75k) and completes, then pauses
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 80_000_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "auto_paused_price" && a.value == "32500"));
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.is_paused);
+
+        let err = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::ContractPaused {}));
+    }
+
+    #[test]
+    fn test_daily_usd_limit() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.usd_available_today, None);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            MessageInfo { sender: buyer.clone(), funds: vec![] },
+            ExecuteMsg::UpdateDailyUsdLimit { daily_usd_limit: Some(Uint128::from(150_000_000u128)) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdateDailyUsdLimit { daily_usd_limit: Some(Uint128::from(150_000_000u128)) },
+        )
+        .unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.usd_available_today, Some(Uint128::from(50_000_000u128)));
+
+        // Well within the token limit, but over the remaining $50
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 60_000_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::DailyUsdLimitExceeded { available: 50_000_000, requested: 60_000_000 }
+        ));
+
+        // The cap resets with the day
+        env.block.time = env.block.time.plus_seconds(86400);
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 60_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_verify_received_rejects_fee_token_shortfall() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let fee_token = deps.api.addr_make("feetoken");
+        let plain_token = deps.api.addr_make("wusdt");
+
+        // The fee token keeps 1% of every transfer: a $100 send leaves the pool holding $99
+        let fee_token_addr = fee_token.to_string();
+        deps.querier.base.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } => {
+                let balance = if *contract_addr == fee_token_addr { "99000000" } else { "100000000" };
+                let response = format!(r#"{{"balance":"{}"}}"#, balance);
+                SystemResult::Ok(ContractResult::Ok(Binary::from(response.as_bytes())))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        // Off by default: the reported amount is trusted
+        let fee_info = MessageInfo {
+            sender: fee_token,
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), fee_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), admin_info, ExecuteMsg::SetVerifyReceived { verify_received: true }).unwrap();
+
+        let err = execute(deps.as_mut(), env.clone(), fee_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::ReceivedAmountMismatch { reported, held }
+                if reported == Uint128::from(100_000_000u128) && held == Uint128::from(99_000_000u128)
+        ));
+
+        let plain_info = MessageInfo {
+            sender: plain_token,
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env, plain_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_recover_misdirected_native() {
+        // 120M tokens for sale plus 5,000 tokens sent to the pool by mistake
+        let mut deps = mock_deps_with_balance(120_005_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let recipient = deps.api.addr_make("recipient");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let recover = |amount: u128| ExecuteMsg::RecoverMisdirectedNative {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            MessageInfo { sender: recipient.clone(), funds: vec![] },
+            recover(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), recover(5_000_000_000_001)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientBalance { available: 5_000_000_000_000, needed: 5_000_000_000_001 }
+        ));
+
+        let res = execute(deps.as_mut(), env, admin_info, recover(5_000_000_000_000)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(5_000_000_000_000, "ngonka"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_burn_unsold() {
+        let mut deps = mock_deps_with_balance(5_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let burn = |amount: u128| ExecuteMsg::BurnUnsold { amount: Uint128::from(amount) };
+        let outsider = MessageInfo {
+            sender: deps.api.addr_make("outsider"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, burn(1)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), burn(5_000_000_000_001)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientBalance { available: 5_000_000_000_000, needed: 5_000_000_000_001 }
+        ));
+
+        let res = execute(deps.as_mut(), env.clone(), admin_info, burn(2_000_000_000_000)).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn { amount: coins(2_000_000_000_000, "ngonka") })
+        );
+        assert!(res.attributes.iter().any(|a| a.key == "burned" && a.value == "2000000000000"));
+
+        // The mock bank does not run messages, so apply the burn to see the sellable balance drop
+        deps.querier.base.bank.update_balance(&env.contract.address, coins(3_000_000_000_000, "ngonka"));
+        let res: SellableBalanceResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::SellableBalance {}).unwrap()).unwrap();
+        assert_eq!(res.sellable, Uint256::from(3_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_empty_purchase_msg_still_accepted() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        // Clients written before PurchaseTokenMsg had fields send a bare {}
+        let parsed: PurchaseTokenMsg = from_json(b"{}").unwrap();
+        assert!(!parsed.allow_daily_partial);
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(100_000_000u128),
+            msg: Binary::from(b"{}".as_slice()),
+        });
+        let res = execute(deps.as_mut(), env, cw20_info, msg).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_purchased" && a.value == "4000000000000"));
+    }
+
+    #[test]
+    fn test_approved_tokens_with_status() {
+        /// Serves a fixed approved list and treats every CW20 except `revoked` as valid
+        struct ApprovedQuerier {
+            approved: Vec<BridgeTradeApprovedToken>,
+            revoked: String,
+        }
+
+        impl Querier for ApprovedQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<Empty> = from_json(bin_request).unwrap();
+                match request {
+                    QueryRequest::Grpc(GrpcQuery { path, data }) if path.ends_with("/ValidateWrappedTokenForTrade") => {
+                        let request = QueryValidateWrappedTokenForTradeRequest::decode(data.as_slice()).unwrap();
+                        let response = QueryValidateWrappedTokenForTradeResponse { is_valid: request.contract_address != self.revoked };
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(response.encode_to_vec())))
+                    }
+                    QueryRequest::Grpc(GrpcQuery { path, .. }) if path.ends_with("/ApprovedTokensForTrade") => {
+                        let response = QueryApprovedTokensForTradeResponseProto { approved_tokens: self.approved.clone() };
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(response.encode_to_vec())))
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "test".to_string() }),
+                }
+            }
+        }
+
+        let api = MockApi::default();
+        let wusdt = api.addr_make("wusdt").to_string();
+        let revoked = api.addr_make("revoked").to_string();
+        let approved = |contract_address: &str| BridgeTradeApprovedToken {
+            chain_id: "ethereum".to_string(),
+            contract_address: contract_address.to_string(),
+        };
+        let deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api,
+            querier: ApprovedQuerier {
+                approved: vec![approved(&wusdt), approved(&revoked), approved("0xdac17f958d2ee523a2206206994597c13d831ec7")],
+                revoked: revoked.clone(),
+            },
+            custom_query_type: PhantomData::<Empty>,
+        };
+
+        let res: ApprovedTokensWithStatusResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::ApprovedTokensWithStatus {}).unwrap()).unwrap();
+        assert!(!res.truncated);
+        let statuses: Vec<_> = res.tokens.iter().map(|t| (t.is_valid, t.error.is_some())).collect();
+        assert_eq!(statuses, vec![(true, false), (false, false), (false, true)]);
+        assert_eq!(res.tokens[1].contract_address, revoked);
+    }
+
+    #[test]
+    fn test_purchase_msg_rejects_unknown_fields() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        // A typo must fail the purchase rather than silently buy without the intended option
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::from(100_000_000u128),
+            msg: Binary::from(br#"{"allow_daily_parital":true}"#.as_slice()),
+        });
+        let err = execute(deps.as_mut(), env.clone(), cw20_info, msg).unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{}", err);
+
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.total_tokens_sold.is_zero());
+    }
+
+    #[test]
+    fn test_sellable_balance_matches_purchase_check() {
+        let mut deps = mock_deps_with_balance(5_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let res: SellableBalanceResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::SellableBalance {}).unwrap()).unwrap();
+        assert_eq!(res.sellable, Uint256::from(5_000_000_000_000u128));
+
+        // $150 buys 6,000 tokens, more than the 5,000 the query reports
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 150_000_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientBalance { available: 5_000_000_000_000, needed: 6_000_000_000_000 }
+        ));
+
+        execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+    }
+
+    #[test]
+    fn test_payment_token_decimals_from_token_info() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let weth = deps.api.addr_make("wweth");
+
+        deps.querier.base.update_wasm(|query| match query {
+            WasmQuery::Smart { .. } => {
+                let response = br#"{"name":"Wrapped ETH","symbol":"WETH","decimals":18,"total_supply":"0"}"#;
+                SystemResult::Ok(ContractResult::Ok(Binary::from(response.as_slice())))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let add = |denom: &str, decimals: Option<u8>| ExecuteMsg::AddPaymentToken {
+            denom: denom.to_string(),
+            usd_rate: Uint128::one(),
+            decimals,
+        };
+
+        // The CW20's TokenInfo answers, and wins over a wrong hand-entered value
+        let res = execute(deps.as_mut(), env.clone(), admin_info.clone(), add(weth.as_str(), Some(6))).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "decimals" && a.value == "18"));
+        assert!(res.attributes.iter().any(|a| a.key == "decimals_source" && a.value == "token_info"));
+
+        // A native denom has no TokenInfo: it is accepted without decimals, and none are recorded
+        let res = execute(deps.as_mut(), env.clone(), admin_info.clone(), add("uusdc", None)).unwrap();
+        assert!(!res.attributes.iter().any(|a| a.key == "decimals" || a.key == "decimals_source"));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), add("uatom", Some(6))).unwrap();
+
+        let res: PaymentTokensResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::PaymentTokens {}).unwrap()).unwrap();
+        assert_eq!(res.tokens.get("uusdc"), Some(&Uint128::one()));
+        assert_eq!(res.decimals.get(weth.as_str()), Some(&18));
+        assert_eq!(res.decimals.get("uusdc"), None);
+        assert_eq!(res.decimals.get("uatom"), Some(&6));
+    }
+
+    #[test]
+    fn test_add_payment_tokens_batch() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let wusdc = deps.api.addr_make("wusdc");
+        let wdai = deps.api.addr_make("wdai");
+
+        deps.querier.base.update_wasm(|query| match query {
+            WasmQuery::Smart { .. } => {
+                let response = br#"{"name":"Wrapped USD","symbol":"WUSD","decimals":6,"total_supply":"0"}"#;
+                SystemResult::Ok(ContractResult::Ok(Binary::from(response.as_slice())))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "wasm".to_string() }),
+        });
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let entry = |denom: &str, decimals: Option<u8>| PaymentTokenEntry {
+            denom: denom.to_string(),
+            usd_rate: Uint128::from(1_000_000u128),
+            decimals,
+        };
+        let payment_tokens = |deps: &OwnedDeps<MockStorage, MockApi, PoolQuerier>| -> PaymentTokensResponse {
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::PaymentTokens {}).unwrap()).unwrap()
+        };
+
+        // A malformed CW20 address fails validation and nothing from the batch is stored
+        let msg = ExecuteMsg::AddPaymentTokens {
+            tokens: vec![
+                entry(wusdc.as_str(), None),
+                entry("cw20:not-an-address", None),
+                entry(wdai.as_str(), None),
+            ],
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidToken { .. }));
+        assert!(payment_tokens(&deps).decimals.is_empty());
+
+        let too_many = ExecuteMsg::AddPaymentTokens {
+            tokens: vec![entry(wusdc.as_str(), None); MAX_BATCH_PAYMENT_TOKENS + 1],
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), too_many).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPaymentTokenBatch { count: 21, max: 20 }));
+
+        let msg = ExecuteMsg::AddPaymentTokens {
+            tokens: vec![
+                entry(wusdc.as_str(), None),
+                entry(wdai.as_str(), None),
+                entry("uusdc", Some(6)),
+                entry("uatom", None),
+            ],
+        };
+        let res = execute(deps.as_mut(), env, admin_info, msg).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_added" && a.value == "4"));
+        let res = payment_tokens(&deps);
+        assert_eq!(res.decimals.get(wusdc.as_str()), Some(&6));
+        assert_eq!(res.decimals.get(wdai.as_str()), Some(&6));
+        assert_eq!(res.decimals.get("uusdc"), Some(&6));
+        // A native denom given without decimals is accepted, with none recorded
+        assert!(res.tokens.contains_key("uatom"));
+        assert_eq!(res.decimals.get("uatom"), None);
+    }
+
+    #[test]
+    fn test_reset_daily_stats() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+
+        // Off unless the pool was instantiated with it
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), ExecuteMsg::ResetDailyStats {}).unwrap_err();
+        assert!(matches!(err, ContractError::DailyStatsResetDisabled {}));
+
+        let msg = InstantiateMsg {
+            allow_daily_stats_reset: Some(true),
+            ..mock_instantiate_msg(Some(admin.to_string()))
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let outsider = MessageInfo {
+            sender: buyer.clone(),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, ExecuteMsg::ResetDailyStats {}).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(deps.as_mut(), env.clone(), admin_info, ExecuteMsg::ResetDailyStats {}).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "tokens_sold_cleared" && a.value == "4000000000000"));
+
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert!(stats.tokens_sold_today.is_zero());
+        assert!(stats.usd_received_today.is_zero());
+        assert_eq!(stats.tokens_available_today, stats.daily_token_limit);
+
+        // Sales after the reset are added to the archived record when the day rolls over
+        let today = stats.current_day;
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        env.block.time = env.block.time.plus_days(1);
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        let archived: DailyStatsForDayResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::DailyStatsForDay { day: today }).unwrap()).unwrap();
+        assert_eq!(archived.tokens_sold, Uint128::from(8_000_000_000_000u128));
+        assert_eq!(archived.usd_received, Uint128::from(200_000_000u128));
+    }
+
+    #[test]
+    fn test_max_purchases_per_buyer_per_day() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let other = deps.api.addr_make("other");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let set_max = |max: Option<u32>| ExecuteMsg::UpdateMaxPurchasesPerDay { max_purchases_per_buyer_per_day: max };
+        let outsider = MessageInfo {
+            sender: buyer.clone(),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, set_max(Some(2))).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), set_max(Some(0))).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+        execute(deps.as_mut(), env.clone(), admin_info, set_max(Some(2))).unwrap();
+
+        // Two small purchases use up the day, however little they bought
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        for _ in 0..2 {
+            execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 1_000_000)).unwrap();
+        }
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 1_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::TooManyPurchasesToday { max: 2 }));
+
+        let purchases_today = |deps: Deps, env: Env| -> PurchasesTodayResponse {
+            from_json(query(deps, env, QueryMsg::PurchasesToday { buyer: buyer.to_string() }).unwrap()).unwrap()
+        };
+        let res = purchases_today(deps.as_ref(), env.clone());
+        assert_eq!(res.purchases, 2);
+        assert_eq!(res.max_purchases_per_buyer_per_day, Some(2));
+
+        // The cap is per buyer
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&other, 1_000_000)).unwrap();
+
+        // and starts over the next day
+        env.block.time = env.block.time.plus_days(1);
+        assert_eq!(purchases_today(deps.as_ref(), env.clone()).purchases, 0);
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 1_000_000)).unwrap();
+        assert_eq!(purchases_today(deps.as_ref(), env).purchases, 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict_math"))]
+    fn test_pricing_math_clamps_on_overflow() {
+        // $3.4e32 at a micro-cent price is more tokens than a Uint128 holds
+        assert_eq!(calculate_tokens_for_usd(Uint128::MAX, Uint128::one(), 18).unwrap(), Uint128::MAX);
+        // A price that can't escalate further stays where it is
+        assert_eq!(
+            calculate_current_price(Uint128::MAX, 1, Uint128::from(1300u128), None).unwrap(),
+            Uint128::MAX
+        );
+        // A partial tier whose unscaled cost exceeds Uint128 is still priced exactly
+        let pricing = PricingConfig {
+            base_price_usd: Uint128::new(1_000_000_000 << 60),
+            tokens_per_tier: Uint128::new(1 << 40),
+            tier_multiplier: Uint128::from(1000u128),
+            max_tier: None,
+        };
+        assert_eq!(
+            calculate_usd_for_tokens(Uint128::new(1 << 39), Uint128::zero(), &pricing, 9).unwrap(),
+            Uint128::new(1 << 99)
+        );
+        // and a cost beyond Uint128 saturates rather than becoming free
+        let pricing = PricingConfig {
+            base_price_usd: Uint128::new(1 << 63),
+            tokens_per_tier: Uint128::new(1 << 64),
+            tier_multiplier: Uint128::from(1300u128),
+            max_tier: None,
+        };
+        assert_eq!(calculate_usd_for_tokens(Uint128::new(1 << 65), Uint128::zero(), &pricing, 0).unwrap(), Uint128::MAX);
+    }
+
+    #[test]
+    #[cfg(feature = "strict_math")]
+    fn test_strict_math_rejects_token_overflow() {
+        assert!(matches!(
+            calculate_tokens_for_usd(Uint128::MAX, Uint128::one(), 18),
+            Err(ContractError::MathOverflow { op }) if op == "calculate_tokens_for_usd"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "strict_math")]
+    fn test_strict_math_rejects_price_overflow() {
+        assert!(matches!(
+            calculate_current_price(Uint128::MAX, 1, Uint128::from(1300u128), None),
+            Err(ContractError::MathOverflow { op }) if op == "calculate_current_price"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "strict_math")]
+    fn test_strict_math_rejects_usd_overflow() {
+        // Each tier's cost fits, but two of them together don't
+        let pricing = PricingConfig {
+            base_price_usd: Uint128::new(1 << 63),
+            tokens_per_tier: Uint128::new(1 << 64),
+            tier_multiplier: Uint128::from(1300u128),
+            max_tier: None,
+        };
+        assert!(matches!(
+            calculate_usd_for_tokens(Uint128::new(1 << 65), Uint128::zero(), &pricing, 0),
+            Err(ContractError::MathOverflow { op }) if op == "calculate_usd_for_tokens"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "strict_math")]
+    fn test_strict_math_overflow_fails_the_query() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let info = MessageInfo { sender: Addr::unchecked("creator"), funds: vec![] };
+        instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg(None)).unwrap();
+        let mut pricing = PRICING_CONFIG.load(&deps.storage).unwrap();
+        pricing.base_price_usd = Uint128::one();
+        PRICING_CONFIG.save(&mut deps.storage, &pricing).unwrap();
+
+        let err = query(deps.as_ref(), mock_env(), QueryMsg::CalculateTokens { usd_amount: Uint128::MAX }).unwrap_err();
+        assert!(err.to_string().contains("calculate_tokens_for_usd"), "{}", err);
+    }
+
+    #[test]
+    fn test_buyers_purchased() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        let carol = deps.api.addr_make("carol");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&alice, 100_000_000)).unwrap();
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&alice, 100_000_000)).unwrap();
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&bob, 50_000_000)).unwrap();
+
+        let addresses = vec![bob.to_string(), alice.to_string(), carol.to_string()];
+        let res: BuyersPurchasedResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::BuyersPurchased { addresses }).unwrap()).unwrap();
+        assert_eq!(
+            res.buyers,
+            vec![
+                BuyerPurchased {
+                    address: bob.to_string(),
+                    total_tokens: Uint128::from(2_000_000_000_000u128),
+                    total_usd: Uint128::from(50_000_000u128),
+                },
+                BuyerPurchased {
+                    address: alice.to_string(),
+                    total_tokens: Uint128::from(8_000_000_000_000u128),
+                    total_usd: Uint128::from(200_000_000u128),
+                },
+                BuyerPurchased { address: carol.to_string(), total_tokens: Uint128::zero(), total_usd: Uint128::zero() },
+            ]
+        );
+
+        let too_many = vec![alice.to_string(); MAX_LIMIT as usize + 1];
+        query(deps.as_ref(), env, QueryMsg::BuyersPurchased { addresses: too_many }).unwrap_err();
+    }
+
+    #[test]
+    fn test_twap_over_purchase_history() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        // One $100 purchase at the base price fills a tier: prices run $0.025, $0.0325, $0.04225
+        let msg = InstantiateMsg {
+            tokens_per_tier: Some(Uint128::from(4_000_000_000_000u128)),
+            ..mock_instantiate_msg(None)
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let twap = |deps: Deps, env: &Env, lookback_seconds: u64| -> StdResult<TwapResponse> {
+            from_json(query(deps, env.clone(), QueryMsg::Twap { lookback_seconds })?)
+        };
+        assert!(twap(deps.as_ref(), &env, 60).is_err());
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 130_000_000)).unwrap();
+        env.block.time = env.block.time.plus_seconds(300);
+
+        // 100s at $0.0325 and 300s at $0.04225
+        let res = twap(deps.as_ref(), &env, 400).unwrap();
+        assert_eq!(res, TwapResponse { twap_price_usd: Uint128::from(39812u128), window_seconds: 400 });
+
+        // The window can start between observations
+        let res = twap(deps.as_ref(), &env, 350).unwrap();
+        assert_eq!(res.twap_price_usd, Uint128::from(40857u128));
+        let res = twap(deps.as_ref(), &env, 300).unwrap();
+        assert_eq!(res.twap_price_usd, Uint128::from(42250u128));
+
+        // Lookbacks before the first purchase shrink to the recorded history
+        let res = twap(deps.as_ref(), &env, 10_000).unwrap();
+        assert_eq!(res, TwapResponse { twap_price_usd: Uint128::from(39812u128), window_seconds: 400 });
+
+        assert!(twap(deps.as_ref(), &env, 0).is_err());
+        assert!(twap(deps.as_ref(), &env, MAX_TWAP_LOOKBACK_SECONDS + 1).is_err());
+    }
+
+    #[test]
+    fn test_twap_observations_are_pruned() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let mut env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let start = env.block.time.seconds();
+        for _ in 0..3 {
+            execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 1_000_000)).unwrap();
+            env.block.time = env.block.time.plus_days(20);
+        }
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 1_000_000)).unwrap();
+
+        // At day 60 the cutoff is day 30: day 0 goes, day 20 stays as the price in effect at the cutoff
+        let observed: Vec<u64> = TWAP_OBSERVATIONS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()
+            .unwrap();
+        assert_eq!(observed, vec![start + 20 * 86_400, start + 40 * 86_400, start + 60 * 86_400]);
+
+        let res: TwapResponse = from_json(
+            query(deps.as_ref(), env, QueryMsg::Twap { lookback_seconds: MAX_TWAP_LOOKBACK_SECONDS }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.window_seconds, MAX_TWAP_LOOKBACK_SECONDS);
+        assert_eq!(res.twap_price_usd, Uint128::from(25000u128));
+    }
+
+    #[test]
+    fn test_cw20_proceeds_recipient() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let treasury = deps.api.addr_make("treasury");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let set_recipient = |recipient: Option<&Addr>| ExecuteMsg::UpdateProceedsRecipient {
+            cw20_proceeds_recipient: recipient.map(|addr| addr.to_string()),
+        };
+        let err = execute(deps.as_mut(), env.clone(), MessageInfo { sender: buyer.clone(), funds: vec![] }, set_recipient(Some(&buyer))).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), set_recipient(Some(&treasury))).unwrap();
+
+        let forwarded_to = |res: &Response| -> Vec<String> {
+            res.messages
+                .iter()
+                .filter_map(|m| match &m.msg {
+                    CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => Some(String::from_utf8(msg.to_vec()).unwrap()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert_eq!(
+            forwarded_to(&res),
+            vec![format!(r#"{{"transfer":{{"recipient":"{}","amount":"100000000"}}}}"#, treasury)]
+        );
+        assert!(res.attributes.iter().any(|a| a.key == "cw20_forwarded_to" && a.value == treasury.as_str()));
+
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.cw20_proceeds_recipient, Some(treasury.to_string()));
+
+        // Clearing it sends proceeds back to the admin
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), set_recipient(None)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert_eq!(
+            forwarded_to(&res),
+            vec![format!(r#"{{"transfer":{{"recipient":"{}","amount":"100000000"}}}}"#, admin)]
+        );
+    }
+
+    #[test]
+    fn test_inventory_value_usd() {
+        // 1M tokens at $0.025
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let res: InventoryValueUsdResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::InventoryValueUsd {}).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            InventoryValueUsdResponse {
+                sellable: Uint256::from(1_000_000_000_000_000u128),
+                current_price_usd: Uint128::from(25000u128),
+                value_usd: Uint256::from(25_000_000_000u128),
+            }
+        );
+
+        // Still valued at the tier 0 price, though selling 4M tokens would reach tier 1
+        deps.querier.base.bank.update_balance(env.contract.address.as_str(), coins(4_000_000_000_000_000, "ngonka"));
+        let res: InventoryValueUsdResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::InventoryValueUsd {}).unwrap()).unwrap();
+        assert_eq!(res.value_usd, Uint256::from(100_000_000_000u128));
+    }
+
+    #[test]
+    fn test_purchase_hook() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let rewards = deps.api.addr_make("rewards");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let set_hook = |hook_required: bool| ExecuteMsg::UpdatePurchaseHook {
+            purchase_hook: Some(rewards.to_string()),
+            hook_required,
+        };
+        let outsider = MessageInfo {
+            sender: buyer.clone(),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, set_hook(false)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), set_hook(false)).unwrap();
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let hook_call = |res: &Response| -> SubMsg {
+            res.messages
+                .iter()
+                .find(|m| matches!(&m.msg, CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) if contract_addr == rewards.as_str()))
+                .cloned()
+                .unwrap()
+        };
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        let hook = hook_call(&res);
+        assert_eq!(hook.reply_on, ReplyOn::Error);
+        let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &hook.msg else { unreachable!() };
+        assert_eq!(
+            from_json::<PurchaseHookMsg>(msg).unwrap(),
+            PurchaseHookMsg::OnPurchase {
+                buyer: buyer.to_string(),
+                tokens: Uint128::from(4_000_000_000_000u128),
+                usd: Uint128::from(100_000_000u128),
+                tier: 0,
+            }
+        );
+
+        // An optional hook's failure is swallowed
+        let failed = Reply {
+            id: hook.id,
+            payload: Binary::default(),
+            gas_used: 0,
+            result: SubMsgResult::Err("rewards contract out of gas".to_string()),
+        };
+        let res = reply(deps.as_mut(), env.clone(), failed).unwrap();
+        assert_eq!(res.attributes[0].value, "rewards contract out of gas");
+
+        // A required hook is a plain message, so its failure reverts the purchase
+        execute(deps.as_mut(), env.clone(), admin_info, set_hook(true)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert_eq!(hook_call(&res).reply_on, ReplyOn::Never);
+    }
+
+    #[test]
+    fn test_stats_day_at_extreme_times() {
+        // No wrap at the top of the range, and a grace longer than the time saturates to day 0
+        assert_eq!(stats_day(u64::MAX, 0), u64::MAX / 86400);
+        assert_eq!(stats_day(u64::MAX, MAX_ROLLOVER_GRACE_SECONDS), (u64::MAX - MAX_ROLLOVER_GRACE_SECONDS) / 86400);
+        assert_eq!(stats_day(10, 60), 0);
+
+        // The latest time a block can carry
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let buyer = deps.api.addr_make("buyer");
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_nanos(u64::MAX);
+        let last_day = env.block.time.seconds() / 86400;
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        // Time stepping back a day keeps counting against the stored day instead of resetting it
+        env.block.time = env.block.time.minus_seconds(86400);
+        execute(deps.as_mut(), env.clone(), cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        let stats: DailyStatsResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.current_day, last_day);
+        assert_eq!(stats.tokens_sold_today, Uint128::from(8_000_000_000_000u128));
+        let archived: DailyStatsForDayResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::DailyStatsForDay { day: last_day - 1 }).unwrap()).unwrap();
+        assert!(archived.tokens_sold.is_zero());
+    }
+
+    #[test]
+    fn test_cost_for_tokens_round_trips_through_quote() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        // Tiers of 4,000 tokens at $0.025, $0.0325, $0.04225
+        let msg = InstantiateMsg {
+            tokens_per_tier: Some(Uint128::from(4_000_000_000_000u128)),
+            ..mock_instantiate_msg(None)
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // 4,000 + 4,000 + 2,000 tokens: $100 + $130 + $84.50
+        let cost: CostForTokensResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::CostForTokens { token_amount: Uint128::from(10_000_000_000_000u128) })
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            cost,
+            CostForTokensResponse {
+                usd_cost: Uint128::from(314_500_000u128),
+                start_tier: 0,
+                end_tier: 2,
+                average_price: Uint128::from(31450u128),
+            }
+        );
+
+        let wusdt = deps.api.addr_make("wusdt");
+        let quote: QuoteForCw20Response = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::QuoteForCw20 { cw20_contract: wusdt.to_string(), amount: cost.usd_cost })
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(quote.tokens, Uint128::from(10_000_000_000_000u128));
+
+        let too_many = QueryMsg::CostForTokens { token_amount: Uint128::from(200_000_000_000_000u128) };
+        assert!(query(deps.as_ref(), env, too_many).is_err());
+    }
+
+    #[test]
+    fn test_pause_only_admin_cannot_withdraw() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let guardian = deps.api.addr_make("guardian");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let guardian_info = MessageInfo {
+            sender: guardian.clone(),
+            funds: vec![],
+        };
+        let grant = |role: AdminRole| ExecuteMsg::GrantRole { address: guardian.to_string(), role };
+
+        // Only the super-admin grants, and only known capabilities
+        let err = execute(deps.as_mut(), env.clone(), guardian_info.clone(), grant(AdminRole::PAUSE)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), grant(AdminRole(1 << 6))).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAdminRole { bits: 64 }));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), grant(AdminRole::PAUSE)).unwrap();
+
+        let role: AdminRoleResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::AdminRole { address: guardian.to_string() }).unwrap()).unwrap();
+        assert_eq!(role, AdminRoleResponse { super_admin: false, role: AdminRole::PAUSE });
+
+        let pause = ExecuteMsg::Pause { flags: None, auto_resume_after: None };
+        execute(deps.as_mut(), env.clone(), guardian_info.clone(), pause.clone()).unwrap();
+
+        let withdraw = ExecuteMsg::WithdrawNativeTokens { amount: Uint128::from(1_000u128), recipient: guardian.to_string() };
+        let err = execute(deps.as_mut(), env.clone(), guardian_info.clone(), withdraw.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            guardian_info.clone(),
+            ExecuteMsg::UpdateDailyLimit { daily_limit_bp: Some(Uint128::from(500u128)) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // The super-admin keeps every capability
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), withdraw).unwrap();
+
+        // Revoking the last capability removes the pause right too
+        let revoke = ExecuteMsg::RevokeRole { address: guardian.to_string(), role: AdminRole::PAUSE };
+        execute(deps.as_mut(), env.clone(), admin_info, revoke).unwrap();
+        let err = execute(deps.as_mut(), env, guardian_info, pause).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn test_role_admins_limits_and_allowlist() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let pricing = deps.api.addr_make("pricing");
+        let tokens = deps.api.addr_make("tokens");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        for (address, role) in [(&pricing, AdminRole::UPDATE_PRICING), (&tokens, AdminRole::MANAGE_TOKENS)] {
+            let grant = ExecuteMsg::GrantRole { address: address.to_string(), role };
+            execute(deps.as_mut(), env.clone(), admin_info.clone(), grant).unwrap();
+        }
+        let pricing_info = MessageInfo {
+            sender: pricing.clone(),
+            funds: vec![],
+        };
+        let tokens_info = MessageInfo {
+            sender: tokens.clone(),
+            funds: vec![],
+        };
+
+        // Limits and fees belong to UPDATE_PRICING
+        let daily_limit = ExecuteMsg::UpdateDailyLimit { daily_limit_bp: Some(Uint128::from(500u128)) };
+        execute(deps.as_mut(), env.clone(), pricing_info.clone(), daily_limit.clone()).unwrap();
+        let fee = ExecuteMsg::UpdateFee { fee_bp: Uint128::from(100u128), fee_recipient: Some(pricing.to_string()) };
+        execute(deps.as_mut(), env.clone(), pricing_info.clone(), fee).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), tokens_info.clone(), daily_limit).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // the allowlist to MANAGE_TOKENS
+        let allow = ExecuteMsg::AddToAllowlist { address: tokens.to_string() };
+        execute(deps.as_mut(), env.clone(), tokens_info.clone(), allow.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), pricing_info.clone(), allow).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // and redirecting proceeds stays with the super-admin
+        let proceeds = ExecuteMsg::UpdateProceedsRecipient { cw20_proceeds_recipient: Some(pricing.to_string()) };
+        for info in [pricing_info, tokens_info] {
+            let err = execute(deps.as_mut(), env.clone(), info, proceeds.clone()).unwrap_err();
+            assert!(matches!(err, ContractError::Unauthorized {}));
+        }
+    }
+
+    #[test]
+    fn test_sweep_cw20_needs_an_admin() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let sweeper = deps.api.addr_make("sweeper");
+        deps.querier.base.update_wasm(|_| SystemResult::Ok(ContractResult::Ok(Binary::from(br#"{"balance":"5"}"#))));
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+        // A role left behind from before the pool lost its admin
+        ADMIN_ROLES.save(deps.as_mut().storage, &sweeper, &AdminRole::WITHDRAW).unwrap();
+
+        let sweeper_info = MessageInfo {
+            sender: sweeper,
+            funds: vec![],
+        };
+        let sweep = ExecuteMsg::SweepCw20 { cw20_contract: deps.api.addr_make("wusdt").to_string() };
+        let err = execute(deps.as_mut(), env, sweeper_info, sweep).unwrap_err();
+        assert!(err.to_string().contains("No admin or proceeds recipient set"));
+    }
+
+    #[test]
+    fn test_sweep_cw20_follows_proceeds_recipient() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let treasury = deps.api.addr_make("treasury");
+        deps.querier.base.update_wasm(|_| SystemResult::Ok(ContractResult::Ok(Binary::from(br#"{"balance":"5"}"#))));
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let sweep = ExecuteMsg::SweepCw20 { cw20_contract: deps.api.addr_make("wusdt").to_string() };
+        let swept_to = |res: &Response| res.attributes.iter().find(|a| a.key == "recipient").unwrap().value.clone();
+
+        let res = execute(deps.as_mut(), env.clone(), admin_info.clone(), sweep.clone()).unwrap();
+        assert_eq!(swept_to(&res), admin.to_string());
+
+        let set_recipient = ExecuteMsg::UpdateProceedsRecipient { cw20_proceeds_recipient: Some(treasury.to_string()) };
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), set_recipient).unwrap();
+        let res = execute(deps.as_mut(), env, admin_info, sweep).unwrap();
+        assert_eq!(swept_to(&res), treasury.to_string());
+    }
+
+    #[test]
+    fn test_calculate_tokens_clamped() {
+        let clamped = |balance: u128, usd_amount: u128| -> ClampedTokenCalculationResponse {
+            let mut deps = mock_deps_with_balance(balance);
+            let env = mock_env();
+            let info = MessageInfo {
+                sender: Addr::unchecked("creator"),
+                funds: vec![],
+            };
+            instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+            let query_msg = QueryMsg::CalculateTokensClamped { usd_amount: Uint128::from(usd_amount) };
+            from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap()
+        };
+
+        // $100 buys 4,000 tokens with plenty of balance and limit left
+        let res = clamped(1_000_000_000_000_000, 100_000_000);
+        assert_eq!(
+            res,
+            ClampedTokenCalculationResponse {
+                tokens: Uint128::from(4_000_000_000_000u128),
+                unclamped_tokens: Uint128::from(4_000_000_000_000u128),
+                clamped: false,
+                limited_by: TokenLimit::None,
+            }
+        );
+
+        // Only 1,000 tokens in the pool
+        let res = clamped(1_000_000_000_000, 100_000_000);
+        assert_eq!(res.tokens, Uint128::from(1_000_000_000_000u128));
+        assert!(res.clamped);
+        assert_eq!(res.limited_by, TokenLimit::Balance);
+
+        // $1M would buy far more than today's 12M-token limit (10% of 120M)
+        let res = clamped(100_000_000_000_000_000, 1_000_000_000_000);
+        assert_eq!(res.tokens, Uint128::from(12_000_000_000_000_000u128));
+        assert!(res.unclamped_tokens > res.tokens);
+        assert_eq!(res.limited_by, TokenLimit::DailyLimit);
+    }
+
+    #[test]
+    fn test_event_prefix() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let set_prefix = |prefix: Option<&str>| ExecuteMsg::UpdateEventPrefix {
+            event_prefix: prefix.map(str::to_string),
+        };
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), set_prefix(Some("LP_"))).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidEventPrefix { .. }));
+        let res = execute(deps.as_mut(), env.clone(), admin_info.clone(), set_prefix(Some("lp"))).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "lp_method" && a.value == "update_event_prefix"));
+
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "lp_buyer" && a.value == buyer.as_str()));
+        assert!(res.attributes.iter().all(|a| a.key.starts_with("lp_")));
+
+        // Clearing the prefix restores the bare keys
+        execute(deps.as_mut(), env.clone(), admin_info, set_prefix(None)).unwrap();
+        let res = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "buyer"));
+    }
+
+    #[test]
+    fn test_max_tier_freezes_price() {
+        use crate::state::calculate_multi_tier_purchase;
+
+        let pricing_config = PricingConfig {
+            base_price_usd: Uint128::from(25000u128),
+            tokens_per_tier: Uint128::from(3_000_000_000_000_000u128),
+            tier_multiplier: Uint128::from(1300u128),
+            max_tier: Some(1),
+        };
+
+        // Tier 4 sells at the tier 1 price of $0.0325: $65,000 -> 2M tokens
+        let (tokens, _, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
+            Uint128::from(65_000_000_000u128),
+            Uint128::from(12_000_000_000_000_000u128),
+            &pricing_config,
+            9,
+        ).unwrap();
+        assert_eq!(tokens, Uint128::from(2_000_000_000_000_000u128));
+        assert_eq!((start_tier, end_tier), (4, 4));
+        assert_eq!(avg_price, Uint128::from(32500u128));
+
+        // Crossing from tier 4 into tier 5 keeps the same price
+        let (tokens, _, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
+            Uint128::from(32_500_000_000u128),
+            Uint128::from(14_500_000_000_000_000u128),
+            &pricing_config,
+            9,
+        ).unwrap();
+        assert_eq!(tokens, Uint128::from(1_000_000_000_000_000u128));
+        assert_eq!((start_tier, end_tier), (4, 5));
+        assert_eq!(avg_price, Uint128::from(32500u128));
+
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::UpdatePricingConfig {
+            base_price_usd: None,
+            tokens_per_tier: None,
+            tier_multiplier: None,
+            max_tier: Some(0),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let pricing: PricingInfoResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::PricingInfo {}).unwrap()).unwrap();
+        assert_eq!(pricing.max_tier, Some(0));
+        assert_eq!(pricing.next_tier_price, pricing.current_price_usd);
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let cw20_info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+
+        // Off by default: anyone can buy
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let require = ExecuteMsg::SetRequireAllowlist { require_allowlist: true };
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), require.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), require).unwrap();
+
+        let err = execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::BuyerNotAllowed { .. }));
+
+        let add = ExecuteMsg::AddToAllowlist { address: buyer.to_string() };
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), add).unwrap();
+        let allowlisted: IsAllowlistedResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::IsAllowlisted { address: buyer.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert!(allowlisted.allowlisted);
+        execute(deps.as_mut(), env.clone(), cw20_info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let remove = ExecuteMsg::RemoveFromAllowlist { address: buyer.to_string() };
+        execute(deps.as_mut(), env.clone(), admin_info, remove).unwrap();
+        let err = execute(deps.as_mut(), env, cw20_info, purchase_msg(&buyer, 100_000_000)).unwrap_err();
+        assert!(matches!(err, ContractError::BuyerNotAllowed { .. }));
+    }
+
+    #[test]
+    fn test_withdraw_native_batch() {
+        let mut deps = mock_deps_with_balance(1_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let payouts: Vec<(String, Uint128)> = ["alice", "bob", "carol"]
+            .iter()
+            .zip([100_000u128, 200_000, 300_000])
+            .map(|(name, amount)| (deps.api.addr_make(name).to_string(), Uint128::from(amount)))
+            .collect();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::WithdrawNativeBatch { payouts: payouts.clone() },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        for (msg, (recipient, amount)) in res.messages.iter().zip(&payouts) {
+            match &msg.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount: sent }) => {
+                    assert_eq!(to_address, recipient);
+                    assert_eq!(sent, &coins(amount.u128(), "ngonka"));
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert!(res.attributes.iter().any(|a| a.key == "total_amount" && a.value == "600000"));
+
+        // 600,000 + 500,000 exceeds the 1,000,000 held
+        let mut over = payouts;
+        over.push((deps.api.addr_make("dave").to_string(), Uint128::from(500_000u128)));
+        let err = execute(deps.as_mut(), env.clone(), admin_info.clone(), ExecuteMsg::WithdrawNativeBatch { payouts: over })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { available: 1_000_000, needed: 1_100_000 }));
+
+        let too_many = vec![(deps.api.addr_make("alice").to_string(), Uint128::one()); MAX_BATCH_PAYOUTS + 1];
+        let err = execute(deps.as_mut(), env, admin_info, ExecuteMsg::WithdrawNativeBatch { payouts: too_many })
+            .unwrap_err();
+        assert!(matches!(err, ContractError::TooManyPayouts { .. }));
+    }
+
+    #[test]
+    fn test_force_return_cw20_admin_only() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let msg = ExecuteMsg::ForceReturnCw20 {
+            cw20_contract: deps.api.addr_make("revoked_cw20").to_string(),
+            recipient: deps.api.addr_make("buyer").to_string(),
+            amount: Uint128::from(1_000u128),
+        };
+
+        let outsider = MessageInfo {
+            sender: deps.api.addr_make("outsider"),
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env.clone(), outsider, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env, admin_info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert!(res.attributes.iter().any(|a| a.key == "validation_bypassed" && a.value == "true"));
+    }
+
+    #[test]
+    fn test_daily_stats_for_day() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            admin: Some(deps.api.addr_make("admin").to_string()),
+            daily_limit_bp: Some(Uint128::from(100u128)), // 1%
+            base_price_usd: None,
+            tokens_per_tier: None,
+            tier_multiplier: None,
+            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
+            fallback_denom: None,
+            native_decimals: None,
+            max_tier: None,
+            require_allowlist: None,
+            rollover_grace_seconds: None,
+            allow_daily_stats_reset: None,
+        };
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let archived_day = env.block.time.seconds() / 86400 - 3;
+        DAILY_STATS_HISTORY
+            .save(
+                deps.as_mut().storage,
+                archived_day,
+                &DailyStats {
+                    current_day: archived_day,
+                    usd_received_today: Uint128::from(500_000_000u128),
+                    tokens_sold_today: Uint128::from(20_000_000_000_000u128),
+                },
+            )
+            .unwrap();
+
+        let response: DailyStatsForDayResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::DailyStatsForDay { day: archived_day }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response.usd_received, Uint128::from(500_000_000u128));
+        assert_eq!(response.tokens_sold, Uint128::from(20_000_000_000_000u128));
+        assert_eq!(response.daily_token_limit, Uint128::from(1_200_000_000_000_000u128));
+
+        // Days without a record report zeros
+        let response: DailyStatsForDayResponse = from_json(
+            query(deps.as_ref(), env, QueryMsg::DailyStatsForDay { day: archived_day - 1 }).unwrap(),
+        )
+        .unwrap();
+        assert!(response.usd_received.is_zero());
+        assert!(response.tokens_sold.is_zero());
+    }
+
+    #[test]
+    fn test_received_from_tracked_without_admin() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+        let cw20 = deps.api.addr_make("wusdt");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let info = MessageInfo {
+            sender: cw20.clone(),
+            funds: vec![],
+        };
+        for _ in 0..2 {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+            // Only the native send; the CW20 stays in the contract
+            assert_eq!(res.messages.len(), 1);
+        }
+
+        let response: ReceivedFromResponse = from_json(
+            query(deps.as_ref(), env, QueryMsg::ReceivedFrom { buyer: buyer.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            response.received,
+            vec![Cw20Amount {
+                cw20_contract: cw20.to_string(),
+                amount: Uint128::from(200_000_000u128),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_purchase_with_native() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::AddPaymentToken { denom: "uusdc".to_string(), usd_rate: Uint128::one(), decimals: Some(6) },
+        )
+        .unwrap();
+
+        let info = MessageInfo {
+            sender: buyer.clone(),
+            funds: coins(100_000_000, "uusdc"), // $100
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::PurchaseWithNative {}).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send { to_address: buyer.to_string(), amount: coins(4_000_000_000_000, "ngonka") }.into()
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            BankMsg::Send { to_address: admin.to_string(), amount: coins(100_000_000, "uusdc") }.into()
+        );
+
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.total_tokens_sold, Uint128::from(4_000_000_000_000u128));
+    }
+
+    #[test]
+    fn test_purchase_with_native_follows_proceeds_recipient() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let treasury = deps.api.addr_make("treasury");
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        let admin_info = MessageInfo {
+            sender: admin.clone(),
+            funds: vec![],
+        };
+        let add_uusdc = ExecuteMsg::AddPaymentToken { denom: "uusdc".to_string(), usd_rate: Uint128::one(), decimals: Some(6) };
+        execute(deps.as_mut(), env.clone(), admin_info.clone(), add_uusdc).unwrap();
+        let set_recipient = ExecuteMsg::UpdateProceedsRecipient { cw20_proceeds_recipient: Some(treasury.to_string()) };
+        execute(deps.as_mut(), env.clone(), admin_info, set_recipient).unwrap();
+
+        let info = MessageInfo {
+            sender: buyer.clone(),
+            funds: coins(100_000_000, "uusdc"),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::PurchaseWithNative {}).unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            BankMsg::Send { to_address: treasury.to_string(), amount: coins(100_000_000, "uusdc") }.into()
+        );
+        assert!(res.attributes.iter().any(|a| a.key == "payment_forwarded_to" && a.value == treasury.as_str()));
+
+        // With no admin and no recipient the payment stays in the pool and no destination is reported
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.admin = String::new();
+        config.cw20_proceeds_recipient = None;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+        let info = MessageInfo {
+            sender: buyer.clone(),
+            funds: coins(100_000_000, "uusdc"),
+        };
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::PurchaseWithNative {}).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert!(!res.attributes.iter().any(|a| a.key == "payment_forwarded_to"));
+    }
+
+    #[test]
+    fn test_purchase_with_native_rejects_unknown_denom() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let info = MessageInfo {
+            sender: buyer.clone(),
+            funds: coins(100_000_000, "uatom"),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::PurchaseWithNative {}).unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAccepted { .. }));
+
+        let info = MessageInfo {
+            sender: buyer,
+            funds: vec![],
+        };
+        let err = execute(deps.as_mut(), env, info, ExecuteMsg::PurchaseWithNative {}).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidToken { .. }));
+    }
+
+    #[test]
+    fn test_lifetime_usd_and_average_price() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), purchase_msg(&buyer, 100_000_000)).unwrap();
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 50_000_000)).unwrap();
+
+        let pricing: PricingInfoResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::PricingInfo {}).unwrap()).unwrap();
+        assert_eq!(pricing.total_usd_received, Uint128::from(150_000_000u128));
+        assert_eq!(pricing.total_tokens_sold, Uint128::from(6_000_000_000_000u128));
+        assert_eq!(pricing.lifetime_average_price, Uint128::from(25000u128));
+    }
+
+    #[test]
+    fn test_pricing_info_human() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 100_000_000)).unwrap();
+
+        let human: PricingInfoHumanResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::PricingInfoHuman {}).unwrap()).unwrap();
+        assert_eq!(human.current_tier, 0);
+        assert_eq!(human.current_price_usd_display, "0.025000");
+        assert_eq!(human.total_tokens_sold_display, "4000.000000000");
+        assert_eq!(human.tokens_per_tier_display, "3000000.000000000");
+        assert_eq!(human.tier_multiplier_display, "1.300");
+        assert_eq!(human.next_tier_price_display, "0.032500");
+        assert_eq!(human.total_usd_received_display, "100.000000");
+
+        assert_eq!(format_decimal(Uint128::zero(), USD_DECIMALS), "0.000000");
+        assert_eq!(format_decimal(Uint128::one(), TOKEN_DECIMALS), "0.000000001");
+        assert_eq!(format_decimal(Uint128::new(42), 0), "42");
+    }
+
+    #[cfg(feature = "debug_grpc")]
+    #[test]
+    fn test_raw_grpc_returns_chain_response() {
+        let mut deps = mock_deps_with_balance(0);
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let mut request = Vec::new();
+        QueryValidateWrappedTokenForTradeRequest { contract_address: deps.api.addr_make("wusdt").to_string() }
+            .encode(&mut request)
+            .unwrap();
+        let msg = QueryMsg::RawGrpc {
+            path: "/inference.inference.Query/ValidateWrappedTokenForTrade".to_string(),
+            data_base64: Binary::from(request).to_base64(),
+        };
+        let raw: Binary = from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        let res = QueryValidateWrappedTokenForTradeResponse::decode(raw.as_slice()).unwrap();
+        assert!(res.is_valid);
+
+        // Chain-side failures and bad input come back as query errors naming the cause
+        let msg = QueryMsg::RawGrpc {
+            path: "/inference.inference.Query/ApprovedTokensForTrade".to_string(),
+            data_base64: String::new(),
+        };
+        let err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        assert!(err.to_string().contains("approved tokens unavailable"));
+        let msg = QueryMsg::RawGrpc {
+            path: "/inference.inference.Query/ValidateWrappedTokenForTrade".to_string(),
+            data_base64: "not base64!".to_string(),
+        };
+        query(deps.as_ref(), env, msg).unwrap_err();
+    }
+
+    #[test]
+    fn test_grpc_errors_are_classified() {
+        // The bare mock querier supports no gRPC at all: a system error, worth retrying
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let cw20 = deps.api.addr_make("wusdt").to_string();
+        let res: TestBridgeValidationResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::TestBridgeValidation { cw20_contract: cw20 }).unwrap(),
+        )
+        .unwrap();
+        assert!(!res.is_valid);
+        let error = res.error.unwrap();
+        assert_eq!(error.kind, GrpcErrorKind::System);
+        assert!(error.retryable);
+
+        let err = query(deps.as_ref(), env.clone(), QueryMsg::TestApprovedTokens {}).unwrap_err();
+        assert!(err.to_string().contains("[grpc:system:retryable=true]"), "{}", err);
+
+        // The pool querier's module rejects ApprovedTokensForTrade: a contract error, not retryable
+        let deps = mock_deps_with_balance(0);
+        let err = query(deps.as_ref(), env, QueryMsg::TestApprovedTokens {}).unwrap_err();
+        assert!(err.to_string().contains("[grpc:contract:retryable=false]"), "{}", err);
+    }
+
+    #[test]
+    fn test_proto_errors_name_the_path() {
+        let deps = mock_deps_with_balance(0);
+        let path = "/inference.inference.Query/ValidateWrappedTokenForTrade";
+
+        // The answer is a bool at tag 1, which can't decode as TotalSupply's repeated coins
+        let err = query_proto::<QueryValidateWrappedTokenForTradeRequest, QueryTotalSupplyResponse>(
+            deps.as_ref(),
+            path,
+            &QueryValidateWrappedTokenForTradeRequest { contract_address: "wusdt".to_string() },
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(&format!("Decode response from {}", path)), "{}", err);
+
+        let path = "/inference.inference.Query/ApprovedTokensForTrade";
+        let err = query_proto::<EmptyRequest, QueryApprovedTokensForTradeResponseProto>(
+            deps.as_ref(),
+            path,
+            &EmptyRequest::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(path), "{}", err);
+    }
+
+    #[test]
+    fn test_bridge_validation_rejects_malformed_address() {
+        let mut deps = mock_deps_with_balance(0);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
+
+        // The stub chain approves everything, so a false here means no gRPC was issued
+        let res: TestBridgeValidationResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::TestBridgeValidation { cw20_contract: "not-a-bech32-address".to_string() },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(!res.is_valid);
+
+        let info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::AddPaymentToken {
+            denom: "cw20:not-a-bech32-address".to_string(),
+            usd_rate: Uint128::one(),
+            decimals: None,
+        };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidToken { .. }));
+    }
+
+    #[test]
+    fn test_daily_limit_used_bp() {
+        let mut deps = mock_deps_with_balance(10_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
+
+        // Without a total supply the limit is zero, which must not divide by zero
+        let mut msg = mock_instantiate_msg(None);
+        msg.total_supply = None;
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.daily_token_limit, Uint128::zero());
+        assert_eq!(stats.daily_limit_used_bp, Uint128::zero());
+
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        // $30,000 buys 1.2M tokens: 10% of the 12M daily limit
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 30_000_000_000)).unwrap();
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::DailyStats {}).unwrap()).unwrap();
+        assert_eq!(stats.daily_limit_used_bp, Uint128::from(1000u128));
     }
-}
 
-// Generic helper: encode request proto and decode response proto
-fn query_proto<TRequest, TResponse>(deps: Deps, path: &str, request: &TRequest) -> StdResult<TResponse>
-where
-    TRequest: prost::Message,
-    TResponse: prost::Message + Default,
-{
-    let mut buf = Vec::new();
-    request
-        .encode(&mut buf)
-        .map_err(|e| StdError::msg(format!("Encode request: {}", e)))?;
-    let bytes = query_grpc(deps, path, Binary::from(buf))?;
-    TResponse::decode(bytes.as_slice())
-        .map_err(|e| StdError::msg(format!("Decode response: {}", e)))
-}
+    #[test]
+    fn test_purchase_fee() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
+        let env = mock_env();
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
+        let treasury = deps.api.addr_make("treasury");
 
-fn query_daily_stats(deps: Deps, env: Env) -> StdResult<DailyStatsResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let mut daily_stats = DAILY_STATS.load(deps.storage)?;
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
 
-    let current_day = env.block.time.seconds() / 86400;
+        let admin_info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info.clone(),
+            ExecuteMsg::UpdateFee { fee_bp: Uint128::from(10000u128), fee_recipient: Some(treasury.to_string()) },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBasisPoints { .. }));
+
+        // 2.5% fee
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin_info,
+            ExecuteMsg::UpdateFee { fee_bp: Uint128::from(250u128), fee_recipient: Some(treasury.to_string()) },
+        )
+        .unwrap();
 
-    // Reset if new day
-    if daily_stats.current_day != current_day {
-        daily_stats.current_day = current_day;
-        daily_stats.usd_received_today = Uint128::zero();
-        daily_stats.tokens_sold_today = Uint128::zero();
-    }
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        let res = execute(deps.as_mut(), env, info, purchase_msg(&buyer, 100_000_000)).unwrap();
 
-    let daily_token_limit = config
-        .total_supply
-        .checked_mul(Uint128::from(config.daily_limit_bp))
-        .map(|x| x.checked_div(Uint128::from(10000u128)).unwrap_or_default())
-        .unwrap_or_default();
+        // 4000 tokens bought: 100 to the treasury, 3900 to the buyer, then the CW20 forward
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: buyer.to_string(), amount: coins(3_900_000_000_000, "ngonka") })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: treasury.to_string(), amount: coins(100_000_000_000, "ngonka") })
+        );
+        assert!(res.attributes.iter().any(|a| a.key == "fee_amount" && a.value == "100000000000"));
+    }
 
-    let tokens_available_today = daily_token_limit
-        .checked_sub(daily_stats.tokens_sold_today)
-        .unwrap_or_default();
+    #[test]
+    fn test_usd_to_next_tier() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
 
-    Ok(DailyStatsResponse {
-        current_day: daily_stats.current_day,
-        usd_received_today: daily_stats.usd_received_today,
-        tokens_sold_today: daily_stats.tokens_sold_today,
-        tokens_available_today,
-        daily_token_limit,
-        total_supply: config.total_supply,
-    })
-}
+        let info = MessageInfo {
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
+        };
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
 
-fn query_native_balance(deps: Deps, env: Env) -> StdResult<NativeBalanceResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let balance = deps
-        .querier
-        .query_balance(&env.contract.address, &config.native_denom)?;
+        // Sell half of tier 0 (1.5M tokens at $0.025 = $37,500)
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), purchase_msg(&buyer, 37_500_000_000)).unwrap();
+
+        let res: UsdToNextTierResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::UsdToNextTier {}).unwrap()).unwrap();
+        assert_eq!(res.current_tier, 0);
+        assert_eq!(res.tokens_remaining_in_tier, Uint128::from(1_500_000_000_000_000u128));
+        assert_eq!(res.usd_to_next_tier, Uint128::from(37_500_000_000u128));
+
+        // Spending exactly that amount lands on the boundary without paying tier 1 prices
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, res.usd_to_next_tier.u128())).unwrap();
+        let pricing: PricingInfoResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::PricingInfo {}).unwrap()).unwrap();
+        assert_eq!(pricing.total_tokens_sold, Uint128::from(3_000_000_000_000_000u128));
+        assert_eq!(pricing.lifetime_average_price, Uint128::from(25000u128));
+    }
 
-    Ok(NativeBalanceResponse { balance })
-}
+    #[test]
+    fn test_native_denom_skips_bridged_and_factory_denoms() {
+        /// Answers the bank TotalSupply gRPC with the whole list, ignoring pagination
+        struct SupplyQuerier {
+            supply: Vec<&'static str>,
+        }
 
-fn query_pricing_info(deps: Deps) -> StdResult<PricingInfoResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+        impl Querier for SupplyQuerier {
+            fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+                let request: QueryRequest<Empty> = from_json(bin_request).unwrap();
+                match request {
+                    QueryRequest::Grpc(GrpcQuery { path, .. }) if path.ends_with("/TotalSupply") => {
+                        let supply = self
+                            .supply
+                            .iter()
+                            .map(|denom| CoinProto { denom: denom.to_string(), amount: "1".to_string() })
+                            .collect();
+                        let response = QueryTotalSupplyResponse { supply, pagination: None };
+                        SystemResult::Ok(ContractResult::Ok(Binary::from(response.encode_to_vec())))
+                    }
+                    _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "test".to_string() }),
+                }
+            }
+        }
 
-    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
-    let current_price = calculate_current_price(
-        pricing_config.base_price_usd,
-        current_tier,
-        pricing_config.tier_multiplier,
-    );
+        let deps_with_supply = |supply: Vec<&'static str>| OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: SupplyQuerier { supply },
+            custom_query_type: PhantomData::<Empty>,
+        };
 
-    // Calculate next tier info - token count needed for next tier
-    let next_tier_at = pricing_config.tokens_per_tier.checked_mul(Uint128::from((current_tier + 1) as u128)).unwrap_or(Uint128::zero());
-    let next_tier_price = calculate_current_price(
-        pricing_config.base_price_usd,
-        current_tier + 1,
-        pricing_config.tier_multiplier,
-    );
+        let deps = deps_with_supply(vec!["ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2", "factory/gonka1xyz/share", "ngonka"]);
+        assert_eq!(get_native_denom(deps.as_ref(), "utestgonka").unwrap(), "ngonka");
 
-    Ok(PricingInfoResponse {
-        current_tier,
-        current_price_usd: current_price,
-        total_tokens_sold: config.total_tokens_sold,
-        tokens_per_tier: pricing_config.tokens_per_tier,
-        base_price_usd: pricing_config.base_price_usd,
-        tier_multiplier: pricing_config.tier_multiplier,
-        next_tier_at,
-        next_tier_price,
-    })
-}
+        // Nothing that can be the base denom: the fallback is used rather than a bridged denom
+        let deps = deps_with_supply(vec!["ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"]);
+        assert_eq!(get_native_denom(deps.as_ref(), "utestgonka").unwrap(), "utestgonka");
+    }
 
-fn query_calculate_tokens(deps: Deps, usd_amount: Uint128) -> StdResult<TokenCalculationResponse> {
-    let config = CONFIG.load(deps.storage)?;
-    let pricing_config = PRICING_CONFIG.load(deps.storage)?;
+    /// Answers the bank TotalSupply gRPC like a node: sorted by denom, paged by `limit` and
+    /// `key`, with the next page's first denom as the next key. Records each requested limit.
+    struct PagedSupplyQuerier {
+        supply: Vec<String>,
+        limits: std::cell::RefCell<Vec<u64>>,
+    }
 
-    let current_tier = calculate_current_tier(config.total_tokens_sold, pricing_config.tokens_per_tier);
-    let current_price = calculate_current_price(
-        pricing_config.base_price_usd,
-        current_tier,
-        pricing_config.tier_multiplier,
-    );
+    impl Querier for PagedSupplyQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            let request: QueryRequest<Empty> = from_json(bin_request).unwrap();
+            match request {
+                QueryRequest::Grpc(GrpcQuery { path, data }) if path.ends_with("/TotalSupply") => {
+                    let page = QueryTotalSupplyRequest::decode(data.as_slice()).unwrap().pagination.unwrap_or_default();
+                    self.limits.borrow_mut().push(page.limit);
+                    let mut sorted = self.supply.clone();
+                    sorted.sort();
+                    let start = sorted.iter().position(|denom| denom.as_bytes() >= page.key.as_slice()).unwrap_or(sorted.len());
+                    let end = (start + page.limit as usize).min(sorted.len());
+                    let supply = sorted[start..end]
+                        .iter()
+                        .map(|denom| CoinProto { denom: denom.clone(), amount: "1".to_string() })
+                        .collect();
+                    let pagination = sorted.get(end).map(|next| PageResponse { next_key: next.clone().into_bytes(), total: 0 });
+                    let response = QueryTotalSupplyResponse { supply, pagination };
+                    SystemResult::Ok(ContractResult::Ok(Binary::from(response.encode_to_vec())))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest { kind: "test".to_string() }),
+            }
+        }
+    }
 
-    let tokens = calculate_tokens_for_usd(usd_amount, current_price);
+    fn deps_with_paged_supply(supply: &[&str]) -> OwnedDeps<MockStorage, MockApi, PagedSupplyQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: PagedSupplyQuerier {
+                supply: supply.iter().map(|denom| denom.to_string()).collect(),
+                limits: Default::default(),
+            },
+            custom_query_type: PhantomData,
+        }
+    }
 
-    Ok(TokenCalculationResponse {
-        tokens,
-        current_price,
-        current_tier,
-    })
-}
+    #[test]
+    fn test_native_denom_first_page_is_one_coin() {
+        let deps = deps_with_paged_supply(&["ngonka"]);
+        assert_eq!(get_native_denom(deps.as_ref(), "utestgonka").unwrap(), "ngonka");
+        assert_eq!(*deps.querier.limits.borrow(), vec![1]);
+
+        // What the limit saves: the bytes the contract decodes for one coin against a 50-denom supply
+        let coin = |denom: &str| CoinProto { denom: denom.to_string(), amount: "1000000000000000000".to_string() };
+        let ibc = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+        let one = QueryTotalSupplyResponse { supply: vec![coin("ngonka")], pagination: None };
+        let mut supply = vec![coin(ibc); 49];
+        supply.push(coin("ngonka"));
+        let all = QueryTotalSupplyResponse { supply, pagination: None };
+        assert_eq!(one.encode_to_vec().len(), 31);
+        assert_eq!(all.encode_to_vec().len(), 4588);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::{coins, from_json, Addr, MessageInfo};
-    use std::collections::HashMap;
+    #[test]
+    fn test_native_denom_pages_past_bridged_denoms() {
+        // "factory/..." and "ibc/..." sort before "ngonka", so the first single-coin page misses it
+        let deps = deps_with_paged_supply(&[
+            "ngonka",
+            "factory/gonka1xyz/share",
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2",
+        ]);
+        assert_eq!(get_native_denom(deps.as_ref(), "utestgonka").unwrap(), "ngonka");
+        assert_eq!(*deps.querier.limits.borrow(), vec![1, SUPPLY_PAGE_LIMIT]);
+
+        // Paging ends at the last page when nothing qualifies
+        let deps = deps_with_paged_supply(&["factory/gonka1xyz/share", "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"]);
+        assert_eq!(get_native_denom(deps.as_ref(), "utestgonka").unwrap(), "utestgonka");
+        assert_eq!(deps.querier.limits.borrow().len(), 2);
+    }
 
     #[test]
-    fn proper_instantiation() {
-        let mut deps = mock_dependencies();
+    fn test_fallback_denom_used_when_supply_query_fails() {
+        // The mock chain doesn't answer the bank TotalSupply gRPC
+        let mut deps = mock_deps_with_balance(0);
         let env = mock_env();
 
-        let msg = InstantiateMsg {
-            admin: Some("admin".to_string()),
-            daily_limit_bp: Some(Uint128::from(100u128)), // 1%
-            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
-            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens (9 decimals)
-            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
-            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
-        };
-
+        let mut msg = mock_instantiate_msg(None);
+        msg.fallback_denom = Some("utestgonka".to_string());
         let info = MessageInfo {
             sender: Addr::unchecked("creator"),
-            funds: vec![], // same as &[] before
+            funds: vec![],
         };
-        let res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let config: ConfigResponse = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.native_denom, "utestgonka");
 
-        assert_eq!(res.attributes.len(), 4);
+        // Migration re-resolves the denom with the stored fallback rather than "ngonka"
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.0.1").unwrap();
+        migrate(deps.as_mut(), mock_env(), Binary::default()).unwrap();
+        assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap().native_denom, "utestgonka");
     }
 
     #[test]
-    fn test_pause_resume() {
+    fn test_tier_boundaries() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-
-        // Instantiate
-        let msg = InstantiateMsg {
-            admin: Some("admin".to_string()),
-            daily_limit_bp: Some(Uint128::from(100u128)),
-            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
-            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens (9 decimals)
-            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
-            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
-        };
-
         let info = MessageInfo {
             sender: Addr::unchecked("creator"),
-            funds: vec![], // same as &[] before
+            funds: vec![],
         };
-        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
+
+        let res: TierBoundariesResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::TierBoundaries { count: 3 }).unwrap()).unwrap();
+        assert_eq!(
+            res.tiers,
+            vec![
+                TierBoundary { tier: 0, starts_at_tokens: Uint128::zero(), price_usd: Uint128::from(25000u128) },
+                TierBoundary {
+                    tier: 1,
+                    starts_at_tokens: Uint128::from(3_000_000_000_000_000u128),
+                    price_usd: Uint128::from(32500u128),
+                },
+                TierBoundary {
+                    tier: 2,
+                    starts_at_tokens: Uint128::from(6_000_000_000_000_000u128),
+                    price_usd: Uint128::from(42250u128),
+                },
+            ]
+        );
 
-        // Pause
-        let pause_msg = ExecuteMsg::Pause {};
-        let info = MessageInfo {
-            sender: Addr::unchecked("admin"),
-            funds: vec![], // same as &[] before
-        };
-        execute(deps.as_mut(), env.clone(), info, pause_msg).unwrap();
+        let res: TierBoundariesResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::TierBoundaries { count: 1000 }).unwrap()).unwrap();
+        assert_eq!(res.tiers.len(), MAX_LIMIT as usize);
+    }
 
-        // Check config
-        let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
-        assert!(config.is_paused);
+    #[test]
+    fn test_tier_sales() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000_000);
+        let env = mock_env();
+        let buyer = deps.api.addr_make("buyer");
 
-        // Resume
-        let resume_msg = ExecuteMsg::Resume {};
         let info = MessageInfo {
-            sender: Addr::unchecked("admin"),
-            funds: vec![], // same as &[] before
+            sender: Addr::unchecked("creator"),
+            funds: vec![],
         };
-        execute(deps.as_mut(), env.clone(), info, resume_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(None)).unwrap();
 
-        // Check config
-        let config: ConfigResponse =
-            from_json(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
-        assert!(!config.is_paused);
+        // $80,000: $75,000 fills tier 0 (3M tokens), $5,000 buys ~153,846 tokens at $0.0325
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 80_000_000_000)).unwrap();
+
+        let res: TierSalesResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::TierSales { start_tier: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.tiers.len(), 2);
+        assert_eq!(res.next_start_tier, None);
+        assert_eq!(res.tiers[0], TierSale { tier: 0, tokens_sold: Uint128::from(3_000_000_000_000_000u128) });
+        assert_eq!(res.tiers[1].tier, 1);
+        assert_eq!(res.tiers[1].tokens_sold, Uint128::from(153_846_153_846_153u128));
+
+        let res: TierSalesResponse = from_json(
+            query(deps.as_ref(), env.clone(), QueryMsg::TierSales { start_tier: None, limit: Some(1) }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.tiers.len(), 1);
+        assert_eq!(res.next_start_tier, Some(1));
+
+        let res: TierSalesResponse = from_json(
+            query(deps.as_ref(), env, QueryMsg::TierSales { start_tier: res.next_start_tier, limit: Some(1) }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.tiers.len(), 1);
+        assert_eq!(res.tiers[0].tier, 1);
+        assert_eq!(res.next_start_tier, None);
     }
 
     #[test]
-    fn test_usd_based_tier_calculation() {
-        let mut deps = mock_dependencies();
+    fn test_update_total_supply() {
+        let mut deps = mock_deps_with_balance(1_000_000_000_000_000);
         let env = mock_env();
-
-        // Instantiate with known values
-        let msg = InstantiateMsg {
-            admin: Some("admin".to_string()),
-            daily_limit_bp: Some(Uint128::from(1000u128)), // 10%
-            base_price_usd: Some(Uint128::from(25000u128)), // $0.025 with 6 decimals for USD
-            tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)), // 3 million tokens per tier (9 decimals)
-            tier_multiplier: Some(Uint128::from(1300u128)), // 1.3x
-            total_supply: Some(Uint128::from(120_000_000_000_000_000u128)), // 120M tokens
-        };
+        let admin = deps.api.addr_make("admin");
+        let buyer = deps.api.addr_make("buyer");
 
         let info = MessageInfo {
             sender: Addr::unchecked("creator"),
-            funds: vec![], // same as &[] before
+            funds: vec![],
         };
-        instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg(Some(admin.to_string()))).unwrap();
 
-        // Test tier calculation for $100 USD (100,000,000 micro-units)
-        let usd_amount = Uint128::from(100_000_000u128); // $100
-        let response: TokenCalculationResponse = from_json(
-            &query(deps.as_ref(), env.clone(), QueryMsg::CalculateTokens { usd_amount }).unwrap()
-        ).unwrap();
+        let info = MessageInfo {
+            sender: deps.api.addr_make("wusdt"),
+            funds: vec![],
+        };
+        execute(deps.as_mut(), env.clone(), info, purchase_msg(&buyer, 100_000_000)).unwrap();
 
-        // With $0.025 base price and 10M tokens per tier:
-        // USD per tier = 10,000,000 * 25,000 = 250,000,000,000 micro-USD = $250,000
-        // $100 should be in tier 0 (before first tier)
-        assert_eq!(response.current_tier, 0);
-        assert_eq!(response.current_price, Uint128::from(25000u128)); // $0.025
-        assert_eq!(response.tokens, Uint128::from(4_000_000_000u128)); // 4000 tokens for $100 (100,000,000 * 1,000,000 / 25,000)
-    }
+        // Only the admin may update
+        let info = MessageInfo {
+            sender: buyer,
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::UpdateTotalSupply {
+            total_supply: Uint128::from(200_000_000_000_000_000u128),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
 
-    #[test]
-    fn test_multi_tier_purchase() {
-        use crate::state::{calculate_multi_tier_purchase, PricingConfig};
+        // Cannot drop below what has already been sold
+        let info = MessageInfo {
+            sender: admin,
+            funds: vec![],
+        };
+        let msg = ExecuteMsg::UpdateTotalSupply {
+            total_supply: Uint128::from(3_999_999_999_999u128),
+        };
+        let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTotalSupply { .. }));
 
-        // Test setup: 3M tokens per tier, $0.025 base price, 1.3x multiplier (token-based tiers)
-        let pricing_config = PricingConfig {
-            base_price_usd: Uint128::from(25000u128), // $0.025
-            tokens_per_tier: Uint128::from(3_000_000_000_000_000u128), // 3M tokens with 9 decimals
-            tier_multiplier: Uint128::from(1300u128), // 1.3x multiplier
+        let msg = ExecuteMsg::UpdateTotalSupply {
+            total_supply: Uint128::from(200_000_000_000_000_000u128),
         };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "old_total_supply" && a.value == "120000000000000000"));
+
+        let stats: DailyStatsResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::DailyStats {}).unwrap()).unwrap();
+        // 10% of the new 200M supply
+        assert_eq!(stats.daily_token_limit, Uint128::from(20_000_000_000_000_000u128));
+    }
 
-        // Test 1: Purchase within single tier
-        let (tokens, usd_spent, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
-            Uint128::from(100_000_000u128), // $100
-            Uint128::zero(), // No tokens sold yet
-            &pricing_config,
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(ContractError::Std(StdError::msg("x")).code(), 0);
+        assert_eq!(ContractError::Unauthorized {}.code(), 1);
+        assert_eq!(ContractError::ContractPaused {}.code(), 2);
+        assert_eq!(ContractError::DailyLimitExceeded { available: 0, requested: 1 }.code(), 3);
+        assert_eq!(ContractError::ZeroAmount {}.code(), 6);
+        assert_eq!(ContractError::TokenNotAccepted { token: "x".to_string() }.code(), 9);
+        assert_eq!(
+            ContractError::InvalidTotalSupply {
+                total_supply: Uint128::zero(),
+                total_tokens_sold: Uint128::one(),
+            }
+            .code(),
+            11
         );
-        // Should get 4000 tokens at $0.025 each
-        assert_eq!(tokens, Uint128::from(4_000_000_000_000u128)); // 4000 tokens (with 9 decimals)
-        assert_eq!(usd_spent, Uint128::from(100_000_000u128)); // $100
-        assert_eq!(start_tier, 0);
-        assert_eq!(end_tier, 0); // Still in same tier
-        assert_eq!(avg_price, Uint128::from(25000u128)); // $0.025
-
-        // Test 2: Purchase spanning two tiers
-        // Start with 2.5M tokens already sold (very close to tier boundary of 3M tokens)
-        // Use $20,000 to ensure we cross into tier 1
-        let (tokens, usd_spent, start_tier, end_tier, avg_price) = calculate_multi_tier_purchase(
-            Uint128::from(20_000_000_000u128), // $20,000 purchase
-            Uint128::from(2_500_000_000_000_000u128), // 2.5M tokens already sold (with 9 decimals)
-            &pricing_config,
+        assert_eq!(ContractError::InvalidNativeDecimals { decimals: 19, max: 18 }.code(), 12);
+        assert_eq!(ContractError::BuyerNotAllowed { buyer: "x".to_string() }.code(), 101);
+        assert_eq!(ContractError::Grpc(GrpcQueryError::new(GrpcErrorKind::System, "x")).code(), 13);
+        assert_eq!(ContractError::TooManyPayouts { count: 21, max: 20 }.code(), 14);
+        assert_eq!(ContractError::InvalidRolloverGrace { seconds: 3601, max: 3600 }.code(), 16);
+        assert_eq!(ContractError::MissingCirculatingSupply {}.code(), 17);
+        assert_eq!(ContractError::AboveMaximumPurchase { max: Uint128::one(), got: Uint128::MAX }.code(), 18);
+        assert_eq!(
+            ContractError::Cw20ForwardFailed { cw20_contract: String::new(), amount: Uint128::one(), reason: String::new() }.code(),
+            19
         );
-        
-        
-        // Should span two tiers:
-        // Tier 0: 0.5M tokens left at $0.025 = $12,500  
-        // Tier 1: $7,500 at $0.0325 = ~230,769 tokens
-        // Total: ~730,769 tokens
-        assert!(tokens > Uint128::from(700_000_000_000_000u128)); // > 700k tokens (9 decimals)  
-        assert!(tokens < Uint128::from(800_000_000_000_000u128)); // < 800k tokens (9 decimals)
-        assert_eq!(usd_spent, Uint128::from(20_000_000_000u128)); // Full $20,000 spent
-        assert_eq!(start_tier, 0); // Started in tier 0
-        assert_eq!(end_tier, 1); // Ended in tier 1
-        // Average price should be between $0.025 and $0.0325
-        assert!(avg_price > Uint128::from(25000u128)); // > $0.025
-        assert!(avg_price < Uint128::from(32500u128)); // < $0.0325
+        assert_eq!(ContractError::TotalSupplyNotConfigured {}.code(), 20);
+        assert_eq!(ContractError::DailyUsdLimitExceeded { available: 0, requested: 1 }.code(), 21);
+        assert_eq!(ContractError::ReceivedAmountMismatch { reported: Uint128::one(), held: Uint128::zero() }.code(), 22);
+        assert_eq!(ContractError::InvalidAutoResume { at: 0, now: 1 }.code(), 23);
+        assert_eq!(ContractError::MissingTokenDecimals { token: String::new() }.code(), 24);
+        assert_eq!(ContractError::DailyStatsResetDisabled {}.code(), 25);
+        assert_eq!(ContractError::TooManyPurchasesToday { max: 1 }.code(), 26);
+        assert_eq!(ContractError::MathOverflow { op: String::new() }.code(), 27);
+        assert_eq!(ContractError::InvalidAdminRole { bits: 0 }.code(), 28);
+        assert_eq!(ContractError::InvalidEventPrefix { prefix: String::new(), max: 0 }.code(), 29);
+        assert_eq!(ContractError::InvalidPaymentTokenBatch { count: 0, max: 0 }.code(), 30);
+        assert_eq!(
+            ContractError::TierMultiplierOverflow { base_price_usd: Uint128::one(), tier_multiplier: Uint128::MAX }.code(),
+            15
+        );
+    }
+
+    /// Independent tier walk in exact integer math: USD is kept scaled by 1e9 so whole tiers
+    /// are bought without rounding and only the final partial tier is floored.
+    fn reference_tokens_for_usd(usd_amount: u128, tokens_sold: u128, pricing: &PricingConfig) -> u128 {
+        let per_tier = pricing.tokens_per_tier.u128();
+        let mut remaining_scaled = usd_amount * 1_000_000_000;
+        let mut sold = tokens_sold;
+        let mut tokens = 0u128;
+        loop {
+            let tier = sold / per_tier;
+            let price = (0..tier).fold(pricing.base_price_usd.u128(), |p, _| {
+                p * pricing.tier_multiplier.u128() / 1000
+            });
+            let left_in_tier = per_tier - sold % per_tier;
+            let tier_cost_scaled = left_in_tier * price;
+            if remaining_scaled < tier_cost_scaled {
+                return tokens + remaining_scaled / price;
+            }
+            remaining_scaled -= tier_cost_scaled;
+            tokens += left_in_tier;
+            sold += left_in_tier;
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_multi_tier_purchase_invariants(
+            usd_amount in 1u128..=1_000_000_000_000_000,
+            base_price in 1u128..=10_000_000,
+            tier_multiplier in 1000u128..=1500,
+            tokens_per_tier in 1_000_000_000u128..=1_000_000_000_000_000_000,
+            tiers_sold in 0u128..20,
+            sold_in_tier_permille in 0u128..1000,
+        ) {
+            use crate::state::calculate_multi_tier_purchase;
+
+            let pricing = PricingConfig {
+                base_price_usd: Uint128::new(base_price),
+                tokens_per_tier: Uint128::new(tokens_per_tier),
+                tier_multiplier: Uint128::new(tier_multiplier),
+                max_tier: None,
+            };
+            let tokens_sold = tiers_sold * tokens_per_tier + tokens_per_tier / 1000 * sold_in_tier_permille;
+
+            let (tokens, usd_spent, start_tier, end_tier, avg_price) =
+                calculate_multi_tier_purchase(Uint128::new(usd_amount), Uint128::new(tokens_sold), &pricing, 9).unwrap();
+
+            proptest::prop_assert!(usd_spent.u128() <= usd_amount);
+            proptest::prop_assert!(start_tier <= end_tier);
+            if !tokens.is_zero() {
+                proptest::prop_assert!(avg_price.u128() >= base_price);
+            }
+            // The whole amount is spent unless the 50-iteration cap stopped the walk
+            proptest::prop_assert!(usd_spent.u128() == usd_amount || end_tier - start_tier >= 50);
+
+            // Completing a tier rounds its cost up by under one micro-USD, and the last tier floors,
+            // so each tier touched may differ from the exact walk by 1e9 / price + 1 token units
+            let expected = reference_tokens_for_usd(usd_spent.u128(), tokens_sold, &pricing);
+            let tolerance = (u128::from(end_tier - start_tier) + 1) * (1_000_000_000 / base_price + 1);
+            proptest::prop_assert!(tokens.u128() <= expected);
+            proptest::prop_assert!(expected - tokens.u128() <= tolerance,
+                "tokens {} reference {} tolerance {}", tokens, expected, tolerance);
+        }
     }
-} 
\ No newline at end of file
+}
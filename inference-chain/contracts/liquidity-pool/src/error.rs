@@ -1,4 +1,6 @@
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::StdError;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,6 +17,34 @@ pub enum ContractError {
     #[error("Daily limit exceeded. Available: {available}, Requested: {requested}")]
     DailyLimitExceeded { available: u128, requested: u128 },
 
+    #[error("Daily USD limit exceeded. Available: {available}, Requested: {requested}")]
+    DailyUsdLimitExceeded { available: u128, requested: u128 },
+
+    #[error("Received amount mismatch: CW20 reported {reported}, pool holds {held}")]
+    ReceivedAmountMismatch { reported: cosmwasm_std::Uint128, held: cosmwasm_std::Uint128 },
+
+    #[error("Auto-resume time {at} must be after the current block time {now}")]
+    InvalidAutoResume { at: u64, now: u64 },
+
+    /// No longer returned now that payment token decimals are optional; kept so codes stay stable
+    #[error("Decimals for {token} must be given: it does not answer a CW20 TokenInfo query")]
+    MissingTokenDecimals { token: String },
+
+    #[error("Daily stats reset is disabled for this pool")]
+    DailyStatsResetDisabled {},
+
+    #[error("Buyer already made the {max} purchases allowed today")]
+    TooManyPurchasesToday { max: u32 },
+
+    #[error("Math overflow in {op}")]
+    MathOverflow { op: String },
+
+    #[error("Invalid admin role bits: {bits:#010b}")]
+    InvalidAdminRole { bits: u8 },
+
+    #[error("Invalid event prefix '{prefix}': use 1-{max} lowercase letters or digits")]
+    InvalidEventPrefix { prefix: String, max: usize },
+
     #[error("Invalid token: {token}")]
     InvalidToken { token: String },
 
@@ -35,4 +65,135 @@ pub enum ContractError {
 
     #[error("No tokens to purchase")]
     NoTokensToPurchase {},
-} 
\ No newline at end of file
+
+    #[error("Invalid total supply: {total_supply}. Must be at least total tokens sold ({total_tokens_sold})")]
+    InvalidTotalSupply {
+        total_supply: cosmwasm_std::Uint128,
+        total_tokens_sold: cosmwasm_std::Uint128,
+    },
+
+    #[error("Invalid native decimals: {decimals}. Must be at most {max}")]
+    InvalidNativeDecimals { decimals: u8, max: u8 },
+
+    #[error("Buyer not allowed: {buyer}")]
+    BuyerNotAllowed { buyer: String },
+
+    #[error("{0}")]
+    Grpc(GrpcQueryError),
+
+    #[error("Too many payouts: {count}. At most {max} per batch")]
+    TooManyPayouts { count: usize, max: usize },
+
+    #[error("Payment token batch must contain between 1 and {max} tokens, got {count}")]
+    InvalidPaymentTokenBatch { count: usize, max: usize },
+
+    #[error("Invalid rollover grace: {seconds} seconds. Must be at most {max}")]
+    InvalidRolloverGrace { seconds: u64, max: u64 },
+
+    #[error("Total supply is not configured; the admin must set it with UpdateTotalSupply before purchases")]
+    TotalSupplyNotConfigured {},
+
+    #[error("Circulating supply must be set while the daily limit uses it")]
+    MissingCirculatingSupply {},
+
+    #[error("Purchase above maximum: at most {max} micro-USD per purchase, got {got}")]
+    AboveMaximumPurchase { max: cosmwasm_std::Uint128, got: cosmwasm_std::Uint128 },
+
+    #[error("Forwarding {amount} of CW20 {cw20_contract} to admin failed: {reason}")]
+    Cw20ForwardFailed {
+        cw20_contract: String,
+        amount: cosmwasm_std::Uint128,
+        reason: String,
+    },
+
+    #[error("Tier multiplier {tier_multiplier} overflows the price for base price {base_price_usd}")]
+    TierMultiplierOverflow {
+        base_price_usd: cosmwasm_std::Uint128,
+        tier_multiplier: cosmwasm_std::Uint128,
+    },
+}
+
+/// Which side of a chain gRPC query failed
+#[cw_serde]
+#[derive(Copy)]
+pub enum GrpcErrorKind {
+    /// The querier or node couldn't serve the request (unsupported path, node unavailable)
+    System,
+    /// The queried module answered with an error for this request
+    Contract,
+}
+
+impl fmt::Display for GrpcErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrpcErrorKind::System => write!(f, "system"),
+            GrpcErrorKind::Contract => write!(f, "contract"),
+        }
+    }
+}
+
+/// A failed chain gRPC query. The message starts with a stable `[grpc:<kind>:retryable=<bool>]`
+/// prefix so clients that only see error text can still match on it.
+#[cw_serde]
+#[derive(Error)]
+#[error("[grpc:{kind}:retryable={retryable}] {message}")]
+pub struct GrpcQueryError {
+    pub kind: GrpcErrorKind,
+    /// Heuristic: system errors may clear up on retry, a module rejecting the request won't
+    pub retryable: bool,
+    pub message: String,
+}
+
+impl GrpcQueryError {
+    pub fn new(kind: GrpcErrorKind, message: impl Into<String>) -> Self {
+        GrpcQueryError {
+            kind,
+            retryable: kind == GrpcErrorKind::System,
+            message: message.into(),
+        }
+    }
+} 
+
+impl ContractError {
+    /// Stable numeric code for clients that need to branch on the failure without parsing the
+    /// message text. Codes are shared with the community-sale contract for equivalent errors and
+    /// are never reassigned; `0` covers wrapped `StdError`s. Failed executions discard their
+    /// events, so the code cannot be attached as an attribute; this match is the canonical mapping.
+    pub fn code(&self) -> u32 {
+        match self {
+            ContractError::Std(_) => 0,
+            ContractError::Unauthorized {} => 1,
+            ContractError::ContractPaused {} => 2,
+            ContractError::DailyLimitExceeded { .. } => 3,
+            ContractError::InvalidToken { .. } => 4,
+            ContractError::InvalidExchangeRate { .. } => 5,
+            ContractError::ZeroAmount {} => 6,
+            ContractError::InsufficientBalance { .. } => 7,
+            ContractError::InvalidBasisPoints { .. } => 8,
+            ContractError::TokenNotAccepted { .. } => 9,
+            ContractError::NoTokensToPurchase {} => 10,
+            ContractError::InvalidTotalSupply { .. } => 11,
+            ContractError::InvalidNativeDecimals { .. } => 12,
+            ContractError::Grpc(_) => 13,
+            ContractError::TooManyPayouts { .. } => 14,
+            ContractError::TierMultiplierOverflow { .. } => 15,
+            ContractError::InvalidRolloverGrace { .. } => 16,
+            ContractError::MissingCirculatingSupply {} => 17,
+            ContractError::AboveMaximumPurchase { .. } => 18,
+            ContractError::Cw20ForwardFailed { .. } => 19,
+            ContractError::TotalSupplyNotConfigured {} => 20,
+            ContractError::DailyUsdLimitExceeded { .. } => 21,
+            ContractError::ReceivedAmountMismatch { .. } => 22,
+            ContractError::InvalidAutoResume { .. } => 23,
+            ContractError::MissingTokenDecimals { .. } => 24,
+            ContractError::DailyStatsResetDisabled {} => 25,
+            ContractError::TooManyPurchasesToday { .. } => 26,
+            ContractError::MathOverflow { .. } => 27,
+            ContractError::InvalidAdminRole { .. } => 28,
+            ContractError::InvalidEventPrefix { .. } => 29,
+            ContractError::InvalidPaymentTokenBatch { .. } => 30,
+            // Same code as the community sale's BuyerNotAllowed
+            ContractError::BuyerNotAllowed { .. } => 101,
+        }
+    }
+}
@@ -2,5 +2,6 @@ pub mod contract;
 pub mod error;
 pub mod msg;
 pub mod state;
+pub mod util;
 
 pub use crate::error::ContractError; 
\ No newline at end of file
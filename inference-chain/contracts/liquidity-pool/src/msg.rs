@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Coin, Uint128};
+use cosmwasm_std::{Binary, Coin, Uint128, Uint256};
 use std::collections::HashMap;
 
+use crate::error::GrpcQueryError;
+use crate::state::{AdminRole, LimitBasis, PauseFlags};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Optional admin address that can pause/unpause and update config. If None, contract is governance-only.
@@ -16,35 +19,135 @@ pub struct InstantiateMsg {
     pub tier_multiplier: Option<Uint128>,
     /// Initial total supply of native tokens (defaults to 0 if not provided)
     pub total_supply: Option<Uint128>,
+    /// Native denom used when it can't be read from the chain (defaults to "ngonka")
+    pub fallback_denom: Option<String>,
+    /// Decimal places of the native token, at most 18 (defaults to 9)
+    pub native_decimals: Option<u8>,
+    /// Optional last tier whose price escalates; the price stays flat beyond it (default: no cap)
+    pub max_tier: Option<u32>,
+    /// Only allowlisted buyers may purchase (default: false)
+    pub require_allowlist: Option<bool>,
+    /// Seconds after UTC midnight that still count against the previous day's limit, at most 3600 (default: 0)
+    pub rollover_grace_seconds: Option<u64>,
+    /// Let the admin zero today's stats with ResetDailyStats, for testnet operations; can't be
+    /// turned on after instantiate (default: false)
+    #[serde(default)]
+    pub allow_daily_stats_reset: Option<bool>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Receive CW20 wrapped bridge tokens to purchase native tokens
     Receive(Cw20ReceiveMsg),
-    /// Admin: Pause the contract
-    Pause {},
-    /// Admin: Resume the contract
-    Resume {},
+    /// Purchase native tokens with a single attached coin of an accepted native payment denom
+    PurchaseWithNative {},
+    /// Admin: Pause the operations set in `flags`; pauses purchases only when omitted.
+    /// With `auto_resume_after` (unix seconds), a purchases pause lapses once the block time passes it.
+    Pause {
+        flags: Option<PauseFlags>,
+        #[serde(default)]
+        auto_resume_after: Option<u64>,
+    },
+    /// Admin: Resume the operations set in `flags`; resumes purchases only when omitted
+    Resume { flags: Option<PauseFlags> },
     /// Admin: Update daily limit in basis points
     UpdateDailyLimit { daily_limit_bp: Option<Uint128> },
+    /// Admin: Set the daily-stats rollover grace period in seconds (0 disables it)
+    UpdateRolloverGrace { rollover_grace_seconds: u64 },
+    /// Admin: Update the native allocation the daily limit is computed from. Operators are
+    /// responsible for calling this after topping up (or withdrawing from) the contract.
+    UpdateTotalSupply { total_supply: Uint128 },
+    /// Admin: Set or clear the circulating supply used by `LimitBasis::CirculatingOverride`
+    UpdateCirculatingSupply { circulating_supply: Option<Uint128> },
+    /// Admin: Choose what the daily limit is computed against
+    UpdateLimitBasis { limit_basis: LimitBasis },
+    /// Admin: Cap the USD value of any single purchase (per transaction, not per buyer); None removes the cap
+    UpdateMaxPurchase { max_purchase_usd: Option<Uint128> },
+    /// Admin: Cap how many purchases one buyer may make per day, whatever their size; None removes the cap
+    UpdateMaxPurchasesPerDay { max_purchases_per_buyer_per_day: Option<u32> },
+    /// Admin: Pause purchases automatically once the price rises above this micro-USD ceiling; None removes it
+    UpdateAutoPausePrice { auto_pause_price_usd: Option<Uint128> },
+    /// Admin: Cap the USD (micro-USD) accepted per day regardless of price; None removes the cap
+    UpdateDailyUsdLimit { daily_usd_limit: Option<Uint128> },
+    /// Admin: Archive today's stats and start the day over with zero sold. Only on pools
+    /// instantiated with `allow_daily_stats_reset`.
+    ResetDailyStats {},
+    /// Admin: Check received CW20 against the pool's balance before crediting (for transfer-fee tokens)
+    SetVerifyReceived { verify_received: bool },
+    /// Admin: Set the protocol fee (basis points of purchased tokens, below 10000) and its
+    /// recipient. Without a recipient no fee is taken.
+    UpdateFee {
+        fee_bp: Uint128,
+        fee_recipient: Option<String>,
+    },
+    /// Admin: Forward CW20 and native proceeds to this address instead of the admin; None forwards to the admin again
+    UpdateProceedsRecipient { cw20_proceeds_recipient: Option<String> },
+    /// Admin: Notify `purchase_hook` of every purchase; None stops notifying. With `hook_required`
+    /// a failing hook reverts the purchase, otherwise the purchase completes without it.
+    UpdatePurchaseHook { purchase_hook: Option<String>, hook_required: bool },
+    /// Admin: Prefix emitted attribute keys with `{event_prefix}_` (e.g. `lp_method`); None emits bare keys
+    UpdateEventPrefix { event_prefix: Option<String> },
     /// Admin: Withdraw native tokens from contract
     WithdrawNativeTokens { amount: Uint128, recipient: String },
+    /// Admin: Withdraw native tokens sent to the pool outside a purchase, i.e. only the balance
+    /// above what is still for sale (total_supply - total_tokens_sold)
+    RecoverMisdirectedNative { recipient: String, amount: Uint128 },
+    /// Admin: Burn native tokens left unsold, e.g. at the end of a sale, instead of withdrawing
+    /// them. Limited to the sellable balance.
+    BurnUnsold { amount: Uint128 },
+    /// Admin: Withdraw native tokens to several (recipient, amount) pairs in one go, at most 20
+    WithdrawNativeBatch { payouts: Vec<(String, Uint128)> },
     /// Admin: Emergency withdraw all funds
     EmergencyWithdraw { recipient: String },
-    /// Admin: Update pricing configuration
+    /// Admin: Forward the contract's whole balance of a CW20 where purchase proceeds go (the
+    /// proceeds recipient, else the admin), e.g. tokens kept from purchases made while no admin was set
+    SweepCw20 { cw20_contract: String },
+    /// Admin: Recovery path that transfers `amount` of a CW20 held by the pool to `recipient`
+    /// without bridge validation, e.g. for tokens whose bridge approval was later revoked
+    ForceReturnCw20 {
+        cw20_contract: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Admin: Update pricing configuration; fields left as None are unchanged
     UpdatePricingConfig {
         base_price_usd: Option<Uint128>,
         tokens_per_tier: Option<Uint128>,
         tier_multiplier: Option<Uint128>,
+        max_tier: Option<u32>,
     },
-    /// Admin: Add or update a payment token and its USD rate
+    /// Admin: Add or update a payment token (CW20 address or native denom) and its USD rate.
+    /// A CW20's decimals are read from its TokenInfo; `decimals` is only used when that query
+    /// fails, as it always does for native denoms, and may be omitted, in which case none are
+    /// recorded. Decimals are only recorded for display; `usd_rate` must already be per base unit.
     AddPaymentToken { 
         denom: String, 
-        usd_rate: Uint128 // micro-USD per token unit
+        usd_rate: Uint128, // micro-USD per token unit
+        #[serde(default)]
+        decimals: Option<u8>,
     },
+    /// Admin: Add or update several payment tokens at once, each validated as in AddPaymentToken
+    /// (so `decimals` stay optional). One invalid token rejects the whole batch.
+    AddPaymentTokens { tokens: Vec<PaymentTokenEntry> },
     /// Admin: Remove a payment token
     RemovePaymentToken { denom: String },
+    /// Admin: Turn the buyer allowlist requirement on or off
+    SetRequireAllowlist { require_allowlist: bool },
+    /// Super-admin: Give `address` the capabilities in `role`, on top of any it already holds
+    GrantRole { address: String, role: AdminRole },
+    /// Super-admin: Take the capabilities in `role` away from `address`
+    RevokeRole { address: String, role: AdminRole },
+    /// Admin: Approve a buyer for purchases while the allowlist is required
+    AddToAllowlist { address: String },
+    /// Admin: Revoke a buyer's approval
+    RemoveFromAllowlist { address: String },
+    /// Admin: Trust a CW20 as a bridge token, skipping the chain's validation query for it.
+    /// Trusted CW20s stay accepted even if the chain later revokes them, until removed here.
+    AddTrustedCw20 { address: String },
+    /// Admin: Stop trusting a CW20; it is validated through the chain again
+    RemoveTrustedCw20 { address: String },
+    /// Admin: Accept only trusted CW20s (true) or fall back to chain validation for the rest (false)
+    SetTrustCacheOnly { trust_cache_only: bool },
 }
 
 #[cw_serde]
@@ -54,9 +157,32 @@ pub struct Cw20ReceiveMsg {
     pub msg: Binary,
 }
 
+/// Execute message sent to the configured purchase hook contract
+#[cw_serde]
+pub enum PurchaseHookMsg {
+    OnPurchase {
+        buyer: String,
+        /// Native tokens bought, including any protocol fee
+        tokens: Uint128,
+        /// USD spent (6 decimals)
+        usd: Uint128,
+        /// Tier the purchase ended in
+        tier: u32,
+    },
+}
+
+/// Receive-hook payload for a purchase. Forward-compatibility contract for integrators: every
+/// field is optional with a serde default, so the empty `{}` (and any older client's payload)
+/// keeps deserializing as fields are added. Unknown fields are rejected, so a client must not send
+/// a field before the deployed contract knows it.
 #[cw_serde]
+#[serde(deny_unknown_fields)]
+#[schemaifier(mute_warnings)]
 pub struct PurchaseTokenMsg {
-    // Empty for now, could add recipient address later
+    /// Fill up to today's remaining daily capacity and refund the unspent CW20 instead of
+    /// rejecting a purchase that exceeds it
+    #[serde(default)]
+    pub allow_daily_partial: bool,
 }
 
 #[cw_serde]
@@ -65,27 +191,126 @@ pub enum QueryMsg {
     /// Get contract configuration
     #[returns(ConfigResponse)]
     Config {},
+    /// Whether purchases are paused; a cheap alternative to Config for health checks
+    #[returns(bool)]
+    Paused {},
     /// Get current daily statistics
     #[returns(DailyStatsResponse)]
     DailyStats {},
+    /// Get archived statistics for a specific day index ((block time - rollover grace) / 86400)
+    #[returns(DailyStatsForDayResponse)]
+    DailyStatsForDay { day: u64 },
+    /// Get cumulative CW20 kept by the contract from a buyer while no admin was set
+    #[returns(ReceivedFromResponse)]
+    ReceivedFrom { buyer: String },
+    /// Id of the latest purchase (0 before the first), for indexers checking for gaps
+    #[returns(LastPurchaseIdResponse)]
+    LastPurchaseId {},
+    /// Capabilities an address holds; the config admin holds all of them
+    #[returns(AdminRoleResponse)]
+    AdminRole { address: String },
+    /// Whether an address is on the buyer allowlist (regardless of whether it is required)
+    #[returns(IsAllowlistedResponse)]
+    IsAllowlisted { address: String },
+    /// Lifetime purchase totals for up to 30 buyers at once; buyers without purchases report zeros
+    #[returns(BuyersPurchasedResponse)]
+    BuyersPurchased { addresses: Vec<String> },
+    /// Purchases a buyer has made today, next to the per-buyer daily cap
+    #[returns(PurchasesTodayResponse)]
+    PurchasesToday { buyer: String },
+    /// CW20s trusted without chain validation
+    #[returns(TrustedCw20sResponse)]
+    TrustedCw20s {},
     /// Get contract's native token balance
     #[returns(NativeBalanceResponse)]
     NativeBalance {},
+    /// Native balance purchases can still draw on, as checked when a purchase is made
+    #[returns(SellableBalanceResponse)]
+    SellableBalance {},
+    /// USD value of the sellable balance at the current tier price. A lower bound: selling it
+    /// all would move through later, higher-priced tiers.
+    #[returns(InventoryValueUsdResponse)]
+    InventoryValueUsd {},
+    /// Overall sale progress for dashboards: supply, sold, current price and pool balance in one call
+    #[returns(SaleProgressResponse)]
+    SaleProgress {},
     /// Get current pricing information
     #[returns(PricingInfoResponse)]
     PricingInfo {},
+    /// Time-weighted average tier price over the last `lookback_seconds`, from purchase-time
+    /// observations; the window shrinks to the first purchase if that is more recent. At most
+    /// 30 days (`MAX_TWAP_LOOKBACK_SECONDS`).
+    #[returns(TwapResponse)]
+    Twap { lookback_seconds: u64 },
+    /// Same as PricingInfo with amounts formatted to their decimal places, for display
+    #[returns(PricingInfoHumanResponse)]
+    PricingInfoHuman {},
+    /// USD that fills the remainder of the current tier at the current price
+    #[returns(UsdToNextTierResponse)]
+    UsdToNextTier {},
+    /// Tokens sold per pricing tier, starting at `start_tier` (inclusive)
+    #[returns(TierSalesResponse)]
+    TierSales {
+        start_tier: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// Where each of the first `count` tiers (at most 30) starts, in tokens sold, and its price
+    #[returns(TierBoundariesResponse)]
+    TierBoundaries { count: u32 },
     /// Calculate how many tokens can be bought with given USD amount
     #[returns(TokenCalculationResponse)]
     CalculateTokens { usd_amount: Uint128 },
+    /// Like CalculateTokens across tiers, but capped at what a purchase could deliver now given the
+    /// sellable balance and what is left of today's limit. Beyond the cap a purchase is rejected
+    /// unless it opts into a partial daily fill.
+    #[returns(ClampedTokenCalculationResponse)]
+    CalculateTokensClamped { usd_amount: Uint128 },
+    /// USD needed to buy `token_amount` native tokens from the current point of the curve, across
+    /// every tier they span. The final partial tier is rounded down, so spending `usd_cost` buys at
+    /// most `token_amount`. Errors past 50 tiers, the most a single purchase walks.
+    #[returns(CostForTokensResponse)]
+    CostForTokens { token_amount: Uint128 },
+    /// List accepted payment tokens and their USD rates
+    #[returns(PaymentTokensResponse)]
+    PaymentTokens {},
+    /// Quote a purchase paid with `amount` units of a wrapped-token CW20, as receive_cw20 would price it
+    #[returns(QuoteForCw20Response)]
+    QuoteForCw20 { cw20_contract: String, amount: Uint128 },
+    /// Pre-flight for a wrapped-token purchase: runs receive_cw20's checks without changing state
+    #[returns(CanPurchaseResponse)]
+    CanPurchase {
+        buyer: String,
+        cw20_contract: String,
+        amount: Uint128,
+    },
+    /// Cumulative CW20 forwarded to the admin, per cw20 contract
+    #[returns(ForwardedTotalsResponse)]
+    ForwardedTotals {},
+    /// Soft rejections (purchases trimmed but still committed), counted per reason
+    #[returns(RejectionStatsResponse)]
+    RejectionStats {},
     /// Test bridge validation with a provided CW20 contract address
     #[returns(TestBridgeValidationResponse)]
     TestBridgeValidation { cw20_contract: String },
+    /// Debug builds only (`debug_grpc` feature): send `data_base64` (a base64 protobuf request) to
+    /// the chain's gRPC `path` and return the raw response bytes. It reaches any query the chain
+    /// exposes to contracts, unauthenticated and with caller-chosen paths and payloads, so it must
+    /// not be compiled into deployed contracts.
+    #[cfg(feature = "debug_grpc")]
+    #[returns(Binary)]
+    RawGrpc { path: String, data_base64: String },
     /// Return the current block height
     #[returns(BlockHeightResponse)]
     BlockHeight {},
+    /// Get the contract name and version recorded by cw2
+    #[returns(cw2::ContractVersion)]
+    ContractVersion {},
     /// Test gRPC call to fetch approved tokens for trade; returns raw protobuf bytes
     #[returns(ApprovedTokensForTradeJson)]
     TestApprovedTokens {},
+    /// The chain's approved tokens, each checked with the bridge-validation gRPC (at most 30)
+    #[returns(ApprovedTokensWithStatusResponse)]
+    ApprovedTokensWithStatus {},
 }
 
 #[cw_serde]
@@ -93,8 +318,30 @@ pub struct ConfigResponse {
     pub admin: String,
     pub native_denom: String,
     pub daily_limit_bp: Uint128,
+    /// Whether purchases are paused (same as `pause_flags.purchases`), after any auto-resume
     pub is_paused: bool,
+    pub pause_flags: PauseFlags,
+    /// When a purchases pause lapses on its own (unix seconds)
+    pub auto_resume_after: Option<u64>,
     pub total_tokens_sold: Uint128,
+    pub fee_bp: Uint128,
+    pub fee_recipient: Option<String>,
+    pub native_decimals: u8,
+    pub require_allowlist: bool,
+    pub rollover_grace_seconds: u64,
+    pub limit_basis: LimitBasis,
+    pub circulating_supply: Option<Uint128>,
+    pub max_purchase_usd: Option<Uint128>,
+    pub trust_cache_only: bool,
+    pub auto_pause_price_usd: Option<Uint128>,
+    pub daily_usd_limit: Option<Uint128>,
+    pub verify_received: bool,
+    pub allow_daily_stats_reset: bool,
+    pub max_purchases_per_buyer_per_day: Option<u32>,
+    pub cw20_proceeds_recipient: Option<String>,
+    pub purchase_hook: Option<String>,
+    pub hook_required: bool,
+    pub event_prefix: Option<String>,
 }
 
 #[cw_serde]
@@ -105,6 +352,76 @@ pub struct DailyStatsResponse {
     pub tokens_available_today: Uint128,
     pub daily_token_limit: Uint128,
     pub total_supply: Uint128,
+    /// Share of today's limit already sold, in basis points (0 when the limit is zero)
+    pub daily_limit_used_bp: Uint128,
+    /// False while total_supply is zero and purchases are rejected
+    pub configured: bool,
+    /// USD that can still be received today under daily_usd_limit; None when there is no USD cap
+    pub usd_available_today: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct DailyStatsForDayResponse {
+    pub day: u64,
+    pub usd_received: Uint128,
+    pub tokens_sold: Uint128,
+    /// Daily limit computed from the current config, not the config in effect on that day
+    pub daily_token_limit: Uint128,
+}
+
+#[cw_serde]
+pub struct Cw20Amount {
+    pub cw20_contract: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct ReceivedFromResponse {
+    pub buyer: String,
+    pub received: Vec<Cw20Amount>,
+}
+
+#[cw_serde]
+pub struct TrustedCw20sResponse {
+    pub addresses: Vec<String>,
+    pub trust_cache_only: bool,
+}
+
+#[cw_serde]
+pub struct IsAllowlistedResponse {
+    pub allowlisted: bool,
+}
+
+#[cw_serde]
+pub struct AdminRoleResponse {
+    /// Whether the address is the config admin, who may grant and revoke roles
+    pub super_admin: bool,
+    pub role: AdminRole,
+}
+
+#[cw_serde]
+pub struct BuyerPurchased {
+    pub address: String,
+    pub total_tokens: Uint128,
+    pub total_usd: Uint128,
+}
+
+#[cw_serde]
+pub struct BuyersPurchasedResponse {
+    /// In the order the addresses were given
+    pub buyers: Vec<BuyerPurchased>,
+}
+
+#[cw_serde]
+pub struct PurchasesTodayResponse {
+    pub purchases: u32,
+    /// None when buyers have no daily purchase cap
+    pub max_purchases_per_buyer_per_day: Option<u32>,
+}
+
+#[cw_serde]
+pub struct LastPurchaseIdResponse {
+    pub purchase_id: u64,
 }
 
 #[cw_serde]
@@ -117,6 +434,21 @@ pub struct NativeBalanceResponse {
     pub balance: Coin,
 }
 
+#[cw_serde]
+pub struct SellableBalanceResponse {
+    pub sellable: Uint256,
+}
+
+#[cw_serde]
+pub struct InventoryValueUsdResponse {
+    /// Sellable native balance, as returned by SellableBalance
+    pub sellable: Uint256,
+    /// Current tier price per token in USD (6 decimals)
+    pub current_price_usd: Uint128,
+    /// sellable * current_price_usd, in USD (6 decimals)
+    pub value_usd: Uint256,
+}
+
 #[cw_serde]
 pub struct PricingInfoResponse {
     pub current_tier: u32,
@@ -125,8 +457,86 @@ pub struct PricingInfoResponse {
     pub tokens_per_tier: Uint128,
     pub base_price_usd: Uint128,
     pub tier_multiplier: Uint128,
+    pub max_tier: Option<u32>,
     pub next_tier_at: Uint128,
     pub next_tier_price: Uint128,
+    /// Total USD received over the life of the sale
+    pub total_usd_received: Uint128,
+    /// Average price paid per token across the entire sale so far
+    pub lifetime_average_price: Uint128,
+    /// False while total_supply is zero and purchases are rejected
+    pub configured: bool,
+}
+
+#[cw_serde]
+pub struct TwapResponse {
+    /// Average price per token in USD (6 decimals)
+    pub twap_price_usd: Uint128,
+    /// Seconds actually averaged over; below lookback_seconds when purchases began more recently
+    pub window_seconds: u64,
+}
+
+/// Display strings for PricingInfoResponse: USD values have 6 decimals, token amounts 9,
+/// and the tier multiplier 3
+#[cw_serde]
+pub struct PricingInfoHumanResponse {
+    pub current_tier: u32,
+    pub current_price_usd_display: String,
+    pub total_tokens_sold_display: String,
+    pub tokens_per_tier_display: String,
+    pub base_price_usd_display: String,
+    pub tier_multiplier_display: String,
+    pub next_tier_at_display: String,
+    pub next_tier_price_display: String,
+    pub total_usd_received_display: String,
+    pub lifetime_average_price_display: String,
+}
+
+#[cw_serde]
+pub struct UsdToNextTierResponse {
+    pub current_tier: u32,
+    pub current_price_usd: Uint128,
+    /// Largest USD amount (6 decimals) that stays within the current tier's price
+    pub usd_to_next_tier: Uint128,
+    /// Tokens left in the current tier (9 decimals)
+    pub tokens_remaining_in_tier: Uint128,
+}
+
+#[cw_serde]
+pub struct TierSale {
+    pub tier: u32,
+    pub tokens_sold: Uint128,
+}
+
+#[cw_serde]
+pub struct SaleProgressResponse {
+    pub total_supply: Uint128,
+    pub total_tokens_sold: Uint128,
+    /// total_tokens_sold as basis points of total_supply (0 while total_supply is unset)
+    pub percent_sold_bp: Uint128,
+    pub current_tier: u32,
+    pub current_price_usd: Uint128,
+    pub native_balance: Uint256,
+}
+
+#[cw_serde]
+pub struct TierSalesResponse {
+    pub tiers: Vec<TierSale>,
+    /// Pass as `start_tier` to fetch the next page; None on the last page
+    pub next_start_tier: Option<u32>,
+}
+
+#[cw_serde]
+pub struct TierBoundary {
+    pub tier: u32,
+    /// Total tokens sold when this tier begins (tier * tokens_per_tier; tier 0 starts at 0)
+    pub starts_at_tokens: Uint128,
+    pub price_usd: Uint128,
+}
+
+#[cw_serde]
+pub struct TierBoundariesResponse {
+    pub tiers: Vec<TierBoundary>,
 }
 
 #[cw_serde]
@@ -136,14 +546,84 @@ pub struct TokenCalculationResponse {
     pub current_tier: u32,
 }
 
+/// What capped a clamped token calculation
+#[cw_serde]
+pub enum TokenLimit {
+    Balance,
+    DailyLimit,
+    None,
+}
+
+#[cw_serde]
+pub struct ClampedTokenCalculationResponse {
+    /// Tokens a purchase of `usd_amount` could deliver right now
+    pub tokens: Uint128,
+    /// Tokens the pricing curve alone gives for `usd_amount`
+    pub unclamped_tokens: Uint128,
+    pub clamped: bool,
+    pub limited_by: TokenLimit,
+}
+
+#[cw_serde]
+pub struct CostForTokensResponse {
+    pub usd_cost: Uint128,
+    pub start_tier: u32,
+    pub end_tier: u32,
+    /// Average price per token in USD (6 decimals)
+    pub average_price: Uint128,
+}
+
+/// One token of an AddPaymentTokens batch, with the same fields as AddPaymentToken
+#[cw_serde]
+pub struct PaymentTokenEntry {
+    pub denom: String,
+    /// micro-USD per token unit
+    pub usd_rate: Uint128,
+    #[serde(default)]
+    pub decimals: Option<u8>,
+}
+
 #[cw_serde]
 pub struct PaymentTokensResponse {
     pub tokens: HashMap<String, Uint128>, // denom -> USD rate
+    /// denom -> decimals, informational (rates are per base unit); tokens whose decimals were
+    /// neither queried nor given, or were added before decimals were recorded, are missing
+    pub decimals: HashMap<String, u8>,
 } 
 
+#[cw_serde]
+pub struct QuoteForCw20Response {
+    /// Native tokens the purchase would buy, before any protocol fee
+    pub tokens: Uint128,
+    pub usd_value: Uint128,
+    /// Whether the purchase fits within what remains of today's sale limit
+    pub fits_daily_limit: bool,
+}
+
+#[cw_serde]
+pub struct CanPurchaseResponse {
+    pub ok: bool,
+    /// Error the purchase would fail with, when `ok` is false
+    pub reason: Option<String>,
+    /// Native tokens the buyer would receive, after any protocol fee (zero when `ok` is false)
+    pub tokens_out: Uint128,
+}
+
+#[cw_serde]
+pub struct ForwardedTotalsResponse {
+    pub totals: HashMap<String, Uint128>, // cw20 contract -> amount forwarded
+}
+
+#[cw_serde]
+pub struct RejectionStatsResponse {
+    pub reasons: HashMap<String, u64>, // reason -> times seen
+}
+
 #[cw_serde]
 pub struct TestBridgeValidationResponse {
     pub is_valid: bool,
+    /// Set when is_valid is false because the chain query failed, rather than the token being rejected
+    pub error: Option<GrpcQueryError>,
 }
 
 #[cw_serde]
@@ -161,4 +641,20 @@ pub struct ApprovedTokensForTradeJson {
 pub struct ApprovedTokenJson {
     pub chain_id: String,
     pub contract_address: String,
+}
+
+#[cw_serde]
+pub struct ApprovedTokenStatus {
+    pub chain_id: String,
+    pub contract_address: String,
+    pub is_valid: bool,
+    /// Why the token couldn't be validated: not a CW20 address on this chain, or a failed query
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct ApprovedTokensWithStatusResponse {
+    pub tokens: Vec<ApprovedTokenStatus>,
+    /// More tokens are approved than were validated
+    pub truncated: bool,
 }
\ No newline at end of file
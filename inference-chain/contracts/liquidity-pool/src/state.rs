@@ -1,6 +1,9 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Uint128;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Uint128, Uint256};
+use cw_storage_plus::{Item, Map};
+
+use crate::error::ContractError;
+use crate::util::{on_overflow, TOKEN_DECIMALS};
 
 #[cw_serde]
 pub struct Config {
@@ -10,17 +13,259 @@ pub struct Config {
     pub native_denom: String,
     /// Daily selling limit in basis points (1-10000)
     pub daily_limit_bp: Uint128,
-    /// Whether contract is paused
-    pub is_paused: bool,
+    /// Which operations are currently paused
+    pub pause_flags: PauseFlags,
     /// Total supply of native tokens allocated to this contract
     pub total_supply: Uint128,
     /// Total tokens sold across all tiers (used for pricing tier calculation)
     pub total_tokens_sold: Uint128,
+    /// Total USD received across all purchases (6 decimals); defaults to zero for pre-existing state
+    #[serde(default)]
+    pub total_usd_received: Uint128,
+    /// Protocol fee taken from each purchase's native tokens, in basis points
+    #[serde(default)]
+    pub fee_bp: Uint128,
+    /// Receives the protocol fee; no fee is taken while unset
+    #[serde(default)]
+    pub fee_recipient: Option<String>,
+    /// Native denom used when the chain's bank supply can't be queried
+    #[serde(default = "default_fallback_denom")]
+    pub fallback_denom: String,
+    /// Decimal places of the native token, used to scale USD amounts into token units
+    #[serde(default = "default_native_decimals")]
+    pub native_decimals: u8,
+    /// Id of the latest purchase; ids start at 1 and increase by one per purchase
+    #[serde(default)]
+    pub purchase_id: u64,
+    /// Only buyers in ALLOWLIST may purchase
+    #[serde(default)]
+    pub require_allowlist: bool,
+    /// Seconds into a new UTC day that still count against the previous day's limit
+    #[serde(default)]
+    pub rollover_grace_seconds: u64,
+    /// Figure the daily limit's basis points apply to
+    #[serde(default)]
+    pub limit_basis: LimitBasis,
+    /// Circulating supply used by `LimitBasis::CirculatingOverride`
+    #[serde(default)]
+    pub circulating_supply: Option<Uint128>,
+    /// Largest USD value (micro-USD) accepted in a single purchase; unlimited while unset
+    #[serde(default)]
+    pub max_purchase_usd: Option<Uint128>,
+    /// Accept only CW20s in TRUSTED_CW20S, never asking the chain about the rest
+    #[serde(default)]
+    pub trust_cache_only: bool,
+    /// Purchases pause once the current tier price (micro-USD) rises above this; no ceiling while unset
+    #[serde(default)]
+    pub auto_pause_price_usd: Option<Uint128>,
+    /// Most USD (micro-USD) accepted per stats day, on top of the token-based daily limit; unlimited while unset
+    #[serde(default)]
+    pub daily_usd_limit: Option<Uint128>,
+    /// Check the pool's CW20 balance covers the reported receive amount, for tokens that take a transfer fee
+    #[serde(default)]
+    pub verify_received: bool,
+    /// Unix seconds after which a purchases pause lapses on its own, so a lost admin key can't
+    /// freeze the sale forever; None keeps the pause until an explicit Resume
+    #[serde(default)]
+    pub purchases_auto_resume_at: Option<u64>,
+    /// Whether the admin may zero today's stats with ResetDailyStats; set only at instantiate
+    #[serde(default)]
+    pub allow_daily_stats_reset: bool,
+    /// Most purchase transactions one buyer may make per stats day, whatever their size; unlimited while unset
+    #[serde(default)]
+    pub max_purchases_per_buyer_per_day: Option<u32>,
+    /// Sum of tier price (micro-USD) times seconds it was in effect, up to `twap_last_update`
+    #[serde(default)]
+    pub twap_price_cumulative: Uint256,
+    /// Block time (seconds) of the latest TWAP observation; zero before the first purchase
+    #[serde(default)]
+    pub twap_last_update: u64,
+    /// Tier price (micro-USD) in effect since `twap_last_update`
+    #[serde(default)]
+    pub twap_last_price: Uint128,
+    /// Receives CW20 and native payments for purchases, e.g. a treasury multisig; the admin
+    /// does while unset. Keeps its original name so stored configs still load
+    #[serde(default)]
+    pub cw20_proceeds_recipient: Option<String>,
+    /// Contract sent `PurchaseHookMsg::OnPurchase` after every purchase; none while unset
+    #[serde(default)]
+    pub purchase_hook: Option<String>,
+    /// Revert the purchase when the hook fails, instead of completing it without the hook
+    #[serde(default)]
+    pub hook_required: bool,
+    /// Prepended as `{prefix}_` to the contract's attribute keys, so indexers can tell several
+    /// instances apart; keys are bare while unset
+    #[serde(default)]
+    pub event_prefix: Option<String>,
+}
+
+impl Config {
+    /// Amount `daily_limit_bp` is taken of, per the selected limit basis
+    pub fn daily_limit_base(&self) -> Uint128 {
+        match self.limit_basis {
+            LimitBasis::TotalSupply => self.total_supply,
+            // Selecting the override requires a circulating supply, so the fallback is defensive
+            LimitBasis::CirculatingOverride => self.circulating_supply.unwrap_or(self.total_supply),
+        }
+    }
+
+    /// Where purchase proceeds are forwarded: the proceeds recipient, else the admin, else
+    /// nowhere (they stay in the contract)
+    pub fn proceeds_destination(&self) -> Option<&str> {
+        self.cw20_proceeds_recipient
+            .as_deref()
+            .or_else(|| Some(self.admin.as_str()).filter(|admin| !admin.is_empty()))
+    }
+
+    /// Whether purchases are paused at `block_seconds`, once any auto-resume time has passed
+    pub fn purchases_paused(&self, block_seconds: u64) -> bool {
+        self.pause_flags.purchases && self.purchases_auto_resume_at.is_none_or(|at| block_seconds <= at)
+    }
+}
+
+/// What the daily limit is computed against
+#[cw_serde]
+#[derive(Default)]
+pub enum LimitBasis {
+    /// The native allocation in `total_supply`
+    #[default]
+    TotalSupply,
+    /// An admin-maintained circulating supply figure (`circulating_supply`)
+    CirculatingOverride,
+}
+
+/// Fallback native denom for configs stored before it was configurable
+pub fn default_fallback_denom() -> String {
+    "ngonka".to_string()
+}
+
+/// Native decimals for configs stored before they were configurable
+pub fn default_native_decimals() -> u8 {
+    TOKEN_DECIMALS as u8
+}
+
+/// One whole native token in base units, 10^native_decimals
+pub fn native_scale(native_decimals: u8) -> Uint128 {
+    Uint128::new(10u128.pow(native_decimals as u32))
+}
+
+/// Day index that daily stats are bucketed under. With a grace period the day starts
+/// `rollover_grace_seconds` after UTC midnight, so purchases just past midnight keep counting
+/// against the previous day's limit instead of opening a fresh one.
+///
+/// Total over all of u64: the subtraction saturates and the division can't overflow, so even a
+/// block time near `u64::MAX` gives a valid (if absurd) day rather than wrapping to a small one.
+pub fn stats_day(block_seconds: u64, rollover_grace_seconds: u64) -> u64 {
+    block_seconds.saturating_sub(rollover_grace_seconds) / 86400
+}
+
+/// Independently pausable operations
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    /// Purchases via CW20 and native payments
+    pub purchases: bool,
+    /// Admin withdrawals of native tokens (emergency withdraw is never paused)
+    pub withdrawals: bool,
+}
+
+impl PauseFlags {
+    /// Flags selected when a Pause/Resume message names none: purchases only
+    pub fn purchases_only() -> Self {
+        PauseFlags {
+            purchases: true,
+            withdrawals: false,
+        }
+    }
+}
+
+/// Capabilities granted to an admin other than the config admin, as a bitmask. The config admin
+/// is the super-admin: it holds every capability and alone grants and revokes roles. Anything not
+/// under a capability is super-admin-only: the proceeds recipient, purchase hook, event prefix,
+/// received-amount verification and daily stats reset.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub struct AdminRole(pub u8);
+
+impl AdminRole {
+    /// Pause and Resume
+    pub const PAUSE: AdminRole = AdminRole(1);
+    /// Native withdrawals, burns, and CW20 sweeps and returns
+    pub const WITHDRAW: AdminRole = AdminRole(1 << 1);
+    /// Pricing config, the auto-pause price ceiling, daily and per-purchase limits, the supply
+    /// they are computed from, the rollover grace, and the protocol fee
+    pub const UPDATE_PRICING: AdminRole = AdminRole(1 << 2);
+    /// Native payment tokens, trusted CW20s and the buyer allowlist
+    pub const MANAGE_TOKENS: AdminRole = AdminRole(1 << 3);
+    pub const ALL: AdminRole = AdminRole(0b1111);
+
+    pub fn contains(self, other: AdminRole) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Config layout before pause flags, when a single `is_paused` blocked purchases.
+/// Only read by `migrate` to convert existing state.
+#[cw_serde]
+pub struct LegacyConfig {
+    pub admin: String,
+    pub native_denom: String,
+    pub daily_limit_bp: Uint128,
+    pub is_paused: bool,
+    pub total_supply: Uint128,
+    pub total_tokens_sold: Uint128,
+    #[serde(default)]
+    pub total_usd_received: Uint128,
+}
+
+impl From<LegacyConfig> for Config {
+    fn from(legacy: LegacyConfig) -> Self {
+        Config {
+            admin: legacy.admin,
+            native_denom: legacy.native_denom,
+            daily_limit_bp: legacy.daily_limit_bp,
+            pause_flags: PauseFlags {
+                purchases: legacy.is_paused,
+                withdrawals: false,
+            },
+            total_supply: legacy.total_supply,
+            total_tokens_sold: legacy.total_tokens_sold,
+            total_usd_received: legacy.total_usd_received,
+            fee_bp: Uint128::zero(),
+            fee_recipient: None,
+            fallback_denom: default_fallback_denom(),
+            native_decimals: default_native_decimals(),
+            purchase_id: 0,
+            require_allowlist: false,
+            rollover_grace_seconds: 0,
+            limit_basis: LimitBasis::TotalSupply,
+            circulating_supply: None,
+            max_purchase_usd: None,
+            trust_cache_only: false,
+            auto_pause_price_usd: None,
+            daily_usd_limit: None,
+            verify_received: false,
+            purchases_auto_resume_at: None,
+            allow_daily_stats_reset: false,
+            max_purchases_per_buyer_per_day: None,
+            twap_price_cumulative: Uint256::zero(),
+            twap_last_update: 0,
+            twap_last_price: Uint128::zero(),
+            cw20_proceeds_recipient: None,
+            purchase_hook: None,
+            hook_required: false,
+            event_prefix: None,
+        }
+    }
 }
 
 #[cw_serde]
 pub struct DailyStats {
-    /// Current day (block time / 86400)
+    /// Current day (block time less the rollover grace, / 86400)
     pub current_day: u64,
     /// USD amount received today (for tracking)
     pub usd_received_today: Uint128,
@@ -28,6 +273,15 @@ pub struct DailyStats {
     pub tokens_sold_today: Uint128,
 }
 
+impl DailyStats {
+    /// Day a purchase at `block_seconds` counts against. Block time is assumed monotonic, but
+    /// should it step back (or a longer rollover grace move the day boundary back), the stored
+    /// day stays current: archiving and resetting it would hand out a second daily limit.
+    pub fn day_at(&self, block_seconds: u64, rollover_grace_seconds: u64) -> u64 {
+        stats_day(block_seconds, rollover_grace_seconds).max(self.current_day)
+    }
+}
+
 #[cw_serde]
 pub struct PricingConfig {
     /// Base price per token in USD (with 6 decimals for USD, so 25000 = $0.025)
@@ -36,14 +290,85 @@ pub struct PricingConfig {
     pub tokens_per_tier: Uint128,
     /// Price multiplier for each tier (1.3x = 1300, representing 1300/1000)
     pub tier_multiplier: Uint128,
+    /// Last tier whose price escalates; later tiers keep this tier's price
+    #[serde(default)]
+    pub max_tier: Option<u32>,
 }
 
 /// Contract configuration
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Same storage key as CONFIG, read with the pre-pause-flags layout during migration
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
 /// Daily selling statistics
 pub const DAILY_STATS: Item<DailyStats> = Item::new("daily_stats");
 
+/// Archived daily statistics keyed by day index, written when the day rolls over
+pub const DAILY_STATS_HISTORY: Map<u64, DailyStats> = Map::new("daily_stats_history");
+
+/// Cumulative CW20 kept by the contract per (buyer, cw20 contract), recorded only while no admin is set
+pub const RECEIVED_FROM: Map<(&Addr, &str), Uint128> = Map::new("received_from");
+
+/// Purchases per (buyer, stats day); a new day starts a new key, so counts reset on their own
+pub const BUYER_DAILY_PURCHASES: Map<(&Addr, u64), u32> = Map::new("buyer_daily_purchases");
+
+/// Lifetime purchases of one buyer, across both payment paths
+#[cw_serde]
+#[derive(Default)]
+pub struct BuyerTotals {
+    /// Native tokens bought, before any protocol fee
+    pub tokens: Uint128,
+    /// Micro-USD spent
+    pub usd: Uint128,
+}
+
+pub const BUYER_TOTALS: Map<&Addr, BuyerTotals> = Map::new("buyer_totals");
+
+/// Cumulative CW20 forwarded to the admin over the life of the pool, per cw20 contract
+pub const FORWARDED_TOTALS: Map<&str, Uint128> = Map::new("forwarded_totals");
+
+/// Count of soft rejections per reason: purchases trimmed but still committed, e.g. a daily partial fill.
+/// Purchases that fail outright revert and are never counted.
+pub const REJECTION_STATS: Map<&str, u64> = Map::new("rejection_stats");
+
+/// Soft rejection reason: the daily token limit trimmed a purchase that allowed partial fills
+pub const REJECTION_DAILY_PARTIAL: &str = "daily_limit_partial";
+
+/// Accepted payment tokens (CW20 identifiers or native denoms) -> micro-USD per token unit
+pub const PAYMENT_TOKENS: Map<&str, Uint128> = Map::new("payment_tokens");
+
+/// Decimals of each payment token, keyed like `PAYMENT_TOKENS`, when known from TokenInfo or the
+/// admin. Informational only: rates are per base unit, so pricing never reads them; they let
+/// operators check a rate against the token.
+pub const PAYMENT_TOKEN_DECIMALS: Map<&str, u8> = Map::new("payment_token_decimals");
+
+/// Buyers approved to purchase while `require_allowlist` is set
+pub const ALLOWLIST: Map<&Addr, bool> = Map::new("allowlist");
+
+/// CW20s the admin trusts as bridge tokens, accepted without the chain's gRPC validation.
+/// A trusted entry outlives an on-chain revocation until the admin removes it.
+pub const TRUSTED_CW20S: Map<&Addr, bool> = Map::new("trusted_cw20s");
+
+/// Capabilities held by admins besides the config admin; addresses with none are removed
+pub const ADMIN_ROLES: Map<&Addr, AdminRole> = Map::new("admin_roles");
+
+/// Tokens sold within each pricing tier, keyed by tier index
+pub const TIER_SALES: Map<u32, Uint128> = Map::new("tier_sales");
+
+/// Accumulator snapshot taken on a purchase, for time-weighted average prices
+#[cw_serde]
+pub struct TwapObservation {
+    /// `Config::twap_price_cumulative` at the observation time
+    pub price_cumulative: Uint256,
+    /// Tier price (micro-USD) after the purchase
+    pub price: Uint128,
+}
+
+/// TWAP observations keyed by block time (seconds); the last purchase in a block wins. Only the
+/// last `MAX_TWAP_LOOKBACK_SECONDS` are kept, plus the observation in effect at its start.
+pub const TWAP_OBSERVATIONS: Map<u64, TwapObservation> = Map::new("twap_observations");
+
 /// Pricing configuration for tiered pricing
 pub const PRICING_CONFIG: Item<PricingConfig> = Item::new("pricing_config");
 
@@ -56,51 +381,192 @@ pub fn calculate_current_tier(tokens_sold: Uint128, tokens_per_tier: Uint128) ->
 }
 
 /// Calculate current tier based on USD value sold
-pub fn calculate_current_tier_usd(usd_sold: Uint128, tokens_per_tier: Uint128, base_price: Uint128) -> u32 {
+pub fn calculate_current_tier_usd(usd_sold: Uint128, tokens_per_tier: Uint128, base_price: Uint128) -> Result<u32, ContractError> {
     if tokens_per_tier.is_zero() || base_price.is_zero() {
-        return 0;
+        return Ok(0);
     }
     // Calculate how much USD is needed for one tier
-    let usd_per_tier = tokens_per_tier.checked_mul(base_price).unwrap_or_default();
+    let usd_per_tier = tokens_per_tier
+        .checked_mul(base_price)
+        .or_else(|_| on_overflow("calculate_current_tier_usd", Uint128::zero()))?;
     if usd_per_tier.is_zero() {
-        return 0;
+        return Ok(0);
     }
-    (usd_sold / usd_per_tier).u128() as u32
+    Ok((usd_sold / usd_per_tier).u128() as u32)
 }
 
-/// Calculate current price per token in USD (6 decimals for USD)
+/// Calculate current price per token in USD (6 decimals for USD), flat beyond `max_tier`
 pub fn calculate_current_price(
     base_price: Uint128,
     current_tier: u32,
     tier_multiplier: Uint128,
-) -> Uint128 {
+    max_tier: Option<u32>,
+) -> Result<Uint128, ContractError> {
+    let effective_tier = max_tier.map_or(current_tier, |max| current_tier.min(max));
     let mut price = base_price;
-    for _ in 0..current_tier {
+    for _ in 0..effective_tier {
         price = price
             .checked_mul(tier_multiplier)
-            .unwrap_or(price)
-            .checked_div(Uint128::from(1000u128))
-            .unwrap_or(price);
+            .map(|scaled| scaled / Uint128::from(1000u128))
+            .or_else(|_| on_overflow("calculate_current_price", price))?;
     }
-    price
+    Ok(price)
 }
 
 /// Calculate how many tokens can be bought with given USD amount
 pub fn calculate_tokens_for_usd(
     usd_amount: Uint128,
     price_per_token: Uint128,
-) -> Uint128 {
+    native_decimals: u8,
+) -> Result<Uint128, ContractError> {
     if price_per_token.is_zero() {
-        return Uint128::zero();
+        return Ok(Uint128::zero());
     }
     // usd_amount has 6 decimals, price_per_token has 6 decimals
-    // Result should be in token units (9 decimals)
-    // Scale by 1e9 to get 9-decimal tokens
-    usd_amount
-        .checked_mul(Uint128::from(1_000_000_000u128)) // 1e9 for 9-decimal tokens
-        .unwrap_or(Uint128::zero())
-        .checked_div(price_per_token)
-        .unwrap_or(Uint128::zero())
+    // Result should be in native token units, so scale by 10^native_decimals.
+    // The product is taken in Uint256 (two Uint128 factors always fit); a quotient beyond
+    // Uint128 saturates, and callers cap it to what is left in the tier.
+    let tokens = Uint256::from(usd_amount) * Uint256::from(native_scale(native_decimals)) / Uint256::from(price_per_token);
+    Uint128::try_from(tokens).or_else(|_| on_overflow("calculate_tokens_for_usd", Uint128::MAX))
+}
+
+/// Calculate the average price paid per token (6-decimal USD), zero when no tokens were bought
+pub fn calculate_average_price(total_usd: Uint128, total_tokens: Uint128, native_decimals: u8) -> Result<Uint128, ContractError> {
+    if total_tokens.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    // Scale up USD to match token decimals, then divide by tokens
+    total_usd
+        .checked_mul(native_scale(native_decimals))
+        .map(|scaled| scaled / total_tokens)
+        .or_else(|_| on_overflow("calculate_average_price", Uint128::zero()))
+}
+
+/// Calculate the tokens left in the current tier and the USD needed to buy all of them
+/// Returns (tokens_left_in_tier, usd_for_remaining_tier)
+pub fn calculate_tier_remainder(
+    tokens_sold: Uint128,
+    tokens_per_tier: Uint128,
+    current_price: Uint128,
+    native_decimals: u8,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let tokens_already_sold_in_tier = tokens_sold
+        .checked_rem(tokens_per_tier)
+        .unwrap_or_default();
+    let tokens_left_in_tier = tokens_per_tier
+        .checked_sub(tokens_already_sold_in_tier)
+        .unwrap_or_default();
+
+    // tokens_left_in_tier has native decimals, current_price has 6 decimals
+    // We need to divide by 10^native_decimals to get the correct USD amount with 6 decimals.
+    // Rounded up so completing a tier never leaves unpurchasable dust behind. The product is
+    // taken in Uint256 so a large tier at a high price can't overflow to a zero-cost tier; a
+    // tier costing more than Uint128::MAX saturates, which no purchase can complete anyway.
+    let scale = Uint256::from(native_scale(native_decimals));
+    let usd_for_remaining_tier = (Uint256::from(tokens_left_in_tier) * Uint256::from(current_price) + scale
+        - Uint256::one())
+        / scale;
+    let usd_for_remaining_tier = Uint128::try_from(usd_for_remaining_tier)
+        .or_else(|_| on_overflow("calculate_tier_remainder", Uint128::MAX))?;
+
+    Ok((tokens_left_in_tier, usd_for_remaining_tier))
+}
+
+/// USD needed to buy `tokens` starting at `tokens_sold`, walking the tiers like
+/// `calculate_multi_tier_purchase`. Whole tiers cost their rounded-up remainder and the final
+/// partial tier is floored, so spending the result never buys more than `tokens`.
+pub fn calculate_usd_for_tokens(
+    tokens: Uint128,
+    tokens_sold: Uint128,
+    pricing_config: &PricingConfig,
+    native_decimals: u8,
+) -> Result<Uint128, ContractError> {
+    if pricing_config.tokens_per_tier.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let mut usd = Uint128::zero();
+    let mut sold = tokens_sold;
+    let mut remaining = tokens;
+    // Same 50-tier cap as calculate_multi_tier_purchase
+    for _ in 0..50 {
+        if remaining.is_zero() {
+            break;
+        }
+        let current_tier = calculate_current_tier(sold, pricing_config.tokens_per_tier);
+        let current_price = calculate_current_price(
+            pricing_config.base_price_usd,
+            current_tier,
+            pricing_config.tier_multiplier,
+            pricing_config.max_tier,
+        )?;
+        let (tokens_left_in_tier, usd_for_remaining_tier) = calculate_tier_remainder(
+            sold,
+            pricing_config.tokens_per_tier,
+            current_price,
+            native_decimals,
+        )?;
+        if remaining < tokens_left_in_tier {
+            // In Uint256 like calculate_tier_remainder; saturating, as an overflow must never make tokens cheaper
+            let usd_in_tier = Uint256::from(remaining) * Uint256::from(current_price)
+                / Uint256::from(native_scale(native_decimals));
+            let usd_in_tier = Uint128::try_from(usd_in_tier)
+                .or_else(|_| on_overflow("calculate_usd_for_tokens", Uint128::MAX))?;
+            usd = usd
+                .checked_add(usd_in_tier)
+                .or_else(|_| on_overflow("calculate_usd_for_tokens", Uint128::MAX))?;
+            break;
+        }
+        usd = usd
+            .checked_add(usd_for_remaining_tier)
+            .or_else(|_| on_overflow("calculate_usd_for_tokens", Uint128::MAX))?;
+        sold = sold
+            .checked_add(tokens_left_in_tier)
+            .or_else(|_| on_overflow("calculate_usd_for_tokens", sold))?;
+        remaining -= tokens_left_in_tier;
+    }
+    Ok(usd)
+}
+
+/// Cost of buying `tokens` starting at `tokens_sold`, the dual of `calculate_multi_tier_purchase`
+/// Returns (usd_cost, start_tier, end_tier, average_price); rounding as in `calculate_usd_for_tokens`
+pub fn calculate_cost_for_tokens(
+    tokens: Uint128,
+    tokens_sold: Uint128,
+    pricing_config: &PricingConfig,
+    native_decimals: u8,
+) -> Result<(Uint128, u32, u32, Uint128), ContractError> {
+    let usd_cost = calculate_usd_for_tokens(tokens, tokens_sold, pricing_config, native_decimals)?;
+    let start_tier = calculate_current_tier(tokens_sold, pricing_config.tokens_per_tier);
+    // Like calculate_multi_tier_purchase, a purchase that fills its last tier ends in the next one
+    let end_tier = calculate_current_tier(tokens_sold.saturating_add(tokens), pricing_config.tokens_per_tier);
+    let average_price = calculate_average_price(usd_cost, tokens, native_decimals)?;
+    Ok((usd_cost, start_tier, end_tier, average_price))
+}
+
+/// Split a sale of `tokens` starting at `tokens_sold` into the tiers it falls in
+/// Returns (tier, tokens sold in that tier) in ascending tier order
+pub fn split_tokens_by_tier(
+    tokens_sold: Uint128,
+    tokens: Uint128,
+    tokens_per_tier: Uint128,
+) -> Vec<(u32, Uint128)> {
+    if tokens_per_tier.is_zero() {
+        return vec![(0, tokens)];
+    }
+
+    let mut splits = Vec::new();
+    let mut sold = tokens_sold;
+    let mut remaining = tokens;
+    while !remaining.is_zero() {
+        let tier = calculate_current_tier(sold, tokens_per_tier);
+        let left_in_tier = tokens_per_tier - sold % tokens_per_tier;
+        let in_tier = remaining.min(left_in_tier);
+        splits.push((tier, in_tier));
+        sold += in_tier;
+        remaining -= in_tier;
+    }
+    splits
 }
 
 /// Calculate multi-tier purchase: handles purchases that span multiple pricing tiers
@@ -109,9 +575,10 @@ pub fn calculate_multi_tier_purchase(
     usd_amount: Uint128,
     current_tokens_sold: Uint128,
     pricing_config: &PricingConfig,
-) -> (Uint128, Uint128, u32, u32, Uint128) {
+    native_decimals: u8,
+) -> Result<(Uint128, Uint128, u32, u32, Uint128), ContractError> {
     if usd_amount.is_zero() || pricing_config.tokens_per_tier.is_zero() || pricing_config.base_price_usd.is_zero() {
-        return (Uint128::zero(), Uint128::zero(), 0, 0, Uint128::zero());
+        return Ok((Uint128::zero(), Uint128::zero(), 0, 0, Uint128::zero()));
     }
 
     let mut remaining_usd = usd_amount;
@@ -124,7 +591,7 @@ pub fn calculate_multi_tier_purchase(
     let mut end_tier = start_tier;
 
     // Maximum 50 tier iterations to prevent infinite loops in case of edge cases
-    for iteration in 0..50 {
+    for _ in 0..50 {
         if remaining_usd.is_zero() {
             break;
         }
@@ -139,28 +606,20 @@ pub fn calculate_multi_tier_purchase(
             pricing_config.base_price_usd,
             current_tier,
             pricing_config.tier_multiplier,
-        );
+            pricing_config.max_tier,
+        )?;
 
         if current_price.is_zero() {
             break;
         }
 
-        // How many tokens are left in the current tier?
-        let tokens_already_sold_in_tier = current_tokens_sold_so_far
-            .checked_rem(pricing_config.tokens_per_tier)
-            .unwrap_or_default();
-        let tokens_left_in_tier = pricing_config.tokens_per_tier
-            .checked_sub(tokens_already_sold_in_tier)
-            .unwrap_or_default();
-
-        // How much USD is needed to buy all remaining tokens in this tier?
-        // tokens_left_in_tier has 9 decimals, current_price has 6 decimals
-        // We need to divide by 1e9 to get the correct USD amount with 6 decimals
-        let usd_for_remaining_tier = tokens_left_in_tier
-            .checked_mul(current_price)
-            .unwrap_or_default()
-            .checked_div(Uint128::from(1_000_000_000u128))
-            .unwrap_or_default();
+        // How many tokens are left in the current tier, and the USD needed to buy them all
+        let (tokens_left_in_tier, usd_for_remaining_tier) = calculate_tier_remainder(
+            current_tokens_sold_so_far,
+            pricing_config.tokens_per_tier,
+            current_price,
+            native_decimals,
+        )?;
 
         // Calculate USD needed and spending strategy
 
@@ -175,32 +634,28 @@ pub fn calculate_multi_tier_purchase(
             break;
         }
 
-        // Calculate tokens for this tier portion
-        let tokens_in_tier = calculate_tokens_for_usd(usd_to_spend_in_tier, current_price);
+        // Calculate tokens for this tier portion, capped at what is left in the tier
+        let tokens_in_tier = calculate_tokens_for_usd(usd_to_spend_in_tier, current_price, native_decimals)?
+            .min(tokens_left_in_tier);
         
         // Update running totals
-        total_tokens = total_tokens.checked_add(tokens_in_tier).unwrap_or(total_tokens);
-        actual_usd_spent = actual_usd_spent.checked_add(usd_to_spend_in_tier).unwrap_or(actual_usd_spent);
-        remaining_usd = remaining_usd.checked_sub(usd_to_spend_in_tier).unwrap_or_default();
-        current_tokens_sold_so_far = current_tokens_sold_so_far.checked_add(tokens_in_tier).unwrap_or(current_tokens_sold_so_far);
+        let overflowed = |fallback| on_overflow("calculate_multi_tier_purchase", fallback);
+        total_tokens = total_tokens.checked_add(tokens_in_tier).or_else(|_| overflowed(total_tokens))?;
+        actual_usd_spent = actual_usd_spent
+            .checked_add(usd_to_spend_in_tier)
+            .or_else(|_| overflowed(actual_usd_spent))?;
+        remaining_usd = remaining_usd.checked_sub(usd_to_spend_in_tier).or_else(|_| overflowed(Uint128::zero()))?;
+        current_tokens_sold_so_far = current_tokens_sold_so_far
+            .checked_add(tokens_in_tier)
+            .or_else(|_| overflowed(current_tokens_sold_so_far))?;
         
         // Update end tier
         end_tier = calculate_current_tier(current_tokens_sold_so_far, pricing_config.tokens_per_tier);
     }
 
     // Calculate average price paid (USD per token)
-    // USD has 6 decimals, tokens have 9 decimals, we want price in 6-decimal USD format
-    let average_price = if total_tokens.is_zero() {
-        Uint128::zero()
-    } else {
-        // Scale up USD by 1e9 to match token decimals, then divide by tokens
-        // This gives us price in micro-USD per token (same as base_price format)
-        actual_usd_spent
-            .checked_mul(Uint128::from(1_000_000_000u128))
-            .unwrap_or_default()
-            .checked_div(total_tokens)
-            .unwrap_or_default()
-    };
-
-    (total_tokens, actual_usd_spent, start_tier, end_tier, average_price)
+    // USD has 6 decimals, tokens have native decimals, we want price in 6-decimal USD format
+    let average_price = calculate_average_price(actual_usd_spent, total_tokens, native_decimals)?;
+
+    Ok((total_tokens, actual_usd_spent, start_tier, end_tier, average_price))
 } 
\ No newline at end of file
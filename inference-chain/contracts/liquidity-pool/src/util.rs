@@ -0,0 +1,49 @@
+use cosmwasm_std::Uint128;
+
+use crate::error::ContractError;
+
+/// Decimal places of USD amounts and prices (micro-USD)
+pub const USD_DECIMALS: u32 = 6;
+/// Default decimal places of the native token (ngonka)
+pub const TOKEN_DECIMALS: u32 = 9;
+/// Largest supported native decimals: 10^18 times any realistic micro-USD amount fits in a Uint128
+pub const MAX_NATIVE_DECIMALS: u8 = 18;
+/// Decimal places of the tier multiplier (1300 = 1.3x)
+pub const MULTIPLIER_DECIMALS: u32 = 3;
+/// Longest rollover grace: purchases up to an hour into a new day may count against the previous one
+pub const MAX_ROLLOVER_GRACE_SECONDS: u64 = 3600;
+/// Shortfall tolerated between a CW20's reported receive amount and the balance the pool holds (0.1%)
+pub const RECEIVED_TOLERANCE_BP: u128 = 10;
+/// Longest TWAP lookback; older observations are pruned as purchases come in
+pub const MAX_TWAP_LOOKBACK_SECONDS: u64 = 30 * 86_400;
+/// Observations pruned by a single purchase at most, so catching up on a backlog stays cheap
+pub const MAX_TWAP_PRUNE_PER_PURCHASE: usize = 10;
+/// Longest event prefix, so prefixed attribute keys stay readable
+pub const MAX_EVENT_PREFIX_LEN: usize = 16;
+
+/// Value a pricing step falls back to when `op` overflows. Production builds clamp to `fallback`
+/// so a pathological config degrades instead of bricking purchases; with the `strict_math`
+/// feature the step fails with `MathOverflow`, so tests can assert valid inputs never clamp.
+pub fn on_overflow<T>(op: &'static str, fallback: T) -> Result<T, ContractError> {
+    #[cfg(feature = "strict_math")]
+    {
+        let _ = fallback;
+        Err(ContractError::MathOverflow { op: op.to_string() })
+    }
+    #[cfg(not(feature = "strict_math"))]
+    {
+        let _ = op;
+        Ok(fallback)
+    }
+}
+
+/// Format a raw integer amount with the given number of implied decimal places,
+/// always printing every fractional digit (e.g. 25000 with 6 decimals -> "0.025000")
+pub fn format_decimal(amount: Uint128, decimals: u32) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let digits = format!("{:0>width$}", amount.u128(), width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    format!("{}.{}", whole, fraction)
+}
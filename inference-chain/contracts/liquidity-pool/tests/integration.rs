@@ -0,0 +1,453 @@
+//! End-to-end purchase flow using cw-multi-test.
+//!
+//! The wrapped-token contract is built against cosmwasm-std 2.x and can't be loaded into a
+//! cosmwasm-std 3.x `App`, so a minimal CW20 stand-in exposing the same `transfer`/`send`
+//! surface is used instead. The chain's gRPC bridge validation is stubbed through the
+//! `Stargate` handler.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, Api, Binary, BlockInfo, Deps, DepsMut, Empty, Env, Event, GrpcQuery,
+    MessageInfo, Querier, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, DistributionKeeper,
+    Executor, FailingModule, GovFailingModule, IbcFailingModule, StakeKeeper, Stargate, WasmKeeper,
+};
+use cw_storage_plus::Map;
+use prost::Message;
+
+use liquidity_pool::contract::QueryValidateWrappedTokenForTradeResponse;
+use liquidity_pool::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, PurchaseTokenMsg, QueryMsg};
+
+const NATIVE_DENOM: &str = "ngonka";
+
+/// Stubs the inference module gRPC queries; every CW20 validates as an approved bridge token
+struct BridgeStub;
+
+impl Stargate for BridgeStub {
+    fn query_grpc(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> StdResult<Binary> {
+        match request.path.as_str() {
+            "/inference.inference.Query/ValidateWrappedTokenForTrade" => {
+                let mut buf = Vec::new();
+                QueryValidateWrappedTokenForTradeResponse { is_valid: true }
+                    .encode(&mut buf)
+                    .map_err(|e| StdError::msg(e.to_string()))?;
+                Ok(Binary::from(buf))
+            }
+            // Unhandled paths (e.g. bank TotalSupply) make the pool use its fallback denom
+            path => Err(StdError::msg(format!("unsupported grpc path {}", path))),
+        }
+    }
+}
+
+mod mock_cw20 {
+    use super::*;
+
+    const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+    /// Accounts that can't receive transfers, to make a forward fail
+    const FROZEN: Map<&Addr, bool> = Map::new("frozen");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub holder: String,
+        pub amount: Uint128,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Transfer {
+            recipient: String,
+            amount: Uint128,
+        },
+        Send {
+            contract: String,
+            amount: Uint128,
+            msg: Binary,
+        },
+        Freeze {
+            address: String,
+        },
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        Balance { address: String },
+    }
+
+    #[cw_serde]
+    pub struct BalanceResponse {
+        pub balance: Uint128,
+    }
+
+    #[cw_serde]
+    struct Cw20ReceiveMsg {
+        sender: String,
+        amount: Uint128,
+        msg: Binary,
+    }
+
+    #[cw_serde]
+    enum ReceiverExecuteMsg {
+        Receive(Cw20ReceiveMsg),
+    }
+
+    fn move_balance(deps: DepsMut, from: &Addr, to: &Addr, amount: Uint128) -> StdResult<()> {
+        if FROZEN.has(deps.storage, to) {
+            return Err(StdError::msg(format!("account {} is frozen", to)));
+        }
+        BALANCES.update(deps.storage, from, |b| -> StdResult<_> {
+            Ok(b.unwrap_or_default().checked_sub(amount)?)
+        })?;
+        BALANCES.update(deps.storage, to, |b| -> StdResult<_> {
+            Ok(b.unwrap_or_default().checked_add(amount)?)
+        })?;
+        Ok(())
+    }
+
+    fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        let holder = deps.api.addr_validate(&msg.holder)?;
+        BALANCES.save(deps.storage, &holder, &msg.amount)?;
+        Ok(Response::new())
+    }
+
+    fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Transfer { recipient, amount } => {
+                let recipient = deps.api.addr_validate(&recipient)?;
+                move_balance(deps, &info.sender, &recipient, amount)?;
+                Ok(Response::new())
+            }
+            ExecuteMsg::Send {
+                contract,
+                amount,
+                msg,
+            } => {
+                let contract = deps.api.addr_validate(&contract)?;
+                move_balance(deps, &info.sender, &contract, amount)?;
+                let hook = ReceiverExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: info.sender.to_string(),
+                    amount,
+                    msg,
+                });
+                Ok(Response::new().add_message(WasmMsg::Execute {
+                    contract_addr: contract.to_string(),
+                    msg: to_json_binary(&hook)?,
+                    funds: vec![],
+                }))
+            }
+            ExecuteMsg::Freeze { address } => {
+                let address = deps.api.addr_validate(&address)?;
+                FROZEN.save(deps.storage, &address, &true)?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Balance { address } => {
+                let address = deps.api.addr_validate(&address)?;
+                let balance = BALANCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default();
+                to_json_binary(&BalanceResponse { balance })
+            }
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+}
+
+fn liquidity_pool_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            liquidity_pool::contract::execute,
+            liquidity_pool::contract::instantiate,
+            liquidity_pool::contract::query,
+        )
+        .with_reply(liquidity_pool::contract::reply),
+    )
+}
+
+type PoolApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    BridgeStub,
+>;
+
+struct Suite {
+    app: PoolApp,
+    pool: Addr,
+    cw20: Addr,
+    buyer: Addr,
+}
+
+/// Instantiates a funded pool and a CW20 held by the buyer
+fn setup(admin: Option<&str>) -> Suite {
+    let mut app = AppBuilder::new()
+        .with_stargate(BridgeStub)
+        .build(|_, _, _| {});
+    let owner = app.api().addr_make("owner");
+    let buyer = app.api().addr_make("buyer");
+    let admin = admin.map(|a| app.api().addr_make(a).to_string());
+
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, coins(1_000_000_000_000_000, NATIVE_DENOM))
+    })
+    .unwrap();
+
+    let cw20_code = app.store_code(mock_cw20::contract());
+    let pool_code = app.store_code(liquidity_pool_contract());
+
+    let cw20 = app
+        .instantiate_contract(
+            cw20_code,
+            owner.clone(),
+            &mock_cw20::InstantiateMsg {
+                holder: buyer.to_string(),
+                amount: Uint128::from(1_000_000_000u128), // $1,000 of a 6-decimal stablecoin
+            },
+            &[],
+            "wrapped-usdt",
+            None,
+        )
+        .unwrap();
+
+    let pool = app
+        .instantiate_contract(
+            pool_code,
+            owner.clone(),
+            &InstantiateMsg {
+                admin,
+                daily_limit_bp: Some(Uint128::from(1000u128)),
+                base_price_usd: Some(Uint128::from(25000u128)),
+                tokens_per_tier: Some(Uint128::from(3_000_000_000_000_000u128)),
+                tier_multiplier: Some(Uint128::from(1300u128)),
+                total_supply: Some(Uint128::from(120_000_000_000_000_000u128)),
+                fallback_denom: None,
+                native_decimals: None,
+                max_tier: None,
+                require_allowlist: None,
+                rollover_grace_seconds: None,
+                allow_daily_stats_reset: None,
+            },
+            &[],
+            "liquidity-pool",
+            None,
+        )
+        .unwrap();
+
+    app.send_tokens(
+        owner,
+        pool.clone(),
+        &coins(1_000_000_000_000_000, NATIVE_DENOM),
+    )
+    .unwrap();
+
+    Suite {
+        app,
+        pool,
+        cw20,
+        buyer,
+    }
+}
+
+impl Suite {
+    fn purchase(&mut self, amount: u128) -> StdResult<AppResponse> {
+        self.app.execute_contract(
+            self.buyer.clone(),
+            self.cw20.clone(),
+            &mock_cw20::ExecuteMsg::Send {
+                contract: self.pool.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_json_binary(&PurchaseTokenMsg { allow_daily_partial: false }).unwrap(),
+            },
+            &[],
+        )
+    }
+
+    fn cw20_balance(&self, address: &Addr) -> Uint128 {
+        let response: mock_cw20::BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.cw20,
+                &mock_cw20::QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        response.balance
+    }
+
+    fn native_balance(&self, address: &Addr) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(address, NATIVE_DENOM)
+            .unwrap()
+            .amount
+            .try_into()
+            .unwrap()
+    }
+}
+
+#[test]
+fn purchase_moves_native_to_buyer_and_cw20_to_admin() {
+    let mut suite = setup(Some("admin"));
+    let admin = suite.app.api().addr_make("admin");
+
+    suite.purchase(100_000_000).unwrap(); // $100
+
+    // $100 at $0.025 -> 4000 tokens with 9 decimals
+    assert_eq!(
+        suite.native_balance(&suite.buyer),
+        Uint128::from(4_000_000_000_000u128)
+    );
+    assert_eq!(suite.cw20_balance(&admin), Uint128::from(100_000_000u128));
+    assert_eq!(suite.cw20_balance(&suite.pool), Uint128::zero());
+    assert_eq!(
+        suite.cw20_balance(&suite.buyer),
+        Uint128::from(900_000_000u128)
+    );
+
+    let config: ConfigResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.pool, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(
+        config.total_tokens_sold,
+        Uint128::from(4_000_000_000_000u128)
+    );
+}
+
+#[test]
+fn purchase_without_admin_keeps_cw20_in_pool() {
+    let mut suite = setup(None);
+
+    suite.purchase(100_000_000).unwrap();
+
+    assert_eq!(
+        suite.native_balance(&suite.buyer),
+        Uint128::from(4_000_000_000_000u128)
+    );
+    assert_eq!(
+        suite.cw20_balance(&suite.pool),
+        Uint128::from(100_000_000u128)
+    );
+}
+
+#[test]
+fn failed_purchase_reverts_cw20_send() {
+    let mut suite = setup(Some("admin"));
+
+    // Zero-amount purchases are rejected by the pool, reverting the whole send
+    suite.purchase(0).unwrap_err();
+
+    assert_eq!(
+        suite.cw20_balance(&suite.buyer),
+        Uint128::from(1_000_000_000u128)
+    );
+    assert_eq!(suite.native_balance(&suite.buyer), Uint128::zero());
+}
+
+#[test]
+fn admin_sweeps_cw20_held_by_pool() {
+    let mut suite = setup(Some("admin"));
+    let admin = suite.app.api().addr_make("admin");
+
+    // CW20 sent straight to the pool, bypassing the purchase hook
+    suite
+        .app
+        .execute_contract(
+            suite.buyer.clone(),
+            suite.cw20.clone(),
+            &mock_cw20::ExecuteMsg::Transfer {
+                recipient: suite.pool.to_string(),
+                amount: Uint128::from(250_000_000u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let sweep = ExecuteMsg::SweepCw20 {
+        cw20_contract: suite.cw20.to_string(),
+    };
+    suite
+        .app
+        .execute_contract(suite.buyer.clone(), suite.pool.clone(), &sweep, &[])
+        .unwrap_err();
+
+    let res = suite
+        .app
+        .execute_contract(admin.clone(), suite.pool.clone(), &sweep, &[])
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm").add_attribute("swept_amount", "250000000")
+    ));
+    assert_eq!(suite.cw20_balance(&suite.pool), Uint128::zero());
+    assert_eq!(suite.cw20_balance(&admin), Uint128::from(250_000_000u128));
+}
+
+#[test]
+fn failed_cw20_forward_names_the_forward_and_reverts() {
+    let mut suite = setup(Some("admin"));
+    let admin = suite.app.api().addr_make("admin");
+
+    suite
+        .app
+        .execute_contract(
+            suite.buyer.clone(),
+            suite.cw20.clone(),
+            &mock_cw20::ExecuteMsg::Freeze {
+                address: admin.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = suite.purchase(100_000_000).unwrap_err();
+    let message = format!("{:?}", err);
+    assert!(
+        message.contains("Forwarding 100000000 of CW20") && message.contains("to admin failed"),
+        "{}",
+        message
+    );
+
+    // The whole purchase is rolled back
+    assert_eq!(
+        suite.cw20_balance(&suite.buyer),
+        Uint128::from(1_000_000_000u128)
+    );
+    assert_eq!(suite.native_balance(&suite.buyer), Uint128::zero());
+}
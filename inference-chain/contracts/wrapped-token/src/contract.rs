@@ -1,21 +1,26 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, to_json_vec, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, QueryRequest, GrpcQuery, StdError, ContractResult, SystemResult, Uint128, CosmosMsg,
+    StdResult, Storage, QueryRequest, GrpcQuery, StdError, ContractResult, SystemResult, Uint128, CosmosMsg, Order, Event,
 };
 use cw20_base::contract as cw20_base_contract;
 use cw20_base::msg as cw20_base_msg;
+use cw20_base::state::{ALLOWANCES, ALLOWANCES_SPENDER};
 use cw_utils::Expiration as CwExpiration;
 use cw20::{EmbeddedLogo as CwEmbeddedLogo, Logo as CwLogo};
 use cw2::{get_contract_version, set_contract_version};
-use cw_storage_plus::Item;
+use cw_storage_plus::{Bound, Item};
 use prost::Message as ProstMessage;
 
 use crate::error::ContractError;
 use crate::msg::{
-    BridgeInfoResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-    ApprovedTokensForTradeJson, ApprovedTokenJson,
+    AccountBalance, AllAccountsResponse, AllAllowancesResponse, AllBalancesResponse, AllowanceInfo, BridgeInfoResponse, Cw20Coin, ExecuteMsg, FullInfoResponse, InstantiateMsg, QueryMsg,
+    ApprovedTokensForTradeJson, ApprovedTokenJson, HistoricalHoldersResponse, HolderCountResponse, SupplyReconciliationResponse,
+    WithdrawalRequest,
+};
+use crate::state::{
+    BridgeInfo, BALANCES, BRIDGE_INFO, HISTORICAL_HOLDERS, HOLDER_COUNT, TOKEN_METADATA, TokenMetadataOverride, TOTAL_BURNED, TOTAL_MINTED,
+    TOTAL_WITHDRAWN, WITHDRAWAL_NONCE, WITHDRAWALS_PAUSED,
 };
-use crate::state::{ BridgeInfo, BRIDGE_INFO, TOKEN_METADATA, TokenMetadataOverride };
 
 // Admin storage: stores the address of the contract admin (governance module)
 pub const ADMIN: Item<Addr> = Item::new("admin");
@@ -26,6 +31,22 @@ pub const CREATOR: Item<Addr> = Item::new("creator");
 const CONTRACT_NAME: &str = "wrapped-token";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Pagination defaults, matching cw20-base enumerable queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+// Keys an AllBalances page reads at most, emptied accounts included
+const MAX_BALANCE_SCAN: usize = 100;
+
+// Marketing field bounds, to keep state and gas small
+const MAX_PROJECT_LENGTH: usize = 64;
+const MAX_DESCRIPTION_LENGTH: usize = 256;
+
+// Memo bound for TransferWithMemo
+const MAX_MEMO_LENGTH: usize = 128;
+
+// Entries per BatchWithdraw, to keep the message count per transaction bounded
+const MAX_BATCH_WITHDRAWALS: usize = 50;
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -57,6 +78,22 @@ pub fn instantiate(
     };
     ADMIN.save(deps.storage, &admin_addr)?;
     
+    // Supply is exactly the sum of initial balances; reject duplicates and a disagreeing caller total
+    let initial_supply = validate_initial_balances(deps.as_ref(), &msg.initial_balances)?;
+    if let Some(expected) = msg.total_supply {
+        if expected != initial_supply {
+            return Err(ContractError::TotalSupplyMismatch { expected, actual: initial_supply });
+        }
+    }
+    for coin in msg.initial_balances.iter().filter(|c| !c.amount.is_zero()) {
+        record_holder(deps.storage, &deps.api.addr_validate(&coin.address)?)?;
+    }
+
+    // Checked even though the cw20-base marketing below is seeded with fixed values
+    if let Some(marketing) = &msg.marketing {
+        validate_marketing_fields(marketing.project.as_deref(), marketing.description.as_deref())?;
+    }
+
     // Persist bridge info (extra state)
     BRIDGE_INFO.save(deps.storage, &BridgeInfo { chain_id: msg.chain_id.clone(), contract_address: msg.contract_address.clone() })?;
 
@@ -77,39 +114,172 @@ pub fn instantiate(
             logo: None,
         }),
     };
+    TOTAL_MINTED.save(deps.storage, &initial_supply)?;
     let resp = cw20_base_contract::instantiate(deps, env, info, cw20_init)
         .map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
     Ok(resp)
 }
 
+fn validate_marketing_fields(project: Option<&str>, description: Option<&str>) -> Result<(), ContractError> {
+    for (field, value, max) in [("project", project, MAX_PROJECT_LENGTH), ("description", description, MAX_DESCRIPTION_LENGTH)] {
+        if value.is_some_and(|v| v.chars().count() > max) {
+            return Err(ContractError::MarketingFieldTooLong { field: field.to_string(), max });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the sum of initial balances, failing on duplicate (normalized) addresses
+fn validate_initial_balances(deps: Deps, initial_balances: &[Cw20Coin]) -> Result<Uint128, ContractError> {
+    let mut seen = Vec::with_capacity(initial_balances.len());
+    let mut total = Uint128::zero();
+    for coin in initial_balances {
+        let addr = deps.api.addr_validate(&coin.address)?;
+        if seen.contains(&addr) {
+            return Err(ContractError::DuplicateInitialBalanceAddresses {});
+        }
+        seen.push(addr);
+        total = total.checked_add(coin.amount).map_err(StdError::from)?;
+    }
+    Ok(total)
+}
+
 // (Removed: legacy local cw20 state and queries — delegated to cw20-base)
 
 #[entry_point]
 pub fn execute(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if transfer_amount(&msg).is_some_and(|amount| amount.is_zero()) {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // Report allowance problems precisely before cw20-base flattens them into a generic error
+    match &msg {
+        ExecuteMsg::TransferFrom { owner, amount, .. }
+        | ExecuteMsg::SendFrom { owner, amount, .. }
+        | ExecuteMsg::BurnFrom { owner, amount } => check_allowance(deps.as_ref(), &env, &info, owner, *amount)?,
+        ExecuteMsg::IncreaseAllowance { spender, amount, .. } => check_allowance_headroom(deps.as_ref(), &info, spender, *amount)?,
+        _ => {}
+    }
+
+    // The recipient joins the historical holders once the transfer or mint has gone through
+    let recipient = recipient_of(&msg).map(str::to_string);
+    let resp = dispatch(deps.branch(), env, info, msg)?;
+    if let Some(recipient) = recipient {
+        record_holder(deps.storage, &deps.api.addr_validate(&recipient)?)?;
+    }
+    Ok(resp)
+}
+
+fn dispatch(mut deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
     match msg {
         // Custom extras
         ExecuteMsg::Withdraw { amount, destination_address } => withdraw(deps, env, info, amount, destination_address),
+        ExecuteMsg::BatchWithdraw { withdrawals } => batch_withdraw(deps, env, info, withdrawals),
         ExecuteMsg::UpdateMetadata { name, symbol, decimals } => update_metadata(deps, info, name, symbol, decimals),
+        ExecuteMsg::SetWithdrawalsPaused { paused } => set_withdrawals_paused(deps, info, paused),
+        ExecuteMsg::UpdateBridgeInfo { chain_id, contract_address } => update_bridge_info(deps, info, chain_id, contract_address),
+        ExecuteMsg::ForceBurn { owner, amount } => force_burn(deps, info, owner, amount),
+        ExecuteMsg::TransferWithMemo { recipient, amount, memo } => transfer_with_memo(deps, env, info, recipient, amount, memo),
+        ExecuteMsg::UpdateAllowance { spender, current, new, expires } => update_allowance(deps, env, info, spender, current, new, expires),
         // Delegate all standard cw20 ops
         ExecuteMsg::Transfer { recipient, amount } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::Transfer { recipient, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
-        ExecuteMsg::Burn { amount } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::Burn { amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
+        ExecuteMsg::Burn { amount } => {
+            let resp = cw20_base_contract::execute(deps.branch(), env, info, cw20_base_msg::ExecuteMsg::Burn { amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+            add_to_counter(deps.storage, &TOTAL_BURNED, amount)?;
+            Ok(resp)
+        }
         ExecuteMsg::Send { contract, amount, msg } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::Send { contract, amount, msg }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
-        ExecuteMsg::Mint { recipient, amount } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::Mint { recipient, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
+        ExecuteMsg::Mint { recipient, amount } => {
+            let resp = cw20_base_contract::execute(deps.branch(), env, info, cw20_base_msg::ExecuteMsg::Mint { recipient, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+            add_to_counter(deps.storage, &TOTAL_MINTED, amount)?;
+            Ok(resp)
+        }
         ExecuteMsg::IncreaseAllowance { spender, amount, expires } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::IncreaseAllowance { spender, amount, expires: map_expiration(expires) }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
         ExecuteMsg::DecreaseAllowance { spender, amount, expires } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::DecreaseAllowance { spender, amount, expires: map_expiration(expires) }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
         ExecuteMsg::TransferFrom { owner, recipient, amount } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::TransferFrom { owner, recipient, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
         ExecuteMsg::SendFrom { owner, contract, amount, msg } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::SendFrom { owner, contract, amount, msg }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
-        ExecuteMsg::BurnFrom { owner, amount } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::BurnFrom { owner, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
-        ExecuteMsg::UpdateMarketing { project, description, marketing } => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::UpdateMarketing { project, description, marketing }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            let resp = cw20_base_contract::execute(deps.branch(), env, info, cw20_base_msg::ExecuteMsg::BurnFrom { owner, amount }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+            add_to_counter(deps.storage, &TOTAL_BURNED, amount)?;
+            Ok(resp)
+        }
+        ExecuteMsg::UpdateMarketing { project, description, marketing } => {
+            validate_marketing_fields(project.as_deref(), description.as_deref())?;
+            cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::UpdateMarketing { project, description, marketing }).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))
+        }
         ExecuteMsg::UploadLogo(logo) => cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::UploadLogo(map_logo(logo))).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string()))),
     }
 }
 
+/// `NoAllowance` when the sender has no approval from `owner`, `InsufficientAllowance` when the
+/// approval is too small. Expired approvals are left for cw20-base to reject.
+fn check_allowance(deps: Deps, env: &Env, info: &MessageInfo, owner: &str, amount: Uint128) -> Result<(), ContractError> {
+    let owner_addr = deps.api.addr_validate(owner)?;
+    match ALLOWANCES.may_load(deps.storage, (&owner_addr, &info.sender))? {
+        None => Err(ContractError::NoAllowance {}),
+        Some(allowance) if !allowance.expires.is_expired(&env.block) && allowance.allowance < amount => {
+            Err(ContractError::InsufficientAllowance { available: allowance.allowance, required: amount })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// cw20-base adds to an allowance with a panicking `+=`; fail with an overflow error instead
+fn check_allowance_headroom(deps: Deps, info: &MessageInfo, spender: &str, amount: Uint128) -> Result<(), ContractError> {
+    let spender_addr = deps.api.addr_validate(spender)?;
+    if let Some(stored) = ALLOWANCES.may_load(deps.storage, (&info.sender, &spender_addr))? {
+        stored.allowance.checked_add(amount).map_err(StdError::from)?;
+    }
+    Ok(())
+}
+
+/// Amount moved, minted, burned or approved by a message, for the shared zero-amount guard.
+/// `UpdateAllowance` is excluded since setting it to zero is how an allowance is revoked.
+fn transfer_amount(msg: &ExecuteMsg) -> Option<Uint128> {
+    match msg {
+        ExecuteMsg::Transfer { amount, .. }
+        | ExecuteMsg::TransferWithMemo { amount, .. }
+        | ExecuteMsg::Send { amount, .. }
+        | ExecuteMsg::Mint { amount, .. }
+        | ExecuteMsg::Burn { amount }
+        | ExecuteMsg::TransferFrom { amount, .. }
+        | ExecuteMsg::SendFrom { amount, .. }
+        | ExecuteMsg::BurnFrom { amount, .. }
+        | ExecuteMsg::ForceBurn { amount, .. }
+        | ExecuteMsg::Withdraw { amount, .. }
+        | ExecuteMsg::IncreaseAllowance { amount, .. }
+        | ExecuteMsg::DecreaseAllowance { amount, .. } => Some(*amount),
+        _ => None,
+    }
+}
+
+/// Address that ends up holding the tokens a message moves or mints
+fn recipient_of(msg: &ExecuteMsg) -> Option<&str> {
+    match msg {
+        ExecuteMsg::Transfer { recipient, .. }
+        | ExecuteMsg::TransferWithMemo { recipient, .. }
+        | ExecuteMsg::TransferFrom { recipient, .. }
+        | ExecuteMsg::Mint { recipient, .. } => Some(recipient),
+        ExecuteMsg::Send { contract, .. } | ExecuteMsg::SendFrom { contract, .. } => Some(contract),
+        _ => None,
+    }
+}
+
+/// Adds `addr` to the historical holders the first time it receives tokens
+fn record_holder(storage: &mut dyn Storage, addr: &Addr) -> StdResult<()> {
+    if !HISTORICAL_HOLDERS.has(storage, addr) {
+        HISTORICAL_HOLDERS.save(storage, addr, &true)?;
+        let count = HOLDER_COUNT.may_load(storage)?.unwrap_or_default();
+        HOLDER_COUNT.save(storage, &(count + 1))?;
+    }
+    Ok(())
+}
+
 fn map_logo(logo: crate::msg::Logo) -> CwLogo {
     match logo {
         crate::msg::Logo::Url(u) => CwLogo::Url(u),
@@ -161,20 +331,168 @@ fn update_metadata(
         .add_attribute("decimals", decimals.to_string()))
 }
 
-// Special bridge withdraw function
-fn withdraw(
+fn set_withdrawals_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    WITHDRAWALS_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_withdrawals_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+/// Admin-only; withdrawals must be paused so none route with a mix of old and new info.
+fn update_bridge_info(
+    deps: DepsMut,
+    info: MessageInfo,
+    chain_id: String,
+    contract_address: String,
+) -> Result<Response, ContractError> {
+    if info.sender != ADMIN.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !WITHDRAWALS_PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::WithdrawalsNotPaused {});
+    }
+    if chain_id.trim().is_empty() || contract_address.trim().is_empty() {
+        return Err(ContractError::Std(StdError::generic_err("chain_id and contract_address cannot be empty")));
+    }
+
+    let old = BRIDGE_INFO.load(deps.storage)?;
+    BRIDGE_INFO.save(deps.storage, &BridgeInfo { chain_id: chain_id.clone(), contract_address: contract_address.clone() })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_bridge_info")
+        .add_attribute("old_chain_id", old.chain_id)
+        .add_attribute("old_contract_address", old.contract_address)
+        .add_attribute("new_chain_id", chain_id)
+        .add_attribute("new_contract_address", contract_address))
+}
+
+/// A standard cw20-base transfer that also emits `memo`; balances are handled exactly as `Transfer`.
+fn transfer_with_memo(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    memo: String,
+) -> Result<Response, ContractError> {
+    if memo.chars().count() > MAX_MEMO_LENGTH {
+        return Err(ContractError::MemoTooLong { max: MAX_MEMO_LENGTH });
+    }
+
+    let resp = cw20_base_contract::execute(deps, env, info, cw20_base_msg::ExecuteMsg::Transfer { recipient, amount })
+        .map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+    Ok(resp.add_attribute("memo", memo))
+}
+
+/// Compare-and-set allowance update, writing the same owner/spender indexes as cw20-base.
+/// Without `expires` the existing expiration is kept (never, for a new allowance). An expired
+/// allowance counts as none, so it compares as zero and its expiration is not carried over.
+fn update_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    current: Uint128,
+    new: Uint128,
+    expires: Option<crate::msg::Expiration>,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::Std(StdError::generic_err("Cannot set allowance to own account")));
+    }
+
+    let key = (&info.sender, &spender_addr);
+    let stored = ALLOWANCES
+        .may_load(deps.storage, key)?
+        .filter(|stored| !stored.expires.is_expired(&env.block))
+        .unwrap_or_default();
+    if stored.allowance != current {
+        return Err(ContractError::AllowanceChanged { expected: current, current: stored.allowance });
+    }
+
+    if new.is_zero() {
+        ALLOWANCES.remove(deps.storage, key);
+        ALLOWANCES_SPENDER.remove(deps.storage, (&spender_addr, &info.sender));
+    } else {
+        let expires = match map_expiration(expires) {
+            Some(exp) if exp.is_expired(&env.block) => return Err(ContractError::Expired {}),
+            Some(exp) => exp,
+            None => stored.expires,
+        };
+        let allowance = cw20::AllowanceResponse { allowance: new, expires };
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+        ALLOWANCES_SPENDER.save(deps.storage, (&spender_addr, &info.sender), &allowance)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("previous", current)
+        .add_attribute("amount", new))
+}
+
+/// Burns from `owner` without an allowance. Holders burn their own tokens via `Burn`, spenders via
+/// `BurnFrom`, and bridge exits via `Withdraw`; this path is reserved for the creator (inference
+/// module) and admin (governance module).
+fn force_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
     amount: Uint128,
-    destination_address: String,
 ) -> Result<Response, ContractError> {
-    if amount.is_zero() {
+    let creator = CREATOR.load(deps.storage)?;
+    let admin = ADMIN.load(deps.storage)?;
+    if info.sender != creator && info.sender != admin {
+        return Err(ContractError::OnlyAuthorizedCanBurn {});
+    }
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+
+    // Mirror cw20-base burn accounting on its own storage
+    let balance = BALANCES.may_load(deps.storage, &owner_addr)?.unwrap_or_default();
+    if balance < amount {
         return Err(ContractError::InsufficientFunds {
-            balance: 0,
-            required: 1,
+            balance: balance.u128(),
+            required: amount.u128(),
         });
     }
+    BALANCES.save(deps.storage, &owner_addr, &(balance - amount))?;
+    cw20_base::state::TOKEN_INFO.update(deps.storage, |mut token_info| -> StdResult<_> {
+        token_info.total_supply = token_info.total_supply.checked_sub(amount)?;
+        Ok(token_info)
+    })?;
+    add_to_counter(deps.storage, &TOTAL_BURNED, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "force_burn")
+        .add_attribute("from", owner_addr)
+        .add_attribute("amount", amount)
+        .add_attribute("by", info.sender))
+}
+
+// Adds to a supply counter; a missing counter starts at zero
+fn add_to_counter(storage: &mut dyn Storage, counter: &Item<Uint128>, amount: Uint128) -> StdResult<()> {
+    let total = counter.may_load(storage)?.unwrap_or_default().checked_add(amount)?;
+    counter.save(storage, &total)
+}
+
+// Special bridge withdraw function
+fn withdraw(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    destination_address: String,
+) -> Result<Response, ContractError> {
+    if WITHDRAWALS_PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::WithdrawalsPaused {});
+    }
 
     // Validate destination address is not empty
     if destination_address.trim().is_empty() {
@@ -183,11 +501,13 @@ fn withdraw(
 
     // Delegate to cw20-base burn
     let mut resp = cw20_base_contract::execute(
-        deps,
+        deps.branch(),
         env.clone(),
         info.clone(),
         cw20_base_msg::ExecuteMsg::Burn { amount },
     ).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+    add_to_counter(deps.storage, &TOTAL_WITHDRAWN, amount)?;
+    let nonce = next_withdrawal_nonce(deps.storage)?;
 
     // Create the bridge withdrawal message
     let bridge_msg = create_bridge_withdrawal_msg(
@@ -201,11 +521,72 @@ fn withdraw(
         .add_message(bridge_msg)
         .add_attribute("method", "withdraw")
         .add_attribute("burn_amount", amount)
-        .add_attribute("destination_address", destination_address);
+        .add_attribute("destination_address", destination_address)
+        .add_attribute("nonce", nonce.to_string());
 
     Ok(resp)
 }
 
+// Burns the batch total in one go, so either every withdrawal in the batch goes out or none does
+fn batch_withdraw(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    withdrawals: Vec<WithdrawalRequest>,
+) -> Result<Response, ContractError> {
+    if WITHDRAWALS_PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::WithdrawalsPaused {});
+    }
+    if withdrawals.is_empty() || withdrawals.len() > MAX_BATCH_WITHDRAWALS {
+        return Err(ContractError::InvalidBatchSize { max: MAX_BATCH_WITHDRAWALS });
+    }
+
+    let mut total = Uint128::zero();
+    for withdrawal in &withdrawals {
+        if withdrawal.amount.is_zero() {
+            return Err(ContractError::ZeroAmount {});
+        }
+        if withdrawal.destination_address.trim().is_empty() {
+            return Err(ContractError::Std(StdError::generic_err("destination_address cannot be empty")));
+        }
+        total = total.checked_add(withdrawal.amount).map_err(StdError::from)?;
+    }
+
+    let mut resp = cw20_base_contract::execute(
+        deps.branch(),
+        env.clone(),
+        info.clone(),
+        cw20_base_msg::ExecuteMsg::Burn { amount: total },
+    ).map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
+    add_to_counter(deps.storage, &TOTAL_WITHDRAWN, total)?;
+
+    for withdrawal in withdrawals {
+        let nonce = next_withdrawal_nonce(deps.storage)?;
+        let bridge_msg = create_bridge_withdrawal_msg(
+            env.contract.address.to_string(),
+            info.sender.to_string(),
+            withdrawal.amount.to_string(),
+            withdrawal.destination_address.clone(),
+        )?;
+        resp = resp.add_message(bridge_msg).add_event(
+            Event::new("bridge_withdrawal")
+                .add_attribute("nonce", nonce.to_string())
+                .add_attribute("amount", withdrawal.amount)
+                .add_attribute("destination_address", withdrawal.destination_address),
+        );
+    }
+
+    Ok(resp
+        .add_attribute("method", "batch_withdraw")
+        .add_attribute("burn_amount", total))
+}
+
+fn next_withdrawal_nonce(storage: &mut dyn Storage) -> StdResult<u64> {
+    let nonce = WITHDRAWAL_NONCE.may_load(storage)?.unwrap_or_default() + 1;
+    WITHDRAWAL_NONCE.save(storage, &nonce)?;
+    Ok(nonce)
+}
+
 // Proto message for MsgRequestBridgeWithdrawal
 #[derive(Clone, PartialEq, ProstMessage)]
 pub struct MsgRequestBridgeWithdrawal {
@@ -251,6 +632,7 @@ fn create_bridge_withdrawal_msg(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::BridgeInfo {} => to_json_binary(&query_bridge_info(deps)?),
+        QueryMsg::Paused {} => to_json_binary(&WITHDRAWALS_PAUSED.may_load(deps.storage)?.unwrap_or(false)),
         QueryMsg::Balance { address } => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::Balance { address }),
         QueryMsg::TokenInfo {} => {
             let base_bin = cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::TokenInfo {})?;
@@ -269,11 +651,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&resp)
         },
         QueryMsg::Allowance { owner, spender } => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::Allowance { owner, spender }),
-        QueryMsg::AllAllowances { owner, start_after, limit } => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::AllAllowances { owner, start_after, limit }),
-        QueryMsg::AllAccounts { start_after, limit } => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::AllAccounts { start_after, limit }),
+        QueryMsg::AllAllowances { owner, start_after, limit } => to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllAccounts { start_after, limit } => to_json_binary(&query_all_accounts(deps, start_after, limit)?),
+        QueryMsg::HistoricalHolders { start_after, limit } => {
+            to_json_binary(&query_historical_holders(deps, start_after, limit)?)
+        }
+        QueryMsg::HolderCount {} => to_json_binary(&HolderCountResponse {
+            count: HOLDER_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::AllBalances { start_after, limit } => to_json_binary(&query_all_balances(deps, start_after, limit)?),
+        QueryMsg::FullInfo {} => to_json_binary(&query_full_info(deps)?),
         QueryMsg::MarketingInfo {} => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::MarketingInfo {}),
         QueryMsg::DownloadLogo {} => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::DownloadLogo {}),
         QueryMsg::Minter {} => cw20_base_contract::query(deps, env, cw20_base_msg::QueryMsg::Minter {}),
+        QueryMsg::ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
+        QueryMsg::SupplyReconciliation {} => to_json_binary(&query_supply_reconciliation(deps)?),
         QueryMsg::TestApprovedTokens {} => to_json_binary(&query_test_approved_tokens(deps)?),
     }
 }
@@ -298,6 +690,22 @@ pub fn migrate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
         .map_err(|e| ContractError::Std(StdError::generic_err(e.to_string())))?;
 
+    // Contracts from before the supply counters start reconciled: the current supply counts as minted
+    if TOTAL_MINTED.may_load(deps.storage)?.is_none() {
+        let token_info = cw20_base::state::TOKEN_INFO.load(deps.storage)?;
+        TOTAL_MINTED.save(deps.storage, &token_info.total_supply)?;
+    }
+
+    // Contracts from before holder tracking: balance entries outlive a zero balance, so they name
+    // everyone who has held tokens so far. Saving the count marks the backfill done even when empty.
+    if HOLDER_COUNT.may_load(deps.storage)?.is_none() {
+        let holders = BALANCES.keys(deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+        for holder in &holders {
+            record_holder(deps.storage, holder)?;
+        }
+        HOLDER_COUNT.save(deps.storage, &(holders.len() as u64))?;
+    }
+
     Ok(Response::new()
         .add_attribute("action", "migrate")
         .add_attribute("from_contract", old.contract)
@@ -311,7 +719,8 @@ fn query_grpc(deps: Deps, path: &str, data: Binary) -> StdResult<Binary> {
         path: path.to_string(),
         data,
     });
-    query_raw(deps, &request)
+    // Name the path so it's clear which of several gRPC queries failed
+    query_raw(deps, &request).map_err(|e| StdError::generic_err(format!("{}: {}", path, e)))
 }
 
 fn query_raw(deps: Deps, request: &QueryRequest<GrpcQuery>) -> StdResult<Binary> {
@@ -336,6 +745,147 @@ fn query_bridge_info(deps: Deps) -> StdResult<BridgeInfoResponse> {
     })
 }
 
+fn query_supply_reconciliation(deps: Deps) -> StdResult<SupplyReconciliationResponse> {
+    let token_info = cw20_base::state::TOKEN_INFO.load(deps.storage)?;
+    Ok(SupplyReconciliationResponse {
+        total_supply: token_info.total_supply,
+        total_minted: TOTAL_MINTED.may_load(deps.storage)?.unwrap_or_default(),
+        total_burned: TOTAL_BURNED.may_load(deps.storage)?.unwrap_or_default(),
+        total_withdrawn: TOTAL_WITHDRAWN.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn query_full_info(deps: Deps) -> StdResult<FullInfoResponse> {
+    let token_info = cw20_base::state::TOKEN_INFO.load(deps.storage)?;
+    let (name, symbol, decimals) = match TOKEN_METADATA.may_load(deps.storage)? {
+        Some(override_md) => (override_md.name, override_md.symbol, override_md.decimals),
+        None => (token_info.name, token_info.symbol, token_info.decimals),
+    };
+    let bridge = BRIDGE_INFO.load(deps.storage)?;
+    let marketing = cw20_base::state::MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(FullInfoResponse {
+        name,
+        symbol,
+        decimals,
+        total_supply: token_info.total_supply,
+        chain_id: bridge.chain_id,
+        contract_address: bridge.contract_address,
+        project: marketing.project,
+        description: marketing.description,
+    })
+}
+
+fn query_all_balances(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllBalancesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    // Zero balances are left behind by full transfers; skip them so pages only carry holders.
+    // A long run of them ends the page early at the last key read, so it may be short or empty
+    let mut balances = Vec::with_capacity(limit);
+    let mut last_read = None;
+    let mut next_start_after = None;
+    for (read, item) in BALANCES.range(deps.storage, start, None, Order::Ascending).enumerate() {
+        let (address, balance) = item?;
+        if balances.len() == limit || read == MAX_BALANCE_SCAN {
+            next_start_after = last_read.map(|addr: Addr| addr.to_string());
+            break;
+        }
+        if !balance.is_zero() {
+            balances.push(AccountBalance { address: address.to_string(), balance });
+        }
+        last_read = Some(address);
+    }
+
+    Ok(AllBalancesResponse { balances, next_start_after })
+}
+
+// Every account that has held a balance, zero or not, in ascending address order
+fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let mut accounts = BALANCES
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| item.map(|address| address.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = next_cursor(&mut accounts, limit, |a| a.clone());
+
+    Ok(AllAccountsResponse { accounts, next_start_after })
+}
+
+// Everyone who has ever received tokens, in ascending address order
+fn query_historical_holders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<HistoricalHoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let mut holders = HISTORICAL_HOLDERS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| item.map(|address| address.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = next_cursor(&mut holders, limit, |h| h.clone());
+
+    Ok(HistoricalHoldersResponse { holders, next_start_after })
+}
+
+// An owner's allowances in ascending spender order, expired ones included
+fn query_all_allowances(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAllowancesResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    // Same "allowance" namespace as cw20-base, read with this crate's expiration type
+    let mut allowances = crate::state::ALLOWANCES
+        .prefix(&owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            item.map(|(spender, allowance)| AllowanceInfo {
+                spender: spender.to_string(),
+                allowance: allowance.allowance,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = next_cursor(&mut allowances, limit, |a| a.spender.clone());
+
+    Ok(AllAllowancesResponse { allowances, next_start_after })
+}
+
+// Pages are read with one extra item: if it is there, drop it and hand back the last kept
+// key as the cursor, so the final page reports None instead of a cursor to an empty page
+fn next_cursor<T>(items: &mut Vec<T>, limit: usize, key: impl Fn(&T) -> String) -> Option<String> {
+    if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(key)
+    } else {
+        None
+    }
+}
+
 fn query_test_approved_tokens(deps: Deps) -> StdResult<ApprovedTokensForTradeJson> {
     let decoded: QueryApprovedTokensForTradeResponseProto = query_proto(
         deps,
@@ -377,8 +927,682 @@ where
     let mut buf = Vec::new();
     request
         .encode(&mut buf)
-        .map_err(|e| StdError::generic_err(format!("Encode request: {}", e)))?;
+        .map_err(|e| StdError::generic_err(format!("Encode request for {}: {}", path, e)))?;
     let bytes = query_grpc(deps, path, Binary::from(buf))?;
     TResponse::decode(bytes.as_slice())
-        .map_err(|e| StdError::generic_err(format!("Decode response: {}", e)))
-}
\ No newline at end of file
+        .map_err(|e| StdError::generic_err(format!("Decode response from {}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::{BalanceResponse, Expiration, InstantiateMarketingInfo, MinterResponse, TokenInfoResponse};
+    use cosmwasm_std::from_json;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+
+    const USDT: &str = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+
+    fn mock_instantiate_msg(admin: &Addr, holder: &Addr) -> InstantiateMsg {
+        InstantiateMsg {
+            chain_id: "ethereum".to_string(),
+            contract_address: USDT.to_string(),
+            initial_balances: vec![Cw20Coin { address: holder.to_string(), amount: Uint128::new(1_000) }],
+            total_supply: None,
+            mint: None,
+            marketing: None,
+            admin: Some(admin.to_string()),
+        }
+    }
+
+    #[test]
+    fn force_burn_requires_creator_or_admin() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        // Ordinary holders cannot force-burn, not even their own balance
+        let force_burn = ExecuteMsg::ForceBurn { owner: holder.to_string(), amount: Uint128::new(100) };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), force_burn.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::OnlyAuthorizedCanBurn {}));
+
+        // ...but the standard holder burn stays open
+        let burn = ExecuteMsg::Burn { amount: Uint128::new(100) };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), burn).unwrap();
+
+        execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), force_burn.clone()).unwrap();
+        execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), force_burn).unwrap();
+
+        let balance: BalanceResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::Balance { address: holder.to_string() }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(balance.balance, Uint128::new(700));
+        let token_info: TokenInfoResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        assert_eq!(token_info.total_supply, Uint128::new(700));
+
+        // Cannot burn more than the owner holds
+        let too_much = ExecuteMsg::ForceBurn { owner: holder.to_string(), amount: Uint128::new(701) };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), too_much).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientFunds { .. }));
+    }
+
+    #[test]
+    fn all_balances_skips_emptied_accounts_within_a_bounded_scan() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+
+        let mut holders: Vec<Addr> = (0..150).map(|i| deps.api.addr_make(&format!("holder{}", i))).collect();
+        holders.sort();
+        let mut msg = mock_instantiate_msg(&admin, &holders[0]);
+        msg.initial_balances = holders
+            .iter()
+            .map(|holder| Cw20Coin { address: holder.to_string(), amount: Uint128::new(1_000) })
+            .collect();
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        // Empty the first 110 accounts into the last one
+        for holder in &holders[..110] {
+            let transfer = ExecuteMsg::Transfer { recipient: holders[149].to_string(), amount: Uint128::new(1_000) };
+            execute(deps.as_mut(), mock_env(), message_info(holder, &[]), transfer).unwrap();
+        }
+
+        let page = |deps: Deps, start_after: Option<&Addr>, limit: Option<u32>| -> AllBalancesResponse {
+            let msg = QueryMsg::AllBalances { start_after: start_after.map(|a| a.to_string()), limit };
+            from_json(query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // The first page reads its 100 keys, all emptied, and hands back a cursor rather than nothing
+        let res = page(deps.as_ref(), None, Some(5));
+        assert!(res.balances.is_empty());
+        assert_eq!(res.next_start_after, Some(holders[99].to_string()));
+
+        let res = page(deps.as_ref(), Some(&holders[99]), Some(5));
+        let addresses: Vec<String> = res.balances.iter().map(|b| b.address.clone()).collect();
+        let expected: Vec<String> = holders[110..115].iter().map(|a| a.to_string()).collect();
+        assert_eq!(addresses, expected);
+        assert_eq!(res.next_start_after, Some(holders[114].to_string()));
+
+        // A limit above the maximum is clamped to 30
+        let res = page(deps.as_ref(), Some(&holders[109]), Some(1_000));
+        assert_eq!(res.balances.len(), 30);
+        assert_eq!(res.next_start_after, Some(holders[139].to_string()));
+
+        let res = page(deps.as_ref(), Some(&holders[139]), None);
+        assert_eq!(res.balances.len(), 10);
+        assert_eq!(res.balances[9].balance, Uint128::new(111_000));
+        assert_eq!(res.next_start_after, None);
+    }
+
+    #[test]
+    fn update_bridge_info_requires_admin_and_paused_withdrawals() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let new_usdt = "0x1111111111111111111111111111111111111111";
+        let update = ExecuteMsg::UpdateBridgeInfo { chain_id: "ethereum".to_string(), contract_address: new_usdt.to_string() };
+
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), update.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::WithdrawalsNotPaused {}));
+
+        let paused = |deps: Deps| -> bool { from_json(query(deps, mock_env(), QueryMsg::Paused {}).unwrap()).unwrap() };
+        assert!(!paused(deps.as_ref()));
+
+        let pause = ExecuteMsg::SetWithdrawalsPaused { paused: true };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), pause.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), pause).unwrap();
+        assert!(paused(deps.as_ref()));
+
+        // Paused withdrawals are rejected before anything is burned
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(10), destination_address: "0xabc".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), withdraw).unwrap_err();
+        assert!(matches!(err, ContractError::WithdrawalsPaused {}));
+
+        // The creator is not the WASM admin
+        let err = execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), update.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), update).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "old_contract_address" && a.value == USDT));
+        assert!(res.attributes.iter().any(|a| a.key == "new_contract_address" && a.value == new_usdt));
+
+        let bridge: BridgeInfoResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::BridgeInfo {}).unwrap()).unwrap();
+        assert_eq!(bridge.contract_address, new_usdt);
+    }
+
+    #[test]
+    fn full_info_combines_token_bridge_and_marketing() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let update = ExecuteMsg::UpdateMetadata { name: "Tether USD".to_string(), symbol: "USDT".to_string(), decimals: 6 };
+        execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), update).unwrap();
+
+        let info: FullInfoResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::FullInfo {}).unwrap()).unwrap();
+        assert_eq!(info.name, "Tether USD");
+        assert_eq!(info.symbol, "USDT");
+        assert_eq!(info.decimals, 6);
+        assert_eq!(info.total_supply, Uint128::new(1_000));
+        assert_eq!(info.chain_id, "ethereum");
+        assert_eq!(info.contract_address, USDT);
+        assert_eq!(info.project.as_deref(), Some("Gonka Wrapped Token"));
+        assert_eq!(info.description.as_deref(), Some("Bridge-wrapped token for cross-chain transfers"));
+
+        // Until the first migrate, the recorded version is the one cw20-base instantiate wrote
+        let version: cw2::ContractVersion =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::ContractVersion {}).unwrap()).unwrap();
+        assert_eq!(version.contract, "crates.io:cw20-base");
+    }
+
+    #[test]
+    fn instantiate_checks_initial_balances_against_total_supply() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let other = deps.api.addr_make("other");
+
+        let mut msg = mock_instantiate_msg(&admin, &holder);
+        msg.initial_balances.push(Cw20Coin { address: holder.to_string(), amount: Uint128::new(5) });
+        let err = instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateInitialBalanceAddresses {}));
+
+        let mut msg = mock_instantiate_msg(&admin, &holder);
+        msg.initial_balances.push(Cw20Coin { address: other.to_string(), amount: Uint128::new(500) });
+        msg.total_supply = Some(Uint128::new(1_000));
+        let err = instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::TotalSupplyMismatch { expected, actual }
+                if expected == Uint128::new(1_000) && actual == Uint128::new(1_500)
+        ));
+
+        msg.total_supply = Some(Uint128::new(1_500));
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+        let token_info: TokenInfoResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        assert_eq!(token_info.total_supply, Uint128::new(1_500));
+    }
+
+    #[test]
+    fn update_allowance_is_compare_and_set() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let spender = deps.api.addr_make("spender");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let increase = ExecuteMsg::IncreaseAllowance { spender: spender.to_string(), amount: Uint128::new(100), expires: None };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase).unwrap();
+
+        // Spender front-runs and uses part of the allowance
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: holder.to_string(),
+            recipient: spender.to_string(),
+            amount: Uint128::new(40),
+        };
+        execute(deps.as_mut(), mock_env(), message_info(&spender, &[]), transfer_from).unwrap();
+
+        let update = ExecuteMsg::UpdateAllowance {
+            spender: spender.to_string(),
+            current: Uint128::new(100),
+            new: Uint128::new(10),
+            expires: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), update).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AllowanceChanged { expected, current }
+                if expected == Uint128::new(100) && current == Uint128::new(60)
+        ));
+
+        let update = ExecuteMsg::UpdateAllowance {
+            spender: spender.to_string(),
+            current: Uint128::new(60),
+            new: Uint128::new(10),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), update).unwrap();
+
+        let allowance: cw20::AllowanceResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Allowance { owner: holder.to_string(), spender: spender.to_string() },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(allowance.allowance, Uint128::new(10));
+    }
+
+    #[test]
+    fn update_allowance_treats_expired_allowance_as_none() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let spender = deps.api.addr_make("spender");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        let increase = ExecuteMsg::IncreaseAllowance {
+            spender: spender.to_string(),
+            amount: Uint128::new(100),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        execute(deps.as_mut(), env.clone(), message_info(&holder, &[]), increase).unwrap();
+        env.block.height += 10;
+
+        let update = |current: u128| ExecuteMsg::UpdateAllowance {
+            spender: spender.to_string(),
+            current: Uint128::new(current),
+            new: Uint128::new(50),
+            expires: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), message_info(&holder, &[]), update(100)).unwrap_err();
+        assert!(matches!(err, ContractError::AllowanceChanged { current, .. } if current.is_zero()));
+
+        // The new allowance doesn't inherit the lapsed expiration
+        execute(deps.as_mut(), env.clone(), message_info(&holder, &[]), update(0)).unwrap();
+        let stored = ALLOWANCES.load(deps.as_ref().storage, (&holder, &spender)).unwrap();
+        assert_eq!(stored.allowance, Uint128::new(50));
+        assert_eq!(stored.expires, CwExpiration::Never {});
+    }
+
+    #[test]
+    fn increase_allowance_rejects_overflow_and_keeps_expiry_unless_given() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let spender = deps.api.addr_make("spender");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let increase = |amount: u128, expires: Option<Expiration>| ExecuteMsg::IncreaseAllowance {
+            spender: spender.to_string(),
+            amount: Uint128::new(amount),
+            expires,
+        };
+        let allowance = |deps: Deps| -> cw20::AllowanceResponse {
+            let query_msg = QueryMsg::Allowance { owner: holder.to_string(), spender: spender.to_string() };
+            from_json(query(deps, mock_env(), query_msg).unwrap()).unwrap()
+        };
+        let deadline = mock_env().block.height + 100;
+
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase(u128::MAX - 1, Some(Expiration::AtHeight(deadline)))).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase(2, None)).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+        assert_eq!(allowance(deps.as_ref()).allowance, Uint128::new(u128::MAX - 1));
+
+        // None keeps the stored expiry
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase(1, None)).unwrap();
+        let stored = allowance(deps.as_ref());
+        assert_eq!(stored.allowance, Uint128::MAX);
+        assert_eq!(stored.expires, CwExpiration::AtHeight(deadline));
+
+        // An explicit Never replaces it, and later Nones keep Never
+        let decrease = ExecuteMsg::DecreaseAllowance {
+            spender: spender.to_string(),
+            amount: Uint128::new(10),
+            expires: Some(Expiration::Never {}),
+        };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), decrease).unwrap();
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase(5, None)).unwrap();
+        let stored = allowance(deps.as_ref());
+        assert_eq!(stored.allowance, Uint128::new(u128::MAX - 5));
+        assert_eq!(stored.expires, CwExpiration::Never {});
+    }
+
+    #[test]
+    fn transfer_with_memo_emits_memo() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let other = deps.api.addr_make("other");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let transfer = ExecuteMsg::TransferWithMemo {
+            recipient: other.to_string(),
+            amount: Uint128::new(250),
+            memo: "deposit 0xabc/42".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), transfer).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "action" && a.value == "transfer"));
+        assert!(res.attributes.iter().any(|a| a.key == "memo" && a.value == "deposit 0xabc/42"));
+
+        let balance: BalanceResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Balance { address: other.to_string() }).unwrap()).unwrap();
+        assert_eq!(balance.balance, Uint128::new(250));
+
+        let too_long = ExecuteMsg::TransferWithMemo {
+            recipient: other.to_string(),
+            amount: Uint128::new(1),
+            memo: "m".repeat(MAX_MEMO_LENGTH + 1),
+        };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), too_long).unwrap_err();
+        assert!(matches!(err, ContractError::MemoTooLong { max: MAX_MEMO_LENGTH }));
+    }
+
+    #[test]
+    fn zero_amounts_are_rejected() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let other = deps.api.addr_make("other").to_string();
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let zero = Uint128::zero();
+        let msgs = vec![
+            ExecuteMsg::Transfer { recipient: other.clone(), amount: zero },
+            ExecuteMsg::TransferWithMemo { recipient: other.clone(), amount: zero, memo: String::new() },
+            ExecuteMsg::Send { contract: other.clone(), amount: zero, msg: Binary::default() },
+            ExecuteMsg::Mint { recipient: other.clone(), amount: zero },
+            ExecuteMsg::Burn { amount: zero },
+            ExecuteMsg::TransferFrom { owner: other.clone(), recipient: other.clone(), amount: zero },
+            ExecuteMsg::SendFrom { owner: other.clone(), contract: other.clone(), amount: zero, msg: Binary::default() },
+            ExecuteMsg::BurnFrom { owner: other.clone(), amount: zero },
+            ExecuteMsg::ForceBurn { owner: other.clone(), amount: zero },
+            ExecuteMsg::Withdraw { amount: zero, destination_address: "0x0000000000000000000000000000000000000001".to_string() },
+            ExecuteMsg::IncreaseAllowance { spender: other.clone(), amount: zero, expires: None },
+            ExecuteMsg::DecreaseAllowance { spender: other.clone(), amount: zero, expires: None },
+        ];
+        for msg in msgs {
+            let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), msg.clone()).unwrap_err();
+            assert!(matches!(err, ContractError::ZeroAmount {}), "{:?} returned {}", msg, err);
+        }
+    }
+
+    #[test]
+    fn allowance_errors_distinguish_missing_and_insufficient() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let spender = deps.api.addr_make("spender");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let transfer_from = ExecuteMsg::TransferFrom {
+            owner: holder.to_string(),
+            recipient: spender.to_string(),
+            amount: Uint128::new(50),
+        };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&spender, &[]), transfer_from.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoAllowance {}));
+
+        let increase = ExecuteMsg::IncreaseAllowance { spender: spender.to_string(), amount: Uint128::new(30), expires: None };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase).unwrap();
+
+        let err = execute(deps.as_mut(), mock_env(), message_info(&spender, &[]), transfer_from).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientAllowance { available, required }
+                if available == Uint128::new(30) && required == Uint128::new(50)
+        ));
+
+        let burn_from = ExecuteMsg::BurnFrom { owner: holder.to_string(), amount: Uint128::new(30) };
+        execute(deps.as_mut(), mock_env(), message_info(&spender, &[]), burn_from).unwrap();
+    }
+
+    #[test]
+    fn marketing_fields_are_length_bounded() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let mut msg = mock_instantiate_msg(&admin, &holder);
+        msg.marketing = Some(InstantiateMarketingInfo {
+            project: Some("p".repeat(65)),
+            description: None,
+            marketing: None,
+            logo: None,
+        });
+        let err = instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::MarketingFieldTooLong { field, max: 64 } if field == "project"));
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let update = |project: usize, description: usize| ExecuteMsg::UpdateMarketing {
+            project: Some("p".repeat(project)),
+            description: Some("d".repeat(description)),
+            marketing: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), update(64, 257)).unwrap_err();
+        assert!(matches!(err, ContractError::MarketingFieldTooLong { field, max: 256 } if field == "description"));
+
+        execute(deps.as_mut(), mock_env(), message_info(&admin, &[]), update(64, 256)).unwrap();
+    }
+
+    #[test]
+    fn supply_reconciliation_tracks_mint_burn_and_withdraw() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = InstantiateMsg {
+            mint: Some(MinterResponse { minter: creator.to_string(), cap: None }),
+            ..mock_instantiate_msg(&admin, &holder)
+        };
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let reconciliation = |deps: Deps| -> SupplyReconciliationResponse {
+            from_json(query(deps, mock_env(), QueryMsg::SupplyReconciliation {}).unwrap()).unwrap()
+        };
+        let initial = reconciliation(deps.as_ref());
+        assert_eq!(initial.total_supply, Uint128::new(1_000));
+        assert_eq!(initial.total_minted, Uint128::new(1_000));
+
+        let mint = ExecuteMsg::Mint { recipient: holder.to_string(), amount: Uint128::new(500) };
+        execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), mint).unwrap();
+        let after_mint = reconciliation(deps.as_ref());
+        assert_eq!(after_mint.total_supply, Uint128::new(1_500));
+        assert_eq!(after_mint.total_minted, Uint128::new(1_500));
+
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(200), destination_address: "0xabc".to_string() };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), withdraw).unwrap();
+        let burn = ExecuteMsg::Burn { amount: Uint128::new(50) };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), burn).unwrap();
+
+        let after_withdraw = reconciliation(deps.as_ref());
+        assert_eq!(after_withdraw.total_withdrawn, Uint128::new(200));
+        assert_eq!(after_withdraw.total_burned, Uint128::new(50));
+        assert_eq!(after_withdraw.total_supply, Uint128::new(1_250));
+        assert_eq!(
+            after_withdraw.total_supply,
+            after_withdraw.total_minted - after_withdraw.total_burned - after_withdraw.total_withdrawn
+        );
+    }
+
+    #[test]
+    fn batch_withdraw_burns_total_and_numbers_each_withdrawal() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let batch = |amounts: &[u128]| ExecuteMsg::BatchWithdraw {
+            withdrawals: amounts
+                .iter()
+                .map(|amount| WithdrawalRequest { amount: Uint128::new(*amount), destination_address: "0xabc".to_string() })
+                .collect(),
+        };
+
+        // More than the balance in total: nothing is burned
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), batch(&[600, 500])).unwrap_err();
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), batch(&[])).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBatchSize { max: MAX_BATCH_WITHDRAWALS }));
+
+        let resp = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), batch(&[100, 250])).unwrap();
+        assert_eq!(resp.messages.len(), 2);
+        let nonces: Vec<&str> = resp
+            .events
+            .iter()
+            .map(|event| event.attributes.iter().find(|attr| attr.key == "nonce").unwrap().value.as_str())
+            .collect();
+        assert_eq!(nonces, vec!["1", "2"]);
+
+        let info: cw20::TokenInfoResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        assert_eq!(info.total_supply, Uint128::new(650));
+        let reconciliation: SupplyReconciliationResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::SupplyReconciliation {}).unwrap()).unwrap();
+        assert_eq!(reconciliation.total_withdrawn, Uint128::new(350));
+
+        // A later single withdrawal continues the sequence
+        let withdraw = ExecuteMsg::Withdraw { amount: Uint128::new(10), destination_address: "0xabc".to_string() };
+        let resp = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), withdraw).unwrap();
+        assert!(resp.attributes.iter().any(|attr| attr.key == "nonce" && attr.value == "3"));
+    }
+
+    #[test]
+    fn historical_holders_keep_emptied_accounts() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+        let other = deps.api.addr_make("other");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        // A failed transfer doesn't make the recipient a holder
+        let overdraw = ExecuteMsg::Transfer { recipient: other.to_string(), amount: Uint128::new(1_001) };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), overdraw).unwrap_err();
+        let count: HolderCountResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::HolderCount {}).unwrap()).unwrap();
+        assert_eq!(count.count, 1);
+
+        // holder hands over everything and stays on the list at a zero balance
+        let transfer = ExecuteMsg::Transfer { recipient: other.to_string(), amount: Uint128::new(1_000) };
+        execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), transfer).unwrap();
+        let balance: BalanceResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Balance { address: holder.to_string() }).unwrap()).unwrap();
+        assert!(balance.balance.is_zero());
+
+        let holders: HistoricalHoldersResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::HistoricalHolders { start_after: None, limit: None }).unwrap(),
+        )
+        .unwrap();
+        let mut expected = vec![holder.to_string(), other.to_string()];
+        expected.sort();
+        assert_eq!(holders.holders, expected);
+        assert_eq!(holders.next_start_after, None);
+
+        // Receiving again doesn't count twice
+        let back = ExecuteMsg::Transfer { recipient: holder.to_string(), amount: Uint128::new(10) };
+        execute(deps.as_mut(), mock_env(), message_info(&other, &[]), back).unwrap();
+        let count: HolderCountResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::HolderCount {}).unwrap()).unwrap();
+        assert_eq!(count.count, 2);
+    }
+
+    #[test]
+    fn all_accounts_pages_in_order_and_clamps_limit() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holders: Vec<Addr> = (0..35).map(|i| deps.api.addr_make(&format!("holder{i}"))).collect();
+
+        let msg = InstantiateMsg {
+            initial_balances: holders.iter().map(|h| Cw20Coin { address: h.to_string(), amount: Uint128::new(1) }).collect(),
+            ..mock_instantiate_msg(&admin, &holders[0])
+        };
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let page = |deps: Deps, start_after: Option<String>, limit: Option<u32>| -> AllAccountsResponse {
+            from_json(query(deps, mock_env(), QueryMsg::AllAccounts { start_after, limit }).unwrap()).unwrap()
+        };
+        let mut expected: Vec<String> = holders.iter().map(|h| h.to_string()).collect();
+        expected.sort();
+
+        // Following the cursor visits every account exactly once, in ascending order. With 5 per
+        // page the last page is full, and still reports no cursor.
+        for size in [4, 5] {
+            let mut visited = Vec::new();
+            let mut start_after = None;
+            loop {
+                let res = page(deps.as_ref(), start_after, Some(size));
+                visited.extend(res.accounts);
+                match res.next_start_after {
+                    Some(next) => start_after = Some(next),
+                    None => break,
+                }
+            }
+            assert_eq!(visited, expected);
+        }
+
+        assert_eq!(page(deps.as_ref(), None, None).accounts.len(), DEFAULT_LIMIT as usize);
+        assert_eq!(page(deps.as_ref(), None, Some(100)).accounts.len(), MAX_LIMIT as usize);
+    }
+
+    #[test]
+    fn all_allowances_pages_in_order_and_clamps_limit() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let admin = deps.api.addr_make("admin");
+        let holder = deps.api.addr_make("holder");
+
+        let msg = mock_instantiate_msg(&admin, &holder);
+        instantiate(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let mut spenders: Vec<String> = (0..35).map(|i| deps.api.addr_make(&format!("spender{i}")).to_string()).collect();
+        for spender in &spenders {
+            let increase = ExecuteMsg::IncreaseAllowance { spender: spender.clone(), amount: Uint128::new(5), expires: None };
+            execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), increase).unwrap();
+        }
+        spenders.sort();
+
+        let page = |deps: Deps, start_after: Option<String>, limit: Option<u32>| -> AllAllowancesResponse {
+            let msg = QueryMsg::AllAllowances { owner: holder.to_string(), start_after, limit };
+            from_json(query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        let first = page(deps.as_ref(), None, Some(2));
+        assert_eq!(first.next_start_after.as_ref(), Some(&first.allowances[1].spender));
+        let second = page(deps.as_ref(), first.next_start_after, Some(2));
+        let visited: Vec<String> = first.allowances.iter().chain(&second.allowances).map(|a| a.spender.clone()).collect();
+        assert_eq!(visited, spenders[..4]);
+        assert_eq!(first.allowances[0].allowance, Uint128::new(5));
+
+        let last = page(deps.as_ref(), Some(spenders[31].clone()), Some(3));
+        assert_eq!(last.allowances.len(), 3);
+        assert_eq!(last.next_start_after, None);
+
+        assert_eq!(page(deps.as_ref(), None, None).allowances.len(), DEFAULT_LIMIT as usize);
+        assert_eq!(page(deps.as_ref(), None, Some(100)).allowances.len(), MAX_LIMIT as usize);
+    }
+}
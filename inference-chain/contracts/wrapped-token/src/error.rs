@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,12 +15,27 @@ pub enum ContractError {
     #[error("No allowance for this account")]
     NoAllowance {},
 
+    #[error("Insufficient allowance: available {available}, required {required}")]
+    InsufficientAllowance { available: Uint128, required: Uint128 },
+
+    #[error("Allowance changed: expected {expected}, current {current}")]
+    AllowanceChanged { expected: Uint128, current: Uint128 },
+
     #[error("Minting cannot exceed the cap")]
     CannotExceedCap {},
 
     #[error("Duplicate initial balance addresses")]
     DuplicateInitialBalanceAddresses {},
 
+    #[error("Total supply mismatch: expected {expected}, initial balances sum to {actual}")]
+    TotalSupplyMismatch { expected: Uint128, actual: Uint128 },
+
+    #[error("Marketing {field} exceeds {max} characters")]
+    MarketingFieldTooLong { field: String, max: usize },
+
+    #[error("Memo exceeds {max} characters")]
+    MemoTooLong { max: usize },
+
     #[error("Logo binary data exceeds 5KB limit")]
     LogoTooBig {},
 
@@ -30,9 +45,21 @@ pub enum ContractError {
     #[error("Invalid PNG header")]
     InvalidPngHeader {},
 
+    #[error("Zero amount not allowed")]
+    ZeroAmount {},
+
     #[error("Insufficient funds: balance {balance}, required {required}")]
     InsufficientFunds { balance: u128, required: u128 },
 
+    #[error("Bridge withdrawals are paused")]
+    WithdrawalsPaused {},
+
+    #[error("Bridge withdrawals must be paused first")]
+    WithdrawalsNotPaused {},
+
+    #[error("Batch must contain between 1 and {max} withdrawals")]
+    InvalidBatchSize { max: usize },
+
     #[error("Bridge withdrawal not supported yet - query endpoint not ready")]
     WithdrawNotSupported {},
 
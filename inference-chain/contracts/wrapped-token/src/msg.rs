@@ -9,6 +9,8 @@ pub struct InstantiateMsg {
     pub contract_address: String,
     /// Initial balances to set for the wrapped token (usually empty)
     pub initial_balances: Vec<Cw20Coin>,
+    /// Optional expected total supply; instantiate fails unless it equals the sum of initial_balances
+    pub total_supply: Option<Uint128>,
     /// Optional minter, if unset only the instantiating address can mint
     pub mint: Option<MinterResponse>,
     /// Optional marketing data
@@ -60,7 +62,13 @@ pub enum ExecuteMsg {
         recipient: String,
         amount: Uint128,
     },
-    /// Burn tokens from the sender's balance
+    /// Transfer with a memo (at most 128 characters) emitted as an attribute for reconciliation
+    TransferWithMemo {
+        recipient: String,
+        amount: Uint128,
+        memo: String,
+    },
+    /// Burn tokens from the sender's balance. Open to any holder (CW20 standard).
     Burn { amount: Uint128 },
     /// Send tokens to a contract and trigger its receive hook
     Send {
@@ -68,18 +76,27 @@ pub enum ExecuteMsg {
         amount: Uint128,
         msg: Binary,
     },
-    /// Set allowance for spender
+    /// Add to spender's allowance; fails rather than overflowing Uint128. `expires: None` keeps
+    /// the current expiry (Never for a new allowance), `Some` replaces it.
     IncreaseAllowance {
         spender: String,
         amount: Uint128,
         expires: Option<Expiration>,
     },
-    /// Decrease allowance for spender
+    /// Subtract from spender's allowance, removing it at zero. Expiry as for IncreaseAllowance.
     DecreaseAllowance {
         spender: String,
         amount: Uint128,
         expires: Option<Expiration>,
     },
+    /// Set spender's allowance to `new` only if it currently equals `current`, so a spender
+    /// cannot front-run the change and spend both the old and new allowance
+    UpdateAllowance {
+        spender: String,
+        current: Uint128,
+        new: Uint128,
+        expires: Option<Expiration>,
+    },
     /// Transfer tokens from owner to recipient using allowance
     TransferFrom {
         owner: String,
@@ -94,8 +111,11 @@ pub enum ExecuteMsg {
         msg: Binary,
     },
     
-    /// Burn tokens from account using allowance
+    /// Burn tokens from account using allowance. Open to any spender holding an allowance (CW20 standard).
     BurnFrom { owner: String, amount: Uint128 },
+    /// Burn tokens from any account without an allowance. Restricted to the creator (inference
+    /// module) and admin (governance module); everyone else gets `OnlyAuthorizedCanBurn`.
+    ForceBurn { owner: String, amount: Uint128 },
     /// Only with "mintable" extension. Mint new tokens
     Mint { recipient: String, amount: Uint128 },
     /// Special bridge withdraw function that burns tokens and triggers bridge withdrawal
@@ -103,6 +123,17 @@ pub enum ExecuteMsg {
         amount: Uint128,
         destination_address: String, // Ethereum address to receive tokens
     },
+    /// Burn the sum of all amounts from the sender once, then request one bridge withdrawal per
+    /// entry, each with its own nonce. At most 50 entries.
+    BatchWithdraw { withdrawals: Vec<WithdrawalRequest> },
+    /// Admin: Pause or resume bridge withdrawals
+    SetWithdrawalsPaused { paused: bool },
+    /// Admin: Point the token at a new original contract, e.g. after it migrated on the external
+    /// chain. Requires withdrawals to be paused so none are routed with mismatched info.
+    UpdateBridgeInfo {
+        chain_id: String,
+        contract_address: String,
+    },
     UpdateMetadata {
         name: String,
         symbol: String,
@@ -118,6 +149,13 @@ pub enum ExecuteMsg {
     UploadLogo(Logo),
 }
 
+#[cw_serde]
+pub struct WithdrawalRequest {
+    pub amount: Uint128,
+    /// Ethereum address to receive tokens
+    pub destination_address: String,
+}
+
 #[cw_serde]
 pub enum Expiration {
     /// AtHeight will expire when `env.block.height` >= height
@@ -150,6 +188,9 @@ pub enum QueryMsg {
     /// Returns bridge information - chain ID and original contract address
     #[returns(BridgeInfoResponse)]
     BridgeInfo {},
+    /// Whether bridge withdrawals are paused; a cheap check matching the sale contracts' Paused
+    #[returns(bool)]
+    Paused {},
     /// Returns how much spender can use from owner account, 0 if unset.
     #[returns(AllowanceResponse)]
     Allowance { owner: String, spender: String },
@@ -166,6 +207,25 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Returns all non-zero balances with their amounts. Supports pagination; a page crossing many
+    /// emptied accounts may hold fewer than `limit` balances, so page until `next_start_after` is None.
+    #[returns(AllBalancesResponse)]
+    AllBalances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns every address that has ever received tokens, including those now at zero. Supports pagination.
+    #[returns(HistoricalHoldersResponse)]
+    HistoricalHolders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns how many addresses have ever received tokens
+    #[returns(HolderCountResponse)]
+    HolderCount {},
+    /// Returns token info, bridge info and marketing project/description in one call
+    #[returns(FullInfoResponse)]
+    FullInfo {},
     /// Returns metadata for the token (name, symbol, decimals, etc.)
     #[returns(MarketingInfoResponse)]
     MarketingInfo {},
@@ -176,6 +236,12 @@ pub enum QueryMsg {
     #[returns(MinterResponse)]
     Minter {},
 
+    /// Returns the contract name and version recorded by cw2
+    #[returns(cw2::ContractVersion)]
+    ContractVersion {},
+    /// Returns the supply next to the running minted, burned and withdrawn totals
+    #[returns(SupplyReconciliationResponse)]
+    SupplyReconciliation {},
     /// Test gRPC call to fetch approved tokens for trade; returns JSON-normalized data
     #[returns(ApprovedTokensForTradeJson)]
     TestApprovedTokens {},
@@ -200,6 +266,31 @@ pub struct BridgeInfoResponse {
     pub contract_address: String,
 }
 
+#[cw_serde]
+pub struct FullInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+    /// Chain ID where the original token exists
+    pub chain_id: String,
+    /// Original contract address on the external chain
+    pub contract_address: String,
+    pub project: Option<String>,
+    pub description: Option<String>,
+}
+
+#[cw_serde]
+pub struct SupplyReconciliationResponse {
+    pub total_supply: Uint128,
+    /// Initial balances plus everything minted since
+    pub total_minted: Uint128,
+    /// Burned by holders, allowance spenders or ForceBurn; excludes withdrawals
+    pub total_burned: Uint128,
+    /// Burned by bridge withdrawals
+    pub total_withdrawn: Uint128,
+}
+
 #[cw_serde]
 pub struct AllowanceResponse {
     pub allowance: Uint128,
@@ -216,11 +307,40 @@ pub struct AllowanceInfo {
 #[cw_serde]
 pub struct AllAllowancesResponse {
     pub allowances: Vec<AllowanceInfo>,
+    /// Pass as `start_after` to fetch the next page; None on the last page
+    pub next_start_after: Option<String>,
 }
 
 #[cw_serde]
 pub struct AllAccountsResponse {
     pub accounts: Vec<String>,
+    /// Pass as `start_after` to fetch the next page; None on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct AccountBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct AllBalancesResponse {
+    pub balances: Vec<AccountBalance>,
+    /// Pass as `start_after` to fetch the next page; None on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct HistoricalHoldersResponse {
+    pub holders: Vec<String>,
+    /// Pass as `start_after` to fetch the next page; None on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct HolderCountResponse {
+    pub count: u64,
 }
 
 #[cw_serde]
@@ -31,6 +31,18 @@ pub struct MarketingInfo {
 
 pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
 pub const BRIDGE_INFO: Item<BridgeInfo> = Item::new("bridge_info");
+/// Set by the admin to block bridge withdrawals, e.g. while bridge info is being changed
+pub const WITHDRAWALS_PAUSED: Item<bool> = Item::new("withdrawals_paused");
+// Running supply counters for reconciliation: total_supply == minted - burned - withdrawn.
+// Withdrawals burn too but are counted only in TOTAL_WITHDRAWN.
+pub const TOTAL_MINTED: Item<Uint128> = Item::new("total_minted");
+pub const TOTAL_BURNED: Item<Uint128> = Item::new("total_burned");
+pub const TOTAL_WITHDRAWN: Item<Uint128> = Item::new("total_withdrawn");
+/// Nonce of the last bridge withdrawal; the first withdrawal gets 1
+pub const WITHDRAWAL_NONCE: Item<u64> = Item::new("withdrawal_nonce");
+/// Every address that has ever received tokens, kept after its balance drops to zero
+pub const HISTORICAL_HOLDERS: Map<&Addr, bool> = Map::new("historical_holders");
+pub const HOLDER_COUNT: Item<u64> = Item::new("holder_count");
 pub const MARKETING_INFO: Item<MarketingInfo> = Item::new("marketing_info");
 pub const LOGO: Item<Logo> = Item::new("logo");
 pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
@@ -0,0 +1,189 @@
+//! Receive-hook delivery using cw-multi-test.
+//!
+//! The liquidity pool is built against cosmwasm-std 3.x and can't be loaded into this
+//! cosmwasm-std 2.x `App`, so a stand-in receiver that records every `Receive` it gets is used
+//! in its place. The pool side of the flow is covered by the liquidity-pool integration tests.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+
+use wrapped_token::msg::{BalanceResponse, Cw20Coin, Cw20ReceiveMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+
+/// The liquidity pool's purchase payload, forwarded untouched through the hook
+#[cw_serde]
+struct PurchaseTokenMsg {
+    allow_daily_partial: bool,
+}
+
+mod receiver {
+    use super::*;
+
+    const RECEIVED: Item<Vec<Cw20ReceiveMsg>> = Item::new("received");
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Receive(Cw20ReceiveMsg),
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        Received {},
+    }
+
+    fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+        RECEIVED.save(deps.storage, &vec![])?;
+        Ok(Response::new())
+    }
+
+    fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Receive(receive) => {
+                RECEIVED.update(deps.storage, |mut received| -> StdResult<_> {
+                    received.push(receive);
+                    Ok(received)
+                })?;
+                Ok(Response::new())
+            }
+        }
+    }
+
+    fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Received {} => to_json_binary(&RECEIVED.load(deps.storage)?),
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+}
+
+fn wrapped_token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        wrapped_token::contract::execute,
+        wrapped_token::contract::instantiate,
+        wrapped_token::contract::query,
+    ))
+}
+
+struct Suite {
+    app: App,
+    token: Addr,
+    receiver: Addr,
+    holder: Addr,
+}
+
+/// Instantiates the wrapped token with 1000 units held by `holder`, plus a receiver contract
+fn setup() -> Suite {
+    let mut app = App::default();
+    let creator = app.api().addr_make("creator");
+    let admin = app.api().addr_make("admin");
+    let holder = app.api().addr_make("holder");
+
+    let token_code = app.store_code(wrapped_token_contract());
+    let receiver_code = app.store_code(receiver::contract());
+
+    let token = app
+        .instantiate_contract(
+            token_code,
+            creator.clone(),
+            &InstantiateMsg {
+                chain_id: "ethereum".to_string(),
+                contract_address: "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+                initial_balances: vec![Cw20Coin { address: holder.to_string(), amount: Uint128::new(1_000) }],
+                total_supply: None,
+                mint: None,
+                marketing: None,
+                admin: Some(admin.to_string()),
+            },
+            &[],
+            "wrapped-usdt",
+            None,
+        )
+        .unwrap();
+    let receiver = app
+        .instantiate_contract(receiver_code, creator, &Empty {}, &[], "receiver", None)
+        .unwrap();
+
+    Suite { app, token, receiver, holder }
+}
+
+impl Suite {
+    fn balance(&self, address: &Addr) -> Uint128 {
+        let response: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.token, &QueryMsg::Balance { address: address.to_string() })
+            .unwrap();
+        response.balance
+    }
+
+    fn received(&self) -> Vec<Cw20ReceiveMsg> {
+        self.app.wrap().query_wasm_smart(&self.receiver, &receiver::QueryMsg::Received {}).unwrap()
+    }
+}
+
+#[test]
+fn send_invokes_receive_hook() {
+    let mut suite = setup();
+    let payload = to_json_binary(&PurchaseTokenMsg { allow_daily_partial: false }).unwrap();
+
+    suite
+        .app
+        .execute_contract(
+            suite.holder.clone(),
+            suite.token.clone(),
+            &ExecuteMsg::Send { contract: suite.receiver.to_string(), amount: Uint128::new(400), msg: payload.clone() },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(suite.balance(&suite.holder), Uint128::new(600));
+    assert_eq!(suite.balance(&suite.receiver), Uint128::new(400));
+    assert_eq!(
+        suite.received(),
+        vec![Cw20ReceiveMsg { sender: suite.holder.to_string(), amount: Uint128::new(400), msg: payload }]
+    );
+}
+
+#[test]
+fn send_from_invokes_receive_hook_with_spender_as_sender() {
+    let mut suite = setup();
+    let spender = suite.app.api().addr_make("spender");
+    let payload = to_json_binary(&PurchaseTokenMsg { allow_daily_partial: false }).unwrap();
+
+    suite
+        .app
+        .execute_contract(
+            suite.holder.clone(),
+            suite.token.clone(),
+            &ExecuteMsg::IncreaseAllowance { spender: spender.to_string(), amount: Uint128::new(250), expires: None },
+            &[],
+        )
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            spender.clone(),
+            suite.token.clone(),
+            &ExecuteMsg::SendFrom {
+                owner: suite.holder.to_string(),
+                contract: suite.receiver.to_string(),
+                amount: Uint128::new(250),
+                msg: payload.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(suite.balance(&suite.receiver), Uint128::new(250));
+    // Per the CW20 spec the hook names the spender, so the pool credits the purchase to them
+    assert_eq!(
+        suite.received(),
+        vec![Cw20ReceiveMsg { sender: spender.to_string(), amount: Uint128::new(250), msg: payload }]
+    );
+}